@@ -48,9 +48,9 @@ pub fn generate_support_bundle(
         }
     }
 
-    // Database schema (v2)
+    // Database schema (current version)
     zip.start_file("db-schema.sql", options)?;
-    zip.write_all(DB_SCHEMA_V2.as_bytes())?;
+    zip.write_all(format!("-- Schema version {}\n{}", crate::db::CURRENT_SCHEMA_VERSION, crate::db::SCHEMA_DDL).as_bytes())?;
 
     // Quarantine list (paths only, no content)
     let quarantine_dir = seslog_dir.join("quarantine");
@@ -123,68 +123,3 @@ fn mask_secrets(content: &str) -> String {
     }
     result
 }
-
-/// Schema v2 snapshot (includes item_id, depends_on, token_count, estimated_cost_usd, model).
-const DB_SCHEMA_V2: &str = r#"
--- Schema version 2
-
-CREATE TABLE projects (
-    id TEXT PRIMARY KEY,
-    name TEXT NOT NULL,
-    status TEXT NOT NULL DEFAULT 'active',
-    created_at TEXT NOT NULL,
-    archived_at TEXT,
-    description TEXT DEFAULT '',
-    total_sessions INTEGER DEFAULT 0,
-    total_duration_minutes INTEGER DEFAULT 0,
-    last_session_at TEXT,
-    last_machine TEXT,
-    progress_percent REAL DEFAULT 0.0,
-    meta_toml_path TEXT
-);
-
-CREATE TABLE sessions (
-    id TEXT PRIMARY KEY,
-    project_id TEXT NOT NULL,
-    machine TEXT NOT NULL,
-    started_at TEXT NOT NULL,
-    ended_at TEXT,
-    duration_minutes INTEGER,
-    end_reason TEXT,
-    summary TEXT DEFAULT '',
-    summary_source TEXT DEFAULT 'unknown',
-    next_steps TEXT DEFAULT '',
-    files_changed INTEGER DEFAULT 0,
-    recovered INTEGER DEFAULT 0,
-    redaction_count INTEGER DEFAULT 0,
-    source_path TEXT,
-    token_count INTEGER DEFAULT 0,
-    estimated_cost_usd REAL DEFAULT 0.0,
-    model TEXT DEFAULT '',
-    created_at TEXT DEFAULT (datetime('now')),
-    FOREIGN KEY (project_id) REFERENCES projects(id)
-);
-
-CREATE TABLE roadmap_items (
-    id INTEGER PRIMARY KEY AUTOINCREMENT,
-    project_id TEXT NOT NULL,
-    item_id TEXT DEFAULT '',
-    depends_on TEXT DEFAULT '',
-    phase TEXT,
-    item_text TEXT NOT NULL,
-    status TEXT NOT NULL,
-    sort_order INTEGER DEFAULT 0,
-    FOREIGN KEY (project_id) REFERENCES projects(id)
-);
-
-CREATE TABLE machines (
-    hostname TEXT PRIMARY KEY,
-    platform TEXT NOT NULL,
-    registered_at TEXT NOT NULL
-);
-
-CREATE TABLE processed_events (
-    event_file TEXT PRIMARY KEY,
-    processed_at TEXT DEFAULT (datetime('now'))
-);
-"#;
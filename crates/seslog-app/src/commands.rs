@@ -55,14 +55,137 @@ pub struct SessionResponse {
     pub started_at: String,
     pub ended_at: Option<String>,
     pub duration_minutes: Option<i64>,
+    pub active_minutes: Option<i64>,
     pub summary: String,
     pub next_steps: String,
     pub files_changed: i64,
     pub recovered: bool,
     pub transcript_highlights: Vec<String>,
     pub token_count: Option<i64>,
+    /// Per-category token totals; all `None` for sessions recorded before they were tracked.
+    pub input_tokens: Option<i64>,
+    pub output_tokens: Option<i64>,
+    pub cache_creation_tokens: Option<i64>,
+    pub cache_read_tokens: Option<i64>,
     pub estimated_cost_usd: Option<f64>,
     pub model: Option<String>,
+    pub insertions: Option<i64>,
+    pub deletions: Option<i64>,
+    /// `HEAD` at session start and end; both `None` for sessions recorded without a git range.
+    pub git_base: Option<String>,
+    pub git_head: Option<String>,
+    pub git_branch: Option<String>,
+    pub worktree: Option<String>,
+    pub upstream: Option<String>,
+    pub upstream_ahead: Option<i64>,
+    pub upstream_behind: Option<i64>,
+    pub changed_files: Vec<FileChangeResponse>,
+    /// Per-model totals, largest first. Empty for sessions recorded before per-turn usage.
+    pub model_usage: Vec<ModelUsageResponse>,
+    pub turn_usage: Vec<TurnUsageResponse>,
+    /// Calls per tool in the main conversation, in order of first use.
+    pub tool_stats: Vec<ToolStatsResponse>,
+    /// Files Claude read or modified, in order of first use.
+    pub touched_files: Vec<TouchedFileResponse>,
+    /// Subagent runs, in the order they started.
+    pub subagents: Vec<SubagentResponse>,
+}
+
+/// Tokens and cost of all turns on one model.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ModelUsageResponse {
+    pub model: String,
+    pub turns: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cost_usd: Option<f64>,
+}
+
+impl ModelUsageResponse {
+    pub fn total_tokens(&self) -> i64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+}
+
+/// Tokens and cost of one assistant turn, or of older turns folded together.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct TurnUsageResponse {
+    /// Index of the first turn the row stands for.
+    pub turn: i64,
+    /// Turns the row stands for; more than 1 for folded rows.
+    pub turns: i64,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cost_usd: Option<f64>,
+    /// The subagent that made the turn; `None` for the main conversation.
+    pub agent_id: Option<String>,
+}
+
+/// Work delegated to a subagent, with its share of the session's tokens and cost.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SubagentResponse {
+    pub agent_id: String,
+    pub agent_type: Option<String>,
+    pub description: String,
+    pub summaries: Vec<String>,
+    pub model: Option<String>,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub turns: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cost_usd: Option<f64>,
+    pub tool_stats: Vec<ToolStatsResponse>,
+}
+
+impl SubagentResponse {
+    pub fn total_tokens(&self) -> i64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ToolStatsResponse {
+    pub tool: String,
+    pub calls: i64,
+    /// Calls whose result was flagged as an error.
+    pub errors: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct TouchedFileResponse {
+    pub path: String,
+    pub reads: i64,
+    pub edits: i64,
+    pub writes: i64,
+}
+
+/// A file Claude keeps coming back to, summed over a project's sessions.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct HotspotFileResponse {
+    pub path: String,
+    /// Sessions that touched the file.
+    pub sessions: i64,
+    pub reads: i64,
+    /// Edit and Write calls together.
+    pub modifications: i64,
+    pub last_touched_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct FileChangeResponse {
+    pub path: String,
+    /// `added`, `modified`, `deleted` or `renamed`.
+    pub status: String,
+    pub insertions: i64,
+    pub deletions: i64,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -72,6 +195,13 @@ pub struct RoadmapItemResponse {
     pub status: String,
     pub item_id: Option<String>,
     pub depends_on: Vec<String>,
+    /// Pending with every dependency done.
+    pub ready: bool,
+    /// Ids of dependencies that are not done yet.
+    pub waiting_on: Vec<String>,
+    /// Longest dependency chain leading to this item (0 without dependencies).
+    pub depth: usize,
+    pub on_critical_path: bool,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -79,6 +209,38 @@ pub struct RoadmapResponse {
     pub items: Vec<RoadmapItemResponse>,
     pub progress_percent: f64,
     pub warnings: Vec<String>,
+    /// Unfinished items on the longest dependency chain.
+    pub critical_path_length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DecisionResponse {
+    pub date: Option<String>,
+    pub title: String,
+    pub description: String,
+    /// `manual` (from `seslog decision`) or `transcript` (extracted at session end).
+    pub source: String,
+    pub session_id: Option<String>,
+}
+
+/// Which session duration the overview aggregates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeMetric {
+    /// Start-to-end duration, including idle gaps.
+    #[default]
+    WallClock,
+    /// Idle-aware active time. Sessions without transcript timestamps fall back to wall-clock.
+    Active,
+}
+
+impl TimeMetric {
+    fn minutes_expr(self) -> &'static str {
+        match self {
+            TimeMetric::WallClock => "s.duration_minutes",
+            TimeMetric::Active => "COALESCE(s.active_minutes, s.duration_minutes)",
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -93,12 +255,41 @@ pub struct OverviewRow {
     pub total_cost: f64,
 }
 
+/// Delimiters wrapped around matched terms in [`SearchResult::snippet`].
+pub const SNIPPET_MATCH_START: char = '\u{2}';
+pub const SNIPPET_MATCH_END: char = '\u{3}';
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SearchResult {
+    pub project_id: String,
+    pub project_name: String,
+    /// `None` for roadmap items.
+    pub session_id: Option<String>,
+    /// One of `summary`, `next_steps`, `highlight`, `roadmap`.
+    pub kind: String,
+    /// Matched text excerpt, with hits wrapped in `SNIPPET_MATCH_START`/`SNIPPET_MATCH_END`.
+    pub snippet: String,
+    pub started_at: Option<String>,
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct ProjectDetailResponse {
     #[serde(flatten)]
     pub summary: ProjectSummaryResponse,
     pub roadmap: RoadmapResponse,
     pub recent_sessions: Vec<SessionResponse>,
+    pub decisions: Vec<DecisionResponse>,
+    /// Branches the project's sessions ran on, most recently used first.
+    pub branches: Vec<BranchSummary>,
+    /// Files modified most often by Claude, see [`HOTSPOT_LIMIT`].
+    pub hotspots: Vec<HotspotFileResponse>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct BranchSummary {
+    pub branch: String,
+    pub session_count: i64,
+    pub last_session_at: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
@@ -141,9 +332,12 @@ pub fn get_projects_inner(pool: &DbConnector) -> anyhow::Result<Vec<ProjectSumma
     Ok(projects)
 }
 
+/// Project page data. With `branch`, the recent sessions are limited to that branch;
+/// the summary and branch list always cover the whole project.
 pub fn get_project_detail_inner(
     pool: &DbConnector,
     project_id: String,
+    branch: Option<&str>,
 ) -> anyhow::Result<ProjectDetailResponse> {
     let conn = pool.get()?;
 
@@ -183,40 +377,107 @@ pub fn get_project_detail_inner(
     let roadmap = get_roadmap_with_conn(&conn, project_id.clone())?;
 
     // 3. Recent sessions (last 20, reuse same connection)
-    let recent_sessions = get_sessions_with_conn(&conn, project_id, 20)?;
+    let recent_sessions = get_sessions_with_conn(&conn, project_id.clone(), branch, 20)?;
+
+    // 4. Decision history, newest first
+    let decisions = get_decisions_with_conn(&conn, &project_id)?;
+
+    // 5. Branches with session counts
+    let branches = get_branches_with_conn(&conn, &project_id)?;
+
+    // 6. Files Claude touched most, on the selected branch
+    let hotspots = get_hotspots_with_conn(&conn, &project_id, branch, HOTSPOT_LIMIT)?;
 
     Ok(ProjectDetailResponse {
         summary,
         roadmap,
         recent_sessions,
+        decisions,
+        branches,
+        hotspots,
     })
 }
 
+/// How many hotspot files the project page lists.
+pub const HOTSPOT_LIMIT: u32 = 15;
+
+/// Files touched by Claude across a project's sessions: the most modified first, then the
+/// ones touched in the most sessions. Files that were only ever read rank last.
+fn get_hotspots_with_conn(
+    conn: &rusqlite::Connection,
+    project_id: &str,
+    branch: Option<&str>,
+    limit: u32,
+) -> anyhow::Result<Vec<HotspotFileResponse>> {
+    let mut stmt = conn.prepare(
+        "SELECT f.path, COUNT(DISTINCT f.session_id), SUM(f.reads), SUM(f.edits + f.writes),
+                MAX(s.started_at)
+         FROM session_touched_files f
+         JOIN sessions s ON s.id = f.session_id
+         WHERE s.project_id = ?1 AND (?3 IS NULL OR s.git_branch = ?3)
+         GROUP BY f.path
+         ORDER BY SUM(f.edits + f.writes) DESC, COUNT(DISTINCT f.session_id) DESC, SUM(f.reads) DESC, f.path
+         LIMIT ?2",
+    )?;
+    let hotspots = stmt.query_map(params![project_id, limit, branch], |row| {
+        Ok(HotspotFileResponse {
+            path: row.get(0)?,
+            sessions: row.get(1)?,
+            reads: row.get(2)?,
+            modifications: row.get(3)?,
+            last_touched_at: row.get(4)?,
+        })
+    })?.collect::<Result<Vec<_>, _>>()?;
+    Ok(hotspots)
+}
+
+fn get_branches_with_conn(conn: &rusqlite::Connection, project_id: &str) -> anyhow::Result<Vec<BranchSummary>> {
+    let mut stmt = conn.prepare(
+        "SELECT git_branch, COUNT(*), MAX(started_at) AS last_session_at
+         FROM sessions
+         WHERE project_id = ?1 AND git_branch IS NOT NULL
+         GROUP BY git_branch
+         ORDER BY last_session_at DESC",
+    )?;
+    let branches = stmt.query_map(params![project_id], |row| {
+        Ok(BranchSummary {
+            branch: row.get(0)?,
+            session_count: row.get(1)?,
+            last_session_at: row.get(2)?,
+        })
+    })?.collect::<Result<Vec<_>, _>>()?;
+    Ok(branches)
+}
+
 pub fn get_sessions_inner(
     pool: &DbConnector,
     project_id: String,
     limit: u32,
 ) -> anyhow::Result<Vec<SessionResponse>> {
     let conn = pool.get()?;
-    get_sessions_with_conn(&conn, project_id, limit)
+    get_sessions_with_conn(&conn, project_id, None, limit)
 }
 
 fn get_sessions_with_conn(
     conn: &rusqlite::Connection,
     project_id: String,
+    branch: Option<&str>,
     limit: u32,
 ) -> anyhow::Result<Vec<SessionResponse>> {
     let mut stmt = conn.prepare(
         "SELECT id, project_id, machine, started_at, ended_at,
                 duration_minutes, summary, next_steps, files_changed, recovered,
-                token_count, estimated_cost_usd, model
+                token_count, estimated_cost_usd, model, active_minutes,
+                insertions, deletions, git_base, git_head, git_branch,
+                worktree, upstream, upstream_ahead, upstream_behind,
+                input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens
          FROM sessions
-         WHERE project_id = ?1
+         WHERE project_id = ?1 AND (?3 IS NULL OR git_branch = ?3)
          ORDER BY started_at DESC
          LIMIT ?2",
     )?;
 
-    let rows = stmt.query_map(params![project_id, limit], |row| {
+    let rows = stmt.query_map(params![project_id, limit, branch], |row| {
         let files_changed: i64 = row.get::<_, Option<i64>>(8)?.unwrap_or(0);
         let recovered_int: i32 = row.get(9)?;
 
@@ -227,14 +488,34 @@ fn get_sessions_with_conn(
             started_at: row.get(3)?,
             ended_at: row.get(4)?,
             duration_minutes: row.get(5)?,
+            active_minutes: row.get(13)?,
             summary: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
             next_steps: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
             files_changed,
             recovered: recovered_int != 0,
             transcript_highlights: Vec::new(), // filled below
             token_count: row.get(10)?,
+            input_tokens: row.get(23)?,
+            output_tokens: row.get(24)?,
+            cache_creation_tokens: row.get(25)?,
+            cache_read_tokens: row.get(26)?,
             estimated_cost_usd: row.get(11)?,
             model: row.get(12)?,
+            insertions: row.get(14)?,
+            deletions: row.get(15)?,
+            git_base: row.get(16)?,
+            git_head: row.get(17)?,
+            git_branch: row.get(18)?,
+            worktree: row.get(19)?,
+            upstream: row.get(20)?,
+            upstream_ahead: row.get(21)?,
+            upstream_behind: row.get(22)?,
+            changed_files: Vec::new(), // filled by get_session_by_id
+            model_usage: Vec::new(),
+            turn_usage: Vec::new(),
+            tool_stats: Vec::new(),
+            touched_files: Vec::new(),
+            subagents: Vec::new(),
         })
     })?;
 
@@ -257,6 +538,30 @@ fn get_sessions_with_conn(
     Ok(sessions)
 }
 
+fn get_decisions_with_conn(
+    conn: &rusqlite::Connection,
+    project_id: &str,
+) -> anyhow::Result<Vec<DecisionResponse>> {
+    let mut stmt = conn.prepare(
+        "SELECT date, title, description, source, session_id
+         FROM decisions
+         WHERE project_id = ?1
+         ORDER BY date DESC, sort_order DESC",
+    )?;
+    let decisions = stmt
+        .query_map(params![project_id], |row| {
+            Ok(DecisionResponse {
+                date: row.get(0)?,
+                title: row.get(1)?,
+                description: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                source: row.get(3)?,
+                session_id: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(decisions)
+}
+
 pub fn get_roadmap_inner(
     pool: &DbConnector,
     project_id: String,
@@ -277,7 +582,7 @@ fn get_roadmap_with_conn(
          ORDER BY sort_order",
     )?;
 
-    let items: Vec<RoadmapItemResponse> = stmt
+    let mut items: Vec<RoadmapItemResponse> = stmt
         .query_map(params![project_id], |row| {
             let depends_str: Option<String> = row.get(4)?;
             let depends_on: Vec<String> = depends_str
@@ -289,26 +594,33 @@ fn get_roadmap_with_conn(
                 status: row.get(2)?,
                 item_id: row.get(3)?,
                 depends_on,
+                ready: false,
+                waiting_on: Vec::new(),
+                depth: 0,
+                on_critical_path: false,
             })
         })?
         .filter_map(|r| r.ok())
         .collect();
 
-    // Validate dependencies: check that all depends_on references exist as item_ids
-    let known_ids: std::collections::HashSet<&str> = items.iter()
-        .filter_map(|i| i.item_id.as_deref())
+    // Dependency checks and analysis run on the cached items, in file order.
+    let core_items: Vec<seslog_core::roadmap::RoadmapItem> = items.iter().enumerate()
+        .map(|(i, item)| seslog_core::roadmap::RoadmapItem {
+            status: item.status.parse().unwrap_or(seslog_core::roadmap::ItemStatus::Pending),
+            text: item.item_text.clone(),
+            phase: item.phase.clone(),
+            line_number: i + 1,
+            id: item.item_id.clone(),
+            depends_on: item.depends_on.clone(),
+        })
         .collect();
-    let mut warnings = Vec::new();
-    for item in &items {
-        for dep in &item.depends_on {
-            if !known_ids.contains(dep.as_str()) {
-                let label = item.item_id.as_deref().unwrap_or(&item.item_text);
-                warnings.push(format!(
-                    "Item '{}' depends on '{}' which does not exist",
-                    label, dep
-                ));
-            }
-        }
+    let warnings = seslog_core::roadmap::validate_dependencies(&core_items);
+    let analysis = seslog_core::roadmap::analyze_dependencies(&core_items);
+    for (i, item) in items.iter_mut().enumerate() {
+        item.ready = analysis.ready.contains(&i);
+        item.waiting_on = analysis.waiting_on[i].clone();
+        item.depth = analysis.depth.get(i).copied().unwrap_or(0);
+        item.on_critical_path = analysis.critical_path.contains(&i);
     }
 
     // Progress percent from projects table
@@ -324,9 +636,46 @@ fn get_roadmap_with_conn(
         items,
         progress_percent: progress,
         warnings,
+        critical_path_length: analysis.critical_path.len(),
     })
 }
 
+pub fn edit_roadmap_inner(
+    pool: &DbConnector,
+    project_id: &str,
+    edit: &seslog_core::roadmap_edit::RoadmapEdit,
+) -> anyhow::Result<RoadmapResponse> {
+    let conn = pool.get()?;
+    let seslog_dir = seslog_core::storage::seslog_dir()?;
+    edit_roadmap_with_conn(&conn, &seslog_dir, project_id, edit)
+}
+
+/// Apply `edit` to the project's roadmap.md (next to its meta.toml), then re-import
+/// the roadmap so the returned items reflect the file.
+fn edit_roadmap_with_conn(
+    conn: &rusqlite::Connection,
+    data_dir: &Path,
+    project_id: &str,
+    edit: &seslog_core::roadmap_edit::RoadmapEdit,
+) -> anyhow::Result<RoadmapResponse> {
+    let meta_path: Option<String> = conn
+        .query_row(
+            "SELECT meta_toml_path FROM projects WHERE id = ?1",
+            params![project_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    let meta_path = meta_path.ok_or_else(|| anyhow::anyhow!("No project directory known for {}", project_id))?;
+    let roadmap_path = Path::new(&meta_path)
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Invalid meta.toml path for {}", project_id))?
+        .join("roadmap.md");
+    seslog_core::roadmap_edit::edit_file(&roadmap_path, edit)?;
+    crate::reconcile::incremental_update(conn, &roadmap_path, data_dir)?;
+    get_roadmap_with_conn(conn, project_id.to_string())
+}
+
 pub fn rebuild_cache_inner(pool: &DbConnector) -> anyhow::Result<crate::reconcile::ReconcileReport> {
     let conn = pool.get()?;
     let seslog_dir = seslog_core::storage::seslog_dir()?;
@@ -342,7 +691,10 @@ pub fn get_session_by_id(
     let mut stmt = conn.prepare(
         "SELECT id, project_id, machine, started_at, ended_at,
                 duration_minutes, summary, next_steps, files_changed, recovered,
-                token_count, estimated_cost_usd, model
+                token_count, estimated_cost_usd, model, active_minutes,
+                insertions, deletions, git_base, git_head, git_branch,
+                worktree, upstream, upstream_ahead, upstream_behind,
+                input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens
          FROM sessions
          WHERE project_id = ?1 AND id = ?2",
     )?;
@@ -358,14 +710,34 @@ pub fn get_session_by_id(
             started_at: row.get(3)?,
             ended_at: row.get(4)?,
             duration_minutes: row.get(5)?,
+            active_minutes: row.get(13)?,
             summary: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
             next_steps: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
             files_changed,
             recovered: recovered_int != 0,
             transcript_highlights: Vec::new(),
             token_count: row.get(10)?,
+            input_tokens: row.get(23)?,
+            output_tokens: row.get(24)?,
+            cache_creation_tokens: row.get(25)?,
+            cache_read_tokens: row.get(26)?,
             estimated_cost_usd: row.get(11)?,
             model: row.get(12)?,
+            insertions: row.get(14)?,
+            deletions: row.get(15)?,
+            git_base: row.get(16)?,
+            git_head: row.get(17)?,
+            git_branch: row.get(18)?,
+            worktree: row.get(19)?,
+            upstream: row.get(20)?,
+            upstream_ahead: row.get(21)?,
+            upstream_behind: row.get(22)?,
+            changed_files: Vec::new(),
+            model_usage: Vec::new(),
+            turn_usage: Vec::new(),
+            tool_stats: Vec::new(),
+            touched_files: Vec::new(),
+            subagents: Vec::new(),
         })
     }).optional()?;
 
@@ -381,38 +753,183 @@ pub fn get_session_by_id(
             .filter_map(|r| r.ok())
             .collect();
         s.transcript_highlights = highlights;
+
+        let mut files_stmt = conn.prepare(
+            "SELECT path, status, insertions, deletions FROM session_files
+             WHERE session_id = ?1
+             ORDER BY sort_order",
+        )?;
+        s.changed_files = files_stmt
+            .query_map(rusqlite::params![s.id], |row| {
+                Ok(FileChangeResponse {
+                    path: row.get(0)?,
+                    status: row.get(1)?,
+                    insertions: row.get(2)?,
+                    deletions: row.get(3)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        let mut tools_stmt = conn.prepare(
+            "SELECT tool, calls, errors, agent_id FROM session_tools
+             WHERE session_id = ?1
+             ORDER BY sort_order",
+        )?;
+        let tools: Vec<(Option<String>, ToolStatsResponse)> = tools_stmt
+            .query_map(rusqlite::params![s.id], |row| {
+                Ok((row.get(3)?, ToolStatsResponse { tool: row.get(0)?, calls: row.get(1)?, errors: row.get(2)? }))
+            })?
+            .collect::<Result<_, _>>()?;
+
+        let mut subagents_stmt = conn.prepare(
+            "SELECT agent_id, agent_type, description, summaries, model, started_at, ended_at,
+                    turns, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens, cost_usd
+             FROM session_subagents
+             WHERE session_id = ?1
+             ORDER BY sort_order",
+        )?;
+        s.subagents = subagents_stmt
+            .query_map(rusqlite::params![s.id], |row| {
+                let summaries: String = row.get(3)?;
+                Ok(SubagentResponse {
+                    agent_id: row.get(0)?,
+                    agent_type: row.get(1)?,
+                    description: row.get(2)?,
+                    summaries: summaries.lines().map(String::from).collect(),
+                    model: row.get(4)?,
+                    started_at: row.get(5)?,
+                    ended_at: row.get(6)?,
+                    turns: row.get(7)?,
+                    input_tokens: row.get(8)?,
+                    output_tokens: row.get(9)?,
+                    cache_creation_tokens: row.get(10)?,
+                    cache_read_tokens: row.get(11)?,
+                    cost_usd: row.get(12)?,
+                    tool_stats: Vec::new(),
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        for (agent_id, tool) in tools {
+            match agent_id {
+                None => s.tool_stats.push(tool),
+                Some(id) => {
+                    if let Some(subagent) = s.subagents.iter_mut().find(|a| a.agent_id == id) {
+                        subagent.tool_stats.push(tool);
+                    }
+                }
+            }
+        }
+
+        let mut touched_stmt = conn.prepare(
+            "SELECT path, reads, edits, writes FROM session_touched_files
+             WHERE session_id = ?1
+             ORDER BY sort_order",
+        )?;
+        s.touched_files = touched_stmt
+            .query_map(rusqlite::params![s.id], |row| {
+                Ok(TouchedFileResponse {
+                    path: row.get(0)?,
+                    reads: row.get(1)?,
+                    edits: row.get(2)?,
+                    writes: row.get(3)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        s.model_usage = model_usage_with_conn(&conn, "WHERE u.session_id = ?1", rusqlite::params![s.id])?;
+        let mut turns_stmt = conn.prepare(
+            "SELECT turn, model, input_tokens, output_tokens, cache_creation_tokens,
+                    cache_read_tokens, cost_usd, agent_id, turns
+             FROM session_usage
+             WHERE session_id = ?1
+             ORDER BY turn",
+        )?;
+        s.turn_usage = turns_stmt
+            .query_map(rusqlite::params![s.id], |row| {
+                Ok(TurnUsageResponse {
+                    turn: row.get(0)?,
+                    model: row.get(1)?,
+                    input_tokens: row.get(2)?,
+                    output_tokens: row.get(3)?,
+                    cache_creation_tokens: row.get(4)?,
+                    cache_read_tokens: row.get(5)?,
+                    cost_usd: row.get(6)?,
+                    agent_id: row.get(7)?,
+                    turns: row.get(8)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
     }
 
     Ok(session)
 }
 
-pub fn get_overview_inner(pool: &DbConnector, include_archived: bool) -> anyhow::Result<Vec<OverviewRow>> {
+/// Per-model totals over the `session_usage` rows selected by `filter`, largest first.
+/// `filter` may refer to `u` (session_usage), `s` (sessions) and `p` (projects).
+fn model_usage_with_conn(
+    conn: &rusqlite::Connection,
+    filter: &str,
+    params: impl rusqlite::Params,
+) -> anyhow::Result<Vec<ModelUsageResponse>> {
+    let sql = format!(
+        "SELECT u.model, SUM(u.turns), SUM(u.input_tokens), SUM(u.output_tokens),
+                SUM(u.cache_creation_tokens), SUM(u.cache_read_tokens), SUM(u.cost_usd)
+         FROM session_usage u
+         JOIN sessions s ON s.id = u.session_id
+         JOIN projects p ON p.id = s.project_id
+         {}
+         GROUP BY u.model
+         ORDER BY SUM(u.input_tokens + u.output_tokens + u.cache_creation_tokens + u.cache_read_tokens) DESC",
+        filter,
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt
+        .query_map(params, |row| {
+            Ok(ModelUsageResponse {
+                model: row.get(0)?,
+                turns: row.get(1)?,
+                input_tokens: row.get(2)?,
+                output_tokens: row.get(3)?,
+                cache_creation_tokens: row.get(4)?,
+                cache_read_tokens: row.get(5)?,
+                cost_usd: row.get(6)?,
+            })
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(rows)
+}
+
+/// Token usage per model across all projects shown on the overview.
+pub fn get_model_usage_inner(pool: &DbConnector, include_archived: bool) -> anyhow::Result<Vec<ModelUsageResponse>> {
     let conn = pool.get()?;
+    let filter = if include_archived { "" } else { "WHERE p.status = 'active'" };
+    model_usage_with_conn(&conn, filter, [])
+}
 
-    let query = if include_archived {
-        "SELECT p.id, p.name, p.status, p.progress_percent,
-                MAX(s.started_at) AS last_session_at,
-                COUNT(s.id) AS session_count,
-                COALESCE(SUM(s.duration_minutes), 0) AS total_minutes,
-                COALESCE(SUM(s.estimated_cost_usd), 0.0) AS total_cost
-         FROM projects p
-         LEFT JOIN sessions s ON s.project_id = p.id
-         GROUP BY p.id
-         ORDER BY last_session_at DESC NULLS LAST"
-    } else {
+pub fn get_overview_inner(
+    pool: &DbConnector,
+    include_archived: bool,
+    metric: TimeMetric,
+) -> anyhow::Result<Vec<OverviewRow>> {
+    let conn = pool.get()?;
+
+    let status_filter = if include_archived { "" } else { "WHERE p.status = 'active'" };
+    let query = format!(
         "SELECT p.id, p.name, p.status, p.progress_percent,
                 MAX(s.started_at) AS last_session_at,
                 COUNT(s.id) AS session_count,
-                COALESCE(SUM(s.duration_minutes), 0) AS total_minutes,
+                COALESCE(SUM({}), 0) AS total_minutes,
                 COALESCE(SUM(s.estimated_cost_usd), 0.0) AS total_cost
          FROM projects p
          LEFT JOIN sessions s ON s.project_id = p.id
-         WHERE p.status = 'active'
+         {}
          GROUP BY p.id
-         ORDER BY last_session_at DESC NULLS LAST"
-    };
+         ORDER BY last_session_at DESC NULLS LAST",
+        metric.minutes_expr(),
+        status_filter,
+    );
 
-    let mut stmt = conn.prepare(query)?;
+    let mut stmt = conn.prepare(&query)?;
     let overview = stmt.query_map([], |row| {
         Ok(OverviewRow {
             id: row.get(0)?,
@@ -429,6 +946,62 @@ pub fn get_overview_inner(pool: &DbConnector, include_archived: bool) -> anyhow:
     Ok(overview)
 }
 
+/// Turn free-form user input into an FTS5 query: every word must match, as a prefix.
+/// Quoting each word keeps punctuation like `-` or `:` from being parsed as syntax.
+fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|t| format!("\"{}\"*", t.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Ranked full-text search over sessions and roadmap items.
+///
+/// A session appears at most once, represented by its best-ranked field.
+pub fn search_inner(pool: &DbConnector, query: &str, limit: u32) -> anyhow::Result<Vec<SearchResult>> {
+    let fts = match fts_query(query) {
+        Some(q) => q,
+        None => return Ok(Vec::new()),
+    };
+    let conn = pool.get()?;
+    // Each session keeps only its best-ranked row in SQL, so LIMIT counts distinct results.
+    // (snippet() can't run under a window function, hence the rowid subquery.)
+    let sql = format!(
+        "SELECT search_index.project_id, COALESCE(p.name, search_index.project_id),
+                search_index.session_id, search_index.kind,
+                snippet(search_index, 0, '{}', '{}', '\u{2026}', 12),
+                s.started_at
+         FROM search_index
+         LEFT JOIN projects p ON p.id = search_index.project_id
+         LEFT JOIN sessions s ON s.id = search_index.session_id
+         WHERE search_index MATCH ?1
+           AND search_index.rowid IN (
+               SELECT best FROM (
+                   SELECT rowid AS best, MIN(rank)
+                   FROM search_index
+                   WHERE search_index MATCH ?1
+                   GROUP BY COALESCE(session_id, 'row:' || rowid)
+               )
+           )
+         ORDER BY rank
+         LIMIT ?2",
+        SNIPPET_MATCH_START, SNIPPET_MATCH_END,
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params![fts, limit], |row| {
+        Ok(SearchResult {
+            project_id: row.get(0)?,
+            project_name: row.get(1)?,
+            session_id: row.get(2)?,
+            kind: row.get(3)?,
+            snippet: row.get(4)?,
+            started_at: row.get(5)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
 pub fn open_in_editor_inner(project_id: &str) -> anyhow::Result<String> {
     let data_dir = seslog_core::storage::seslog_dir()?;
     let meta_path = data_dir.join("projects").join(project_id).join("meta.toml");
@@ -472,9 +1045,8 @@ pub fn update_settings_inner(config: serde_json::Value) -> anyhow::Result<()> {
     let mut app_config = seslog_core::config::load_config(&config_path)?;
 
     if let Some(v) = config.get("privacy_mode") {
-        if let Ok(mode) = serde_json::from_value::<seslog_core::config::PrivacyMode>(v.clone()) {
-            app_config.privacy_mode = mode;
-        }
+        app_config.privacy_mode = serde_json::from_value::<seslog_core::config::PrivacyMode>(v.clone())
+            .map_err(|_| anyhow::anyhow!("Unknown privacy mode: {}", v))?;
     }
     if let Some(v) = config.get("checkpoint_interval_minutes").and_then(|v| v.as_u64()) {
         app_config.checkpoint_interval_minutes = v as u32;
@@ -482,6 +1054,9 @@ pub fn update_settings_inner(config: serde_json::Value) -> anyhow::Result<()> {
     if let Some(v) = config.get("sanitize_secrets").and_then(|v| v.as_bool()) {
         app_config.sanitize_secrets = v;
     }
+    if let Some(v) = config.get("idle_threshold_minutes").and_then(|v| v.as_u64()) {
+        app_config.idle_threshold_minutes = v as u32;
+    }
 
     seslog_core::config::write_config(&config_path, &app_config)
 }
@@ -540,6 +1115,69 @@ mod tests {
         assert_eq!(sessions[0].summary, "Did stuff");
     }
 
+    #[test]
+    fn test_get_session_by_id_includes_changed_files() {
+        let (_tmp, pool) = setup();
+        let conn = pool.get().unwrap();
+        conn.execute_batch("
+            UPDATE sessions SET insertions = 12, deletions = 3, git_base = 'aaaaaaa', git_head = 'bbbbbbb'
+                WHERE id = 'ses_1';
+            INSERT INTO session_files (session_id, path, status, insertions, deletions, sort_order)
+                VALUES ('ses_1', 'src/b.rs', 'added', 10, 0, 1), ('ses_1', 'src/a.rs', 'modified', 2, 3, 0);
+        ").unwrap();
+
+        let session = get_session_by_id(&pool, "proj_1", "ses_1").unwrap().unwrap();
+        assert_eq!((session.insertions, session.deletions), (Some(12), Some(3)));
+        assert_eq!(session.git_base.as_deref(), Some("aaaaaaa"));
+        let paths: Vec<&str> = session.changed_files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/a.rs", "src/b.rs"]);
+        assert_eq!(session.changed_files[1].status, "added");
+    }
+
+    #[test]
+    fn test_project_detail_filters_by_branch() {
+        let (_tmp, pool) = setup();
+        let conn = pool.get().unwrap();
+        conn.execute_batch("
+            UPDATE sessions SET git_branch = 'main' WHERE id = 'ses_1';
+            INSERT INTO sessions (id, project_id, machine, started_at, git_branch, worktree)
+                VALUES ('ses_2', 'proj_1', 'macbook', '2026-01-02T10:00:00Z', 'feature/export', '/src/export'),
+                       ('ses_3', 'proj_1', 'macbook', '2026-01-03T10:00:00Z', 'feature/export', '/src/export'),
+                       ('ses_4', 'proj_1', 'macbook', '2026-01-04T10:00:00Z', NULL, NULL);
+        ").unwrap();
+
+        let all = get_project_detail_inner(&pool, "proj_1".into(), None).unwrap();
+        assert_eq!(all.recent_sessions.len(), 4);
+        assert_eq!(all.branches, vec![
+            BranchSummary { branch: "feature/export".into(), session_count: 2, last_session_at: Some("2026-01-03T10:00:00Z".into()) },
+            BranchSummary { branch: "main".into(), session_count: 1, last_session_at: Some("2026-01-01T10:00:00Z".into()) },
+        ]);
+
+        let feature = get_project_detail_inner(&pool, "proj_1".into(), Some("feature/export")).unwrap();
+        let ids: Vec<&str> = feature.recent_sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["ses_3", "ses_2"]);
+        assert_eq!(feature.recent_sessions[0].worktree.as_deref(), Some("/src/export"));
+        assert_eq!(feature.summary.session_count, 4);
+    }
+
+    #[test]
+    fn test_project_detail_includes_decisions_newest_first() {
+        let (_tmp, pool) = setup();
+        let conn = pool.get().unwrap();
+        conn.execute_batch(
+            "INSERT INTO decisions (project_id, date, title, description, source, session_id)
+                VALUES ('proj_1', '2026-01-01T10:00:00Z', 'Keep JSONL', '', 'transcript', 'ses_1');
+             INSERT INTO decisions (project_id, date, title, description, source)
+                VALUES ('proj_1', '2026-01-02T09:00:00Z', 'Use FTS5', 'no extra service', 'manual');",
+        )
+        .unwrap();
+
+        let detail = get_project_detail_inner(&pool, "proj_1".into(), None).unwrap();
+        let titles: Vec<&str> = detail.decisions.iter().map(|d| d.title.as_str()).collect();
+        assert_eq!(titles, vec!["Use FTS5", "Keep JSONL"]);
+        assert_eq!(detail.decisions[1].session_id.as_deref(), Some("ses_1"));
+    }
+
     #[test]
     fn test_get_roadmap_empty() {
         let (_tmp, pool) = setup();
@@ -548,16 +1186,164 @@ mod tests {
         assert_eq!(roadmap.progress_percent, 50.0); // from projects table
     }
 
+    #[test]
+    fn test_edit_roadmap_writes_file_and_refreshes_items() {
+        let (tmp, pool) = setup();
+        let conn = pool.get().unwrap();
+        let project_dir = tmp.path().join("projects").join("test");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        std::fs::write(project_dir.join("meta.toml"), "schema_version = 1\n\n[project]\nid = \"proj_1\"\nname = \"Test Project\"\nstatus = \"active\"\ncreated_at = \"2026-01-01T00:00:00Z\"\n\n[paths]\n").unwrap();
+        std::fs::write(project_dir.join("roadmap.md"), "## Phase 1\n- [>] Auth {id: auth}\n").unwrap();
+        conn.execute(
+            "UPDATE projects SET meta_toml_path = ?1 WHERE id = 'proj_1'",
+            params![project_dir.join("meta.toml").to_string_lossy().to_string()],
+        ).unwrap();
+
+        use seslog_core::roadmap::ItemStatus;
+        use seslog_core::roadmap_edit::RoadmapEdit;
+        let add = RoadmapEdit::AddItem {
+            text: "Billing".into(), status: ItemStatus::Pending, phase: None,
+            id: None, depends_on: vec!["auth".into()], after: None,
+        };
+        let roadmap = edit_roadmap_with_conn(&conn, tmp.path(), "proj_1", &add).unwrap();
+        assert_eq!(roadmap.items.len(), 2);
+        assert_eq!(roadmap.items[1].depends_on, vec!["auth"]);
+
+        let done = RoadmapEdit::SetStatus { item: "auth".into(), status: ItemStatus::Done };
+        let roadmap = edit_roadmap_with_conn(&conn, tmp.path(), "proj_1", &done).unwrap();
+        assert_eq!(roadmap.items[0].status, "done");
+        assert_eq!(roadmap.progress_percent, 50.0);
+        assert_eq!(
+            std::fs::read_to_string(project_dir.join("roadmap.md")).unwrap(),
+            "## Phase 1\n- [x] Auth {id: auth}\n- [ ] Billing {depends: auth}\n"
+        );
+
+        // Rejected edits leave the file alone.
+        let remove = RoadmapEdit::Remove { item: "auth".into() };
+        assert!(edit_roadmap_with_conn(&conn, tmp.path(), "proj_1", &remove).is_err());
+        assert!(edit_roadmap_with_conn(&conn, tmp.path(), "proj_missing", &remove).is_err());
+    }
+
+    #[test]
+    fn test_get_roadmap_reports_dependencies() {
+        let (_tmp, pool) = setup();
+        let conn = pool.get().unwrap();
+        conn.execute_batch(r#"
+            INSERT INTO roadmap_items (project_id, phase, item_text, status, sort_order, item_id, depends_on) VALUES
+                ('proj_1', 'P', 'Data', 'done', 0, 'data', NULL),
+                ('proj_1', 'P', 'Train', 'active', 1, 'train', '["data"]'),
+                ('proj_1', 'P', 'Eval', 'pending', 2, 'eval', '["train"]'),
+                ('proj_1', 'P', 'Docs', 'pending', 3, NULL, '["gone"]');
+        "#).unwrap();
+        let roadmap = get_roadmap_inner(&pool, "proj_1".into()).unwrap();
+        assert_eq!(roadmap.items[2].waiting_on, vec!["train"]);
+        assert!(!roadmap.items[2].ready);
+        assert!(roadmap.items[3].ready);
+        assert_eq!(roadmap.items.iter().map(|i| i.depth).collect::<Vec<_>>(), vec![0, 1, 2, 0]);
+        assert_eq!(roadmap.critical_path_length, 2);
+        assert!(roadmap.items[1].on_critical_path && roadmap.items[2].on_critical_path);
+        assert_eq!(roadmap.warnings, vec!["Item 'Docs' depends on 'gone' which does not exist"]);
+    }
+
     #[test]
     fn test_get_overview_active_only() {
         let (_tmp, pool) = setup();
-        let rows = get_overview_inner(&pool, false).unwrap();
+        let rows = get_overview_inner(&pool, false, TimeMetric::WallClock).unwrap();
         assert_eq!(rows.len(), 1);
         assert_eq!(rows[0].name, "Test Project");
         assert_eq!(rows[0].session_count, 1);
         assert_eq!(rows[0].total_minutes, 30);
     }
 
+    #[test]
+    fn test_usage_breakdown_per_model_and_turn() {
+        let (_tmp, pool) = setup();
+        let conn = pool.get().unwrap();
+        conn.execute_batch("
+            INSERT INTO projects (id, name, status, created_at) VALUES ('proj_2', 'Old', 'archived', '2025-01-01T00:00:00Z');
+            INSERT INTO sessions (id, project_id, machine, started_at) VALUES ('ses_2', 'proj_2', 'mac', '2025-01-01T10:00:00Z');
+            INSERT INTO session_usage (session_id, turn, model, input_tokens, output_tokens, cache_read_tokens, cost_usd, turns) VALUES
+                ('ses_1', 0, 'claude-haiku-4-5', 10, 5, 0, 0.25, 4),
+                ('ses_1', 4, 'claude-opus-4-6', 100, 50, 1000, 1.5, 1),
+                ('ses_1', 5, 'claude-haiku-4-5', 20, 5, 0, NULL, 1),
+                ('ses_2', 0, 'claude-sonnet-4-5', 5000, 0, 0, 0.1, 1);
+        ").unwrap();
+
+        let session = get_session_by_id(&pool, "proj_1", "ses_1").unwrap().unwrap();
+        let models: Vec<(&str, i64, i64)> = session.model_usage.iter()
+            .map(|m| (m.model.as_str(), m.turns, m.total_tokens()))
+            .collect();
+        assert_eq!(models, vec![("claude-opus-4-6", 1, 1150), ("claude-haiku-4-5", 5, 40)]);
+        assert_eq!(session.model_usage[1].cost_usd, Some(0.25));
+        let turns: Vec<&str> = session.turn_usage.iter().map(|t| t.model.as_str()).collect();
+        assert_eq!(turns, vec!["claude-haiku-4-5", "claude-opus-4-6", "claude-haiku-4-5"]);
+
+        assert_eq!(get_model_usage_inner(&pool, false).unwrap().len(), 2);
+        let all = get_model_usage_inner(&pool, true).unwrap();
+        assert_eq!(all[0].model, "claude-sonnet-4-5");
+    }
+
+    #[test]
+    fn test_tool_stats_and_hotspots() {
+        let (_tmp, pool) = setup();
+        let conn = pool.get().unwrap();
+        conn.execute_batch("
+            INSERT INTO sessions (id, project_id, machine, started_at, git_branch) VALUES ('ses_2', 'proj_1', 'mac', '2026-01-02T10:00:00Z', 'main');
+            INSERT INTO session_tools (session_id, tool, calls, errors, sort_order) VALUES
+                ('ses_1', 'Read', 4, 0, 0),
+                ('ses_1', 'Edit', 3, 1, 1);
+            INSERT INTO session_touched_files (session_id, path, reads, edits, writes, sort_order) VALUES
+                ('ses_1', 'src/lib.rs', 2, 3, 0, 0),
+                ('ses_1', 'README.md', 2, 0, 0, 1),
+                ('ses_2', 'src/lib.rs', 1, 1, 0, 0),
+                ('ses_2', 'src/new.rs', 0, 0, 1, 1);
+        ").unwrap();
+
+        let session = get_session_by_id(&pool, "proj_1", "ses_1").unwrap().unwrap();
+        assert_eq!(session.tool_stats[1], ToolStatsResponse { tool: "Edit".into(), calls: 3, errors: 1 });
+        assert!(session.subagents.is_empty());
+        let paths: Vec<&str> = session.touched_files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/lib.rs", "README.md"]);
+
+        let detail = get_project_detail_inner(&pool, "proj_1".into(), None).unwrap();
+        let hotspots: Vec<(&str, i64, i64)> = detail.hotspots.iter()
+            .map(|h| (h.path.as_str(), h.sessions, h.modifications))
+            .collect();
+        assert_eq!(hotspots, vec![("src/lib.rs", 2, 4), ("src/new.rs", 1, 1), ("README.md", 1, 0)]);
+        assert_eq!(detail.hotspots[0].last_touched_at, "2026-01-02T10:00:00Z");
+
+        let main = get_project_detail_inner(&pool, "proj_1".into(), Some("main")).unwrap();
+        assert_eq!(main.hotspots.len(), 2);
+    }
+
+    #[test]
+    fn test_subagents_with_their_tools() {
+        let (_tmp, pool) = setup();
+        let conn = pool.get().unwrap();
+        conn.execute_batch("
+            INSERT INTO session_subagents (session_id, agent_id, agent_type, description, summaries, turns, input_tokens, cost_usd, sort_order) VALUES
+                ('ses_1', 'a1', 'Explore', 'Find callers', 'Found two\nBoth in auth', 2, 500, 0.05, 0),
+                ('ses_1', 'b2', NULL, 'Run the tests', '', 1, 100, NULL, 1);
+            INSERT INTO session_tools (session_id, tool, calls, errors, sort_order, agent_id) VALUES
+                ('ses_1', 'Task', 2, 0, 0, NULL),
+                ('ses_1', 'Grep', 3, 1, 0, 'a1'),
+                ('ses_1', 'Bash', 1, 0, 0, 'b2');
+            INSERT INTO session_usage (session_id, turn, model, input_tokens, agent_id) VALUES
+                ('ses_1', 0, 'claude-opus-4-6', 50, NULL),
+                ('ses_1', 1, 'claude-haiku-4-5', 500, 'a1');
+        ").unwrap();
+
+        let session = get_session_by_id(&pool, "proj_1", "ses_1").unwrap().unwrap();
+        assert_eq!(session.tool_stats, vec![ToolStatsResponse { tool: "Task".into(), calls: 2, errors: 0 }]);
+        let subagents: Vec<(&str, Option<&str>, usize, i64)> = session.subagents.iter()
+            .map(|a| (a.agent_id.as_str(), a.agent_type.as_deref(), a.summaries.len(), a.total_tokens()))
+            .collect();
+        assert_eq!(subagents, vec![("a1", Some("Explore"), 2, 500), ("b2", None, 0, 100)]);
+        assert_eq!(session.subagents[0].tool_stats[0], ToolStatsResponse { tool: "Grep".into(), calls: 3, errors: 1 });
+        assert_eq!(session.subagents[1].tool_stats[0].tool, "Bash");
+        assert_eq!(session.turn_usage[1].agent_id.as_deref(), Some("a1"));
+    }
+
     #[test]
     fn test_get_overview_includes_archived() {
         let (_tmp, pool) = setup();
@@ -568,7 +1354,81 @@ mod tests {
             params!["proj_2", "Archived Project", "archived", "2025-06-01T00:00:00Z", 100.0],
         )
         .unwrap();
-        let rows = get_overview_inner(&pool, true).unwrap();
+        let rows = get_overview_inner(&pool, true, TimeMetric::WallClock).unwrap();
         assert_eq!(rows.len(), 2);
     }
+
+    #[test]
+    fn test_get_overview_active_time_metric() {
+        let (_tmp, pool) = setup();
+        let conn = pool.get().unwrap();
+        conn.execute(
+            "INSERT INTO sessions (id, project_id, machine, started_at, duration_minutes, active_minutes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params!["ses_2", "proj_1", "macbook", "2026-01-02T10:00:00Z", 120, 45],
+        )
+        .unwrap();
+
+        let wall = get_overview_inner(&pool, false, TimeMetric::WallClock).unwrap();
+        assert_eq!(wall[0].total_minutes, 150);
+        // ses_1 has no active time recorded, so its wall-clock 30 minutes count
+        let active = get_overview_inner(&pool, false, TimeMetric::Active).unwrap();
+        assert_eq!(active[0].total_minutes, 75);
+    }
+
+    #[test]
+    fn test_search_ranks_and_dedupes_sessions() {
+        let (tmp, pool) = setup();
+        let conn = pool.get().unwrap();
+        let project_dir = tmp.path().join("projects/test-project");
+        std::fs::create_dir_all(project_dir.join("sessions")).unwrap();
+        std::fs::write(project_dir.join("meta.toml"), r#"schema_version = 1
+
+[project]
+id = "proj_1"
+name = "Test Project"
+status = "active"
+created_at = "2026-01-01T00:00:00Z"
+
+[paths]
+"#).unwrap();
+        std::fs::write(project_dir.join("sessions/ses_oauth.json"), r#"{
+            "schema_version": 1,
+            "id": "ses_oauth",
+            "project_id": "proj_1",
+            "machine": "macbook",
+            "started_at": "2026-01-05T10:00:00Z",
+            "summary": "Fixed the OAuth refresh bug",
+            "transcript_highlights": ["OAuth token refresh raced with logout"]
+        }"#).unwrap();
+        std::fs::write(project_dir.join("roadmap.md"), "## Auth\n- [ ] OAuth provider docs\n").unwrap();
+        crate::reconcile::full_rebuild(&conn, tmp.path()).unwrap();
+
+        let results = search_inner(&pool, "oauth refr", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].session_id.as_deref(), Some("ses_oauth"));
+        assert_eq!(results[0].project_name, "Test Project");
+        assert!(results[0].snippet.contains(&format!("{}OAuth{}", SNIPPET_MATCH_START, SNIPPET_MATCH_END)));
+
+        let results = search_inner(&pool, "oauth", 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().any(|r| r.kind == "roadmap" && r.session_id.is_none()));
+
+        // A session matching in many fields still counts once towards the limit. The
+        // filler keeps "oauth" rare enough for bm25 to rank the short rows first.
+        let highlights: Vec<&str> = std::iter::repeat_n("OAuth", 12).chain(std::iter::repeat_n("unrelated filler text", 40)).collect();
+        std::fs::write(project_dir.join("sessions/ses_chatty.json"), serde_json::json!({
+            "schema_version": 1, "id": "ses_chatty", "project_id": "proj_1", "machine": "macbook",
+            "started_at": "2026-01-06T10:00:00Z", "summary": "OAuth", "transcript_highlights": highlights,
+        }).to_string()).unwrap();
+        crate::reconcile::full_rebuild(&conn, tmp.path()).unwrap();
+        let results = search_inner(&pool, "oauth", 2).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].session_id.as_deref(), Some("ses_chatty"));
+        assert_ne!(results[1].session_id.as_deref(), Some("ses_chatty"));
+
+        // Punctuation and quotes are not FTS syntax errors
+        assert!(search_inner(&pool, "\"oauth-refresh:", 10).is_ok());
+        assert!(search_inner(&pool, "   ", 10).unwrap().is_empty());
+    }
 }
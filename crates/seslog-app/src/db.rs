@@ -3,7 +3,7 @@ use rusqlite::Connection;
 use std::path::Path;
 
 /// Current schema version. Bump when adding migrations.
-pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+pub const CURRENT_SCHEMA_VERSION: u32 = 12;
 
 /// DDL for the current schema. Applied when initializing a fresh database.
 pub const SCHEMA_DDL: &str = r#"
@@ -29,6 +29,7 @@ CREATE TABLE IF NOT EXISTS sessions (
     started_at          TEXT NOT NULL,
     ended_at            TEXT,
     duration_minutes    INTEGER,
+    active_minutes      INTEGER,
     end_reason          TEXT,
     summary             TEXT,
     summary_source      TEXT,
@@ -40,9 +41,90 @@ CREATE TABLE IF NOT EXISTS sessions (
     token_count         INTEGER,
     estimated_cost_usd  REAL,
     model               TEXT,
+    insertions          INTEGER,
+    deletions           INTEGER,
+    git_base            TEXT,
+    git_head            TEXT,
+    git_branch          TEXT,
+    worktree            TEXT,
+    upstream            TEXT,
+    upstream_ahead      INTEGER,
+    upstream_behind     INTEGER,
+    input_tokens        INTEGER,
+    output_tokens       INTEGER,
+    cache_creation_tokens INTEGER,
+    cache_read_tokens   INTEGER,
     created_at          TEXT NOT NULL DEFAULT (datetime('now'))
 );
 
+-- Token usage of each assistant turn; per-model totals are grouped from it.
+CREATE TABLE IF NOT EXISTS session_usage (
+    id                      INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id              TEXT NOT NULL REFERENCES sessions(id),
+    turn                    INTEGER NOT NULL,
+    model                   TEXT NOT NULL,
+    input_tokens            INTEGER NOT NULL DEFAULT 0,
+    output_tokens           INTEGER NOT NULL DEFAULT 0,
+    cache_creation_tokens   INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens       INTEGER NOT NULL DEFAULT 0,
+    cost_usd                REAL,
+    agent_id                TEXT,
+    turns                   INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE TABLE IF NOT EXISTS session_files (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id  TEXT NOT NULL REFERENCES sessions(id),
+    path        TEXT NOT NULL,
+    status      TEXT NOT NULL,
+    insertions  INTEGER NOT NULL DEFAULT 0,
+    deletions   INTEGER NOT NULL DEFAULT 0,
+    sort_order  INTEGER NOT NULL DEFAULT 0
+);
+
+-- Calls and failed results per tool, from the transcript. `agent_id` is NULL for
+-- the main conversation.
+CREATE TABLE IF NOT EXISTS session_tools (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id  TEXT NOT NULL REFERENCES sessions(id),
+    tool        TEXT NOT NULL,
+    calls       INTEGER NOT NULL DEFAULT 0,
+    errors      INTEGER NOT NULL DEFAULT 0,
+    sort_order  INTEGER NOT NULL DEFAULT 0,
+    agent_id    TEXT
+);
+
+-- Subagent runs (sidechains); their turns are also in session_usage, tagged by agent_id.
+CREATE TABLE IF NOT EXISTS session_subagents (
+    id                      INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id              TEXT NOT NULL REFERENCES sessions(id),
+    agent_id                TEXT NOT NULL,
+    agent_type              TEXT,
+    description             TEXT NOT NULL DEFAULT '',
+    summaries               TEXT NOT NULL DEFAULT '',
+    model                   TEXT,
+    started_at              TEXT,
+    ended_at                TEXT,
+    turns                   INTEGER NOT NULL DEFAULT 0,
+    input_tokens            INTEGER NOT NULL DEFAULT 0,
+    output_tokens           INTEGER NOT NULL DEFAULT 0,
+    cache_creation_tokens   INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens       INTEGER NOT NULL DEFAULT 0,
+    cost_usd                REAL,
+    sort_order              INTEGER NOT NULL DEFAULT 0
+);
+
+-- Files named by Read, Edit and Write calls; project hotspots are grouped from it.
+CREATE TABLE IF NOT EXISTS session_touched_files (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id  TEXT NOT NULL REFERENCES sessions(id),
+    path        TEXT NOT NULL,
+    reads       INTEGER NOT NULL DEFAULT 0,
+    edits       INTEGER NOT NULL DEFAULT 0,
+    writes      INTEGER NOT NULL DEFAULT 0,
+    sort_order  INTEGER NOT NULL DEFAULT 0
+);
+
 CREATE TABLE IF NOT EXISTS transcript_highlights (
     id          INTEGER PRIMARY KEY AUTOINCREMENT,
     session_id  TEXT NOT NULL REFERENCES sessions(id),
@@ -67,7 +149,9 @@ CREATE TABLE IF NOT EXISTS decisions (
     date        TEXT,
     title       TEXT NOT NULL,
     description TEXT,
-    sort_order  INTEGER NOT NULL DEFAULT 0
+    sort_order  INTEGER NOT NULL DEFAULT 0,
+    session_id  TEXT,
+    source      TEXT NOT NULL DEFAULT 'manual'
 );
 
 CREATE TABLE IF NOT EXISTS machines (
@@ -86,6 +170,24 @@ CREATE INDEX IF NOT EXISTS idx_sessions_project  ON sessions(project_id);
 CREATE INDEX IF NOT EXISTS idx_sessions_started  ON sessions(started_at DESC);
 CREATE INDEX IF NOT EXISTS idx_sessions_machine  ON sessions(machine);
 CREATE INDEX IF NOT EXISTS idx_roadmap_project   ON roadmap_items(project_id);
+CREATE INDEX IF NOT EXISTS idx_decisions_project ON decisions(project_id);
+CREATE INDEX IF NOT EXISTS idx_session_files     ON session_files(session_id);
+CREATE INDEX IF NOT EXISTS idx_session_usage     ON session_usage(session_id);
+CREATE INDEX IF NOT EXISTS idx_session_tools     ON session_tools(session_id);
+CREATE INDEX IF NOT EXISTS idx_subagents_session ON session_subagents(session_id);
+CREATE INDEX IF NOT EXISTS idx_touched_session   ON session_touched_files(session_id);
+CREATE INDEX IF NOT EXISTS idx_touched_path      ON session_touched_files(path);
+CREATE INDEX IF NOT EXISTS idx_sessions_branch   ON sessions(project_id, git_branch);
+
+-- Full-text search over session summaries, next steps, highlights and roadmap items.
+-- `kind` is one of: summary, next_steps, highlight, roadmap. Roadmap rows have no session_id.
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+    content,
+    kind        UNINDEXED,
+    project_id  UNINDEXED,
+    session_id  UNINDEXED,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
 
 -- Aggregated project summary view
 CREATE VIEW IF NOT EXISTS project_summary AS
@@ -154,11 +256,191 @@ fn migrate_v1_to_v2(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Migrate from schema v2 to v3: add idle-aware active time alongside wall-clock duration.
+fn migrate_v2_to_v3(conn: &Connection) -> Result<()> {
+    conn.execute_batch("
+        ALTER TABLE sessions ADD COLUMN active_minutes INTEGER;
+    ")?;
+    Ok(())
+}
+
+/// Migrate from schema v3 to v4: add the FTS5 search index and backfill it from existing rows.
+fn migrate_v3_to_v4(conn: &Connection) -> Result<()> {
+    conn.execute_batch("
+        CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+            content,
+            kind        UNINDEXED,
+            project_id  UNINDEXED,
+            session_id  UNINDEXED,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+        INSERT INTO search_index (content, kind, project_id, session_id)
+            SELECT summary, 'summary', project_id, id FROM sessions
+            WHERE summary IS NOT NULL AND summary != '';
+        INSERT INTO search_index (content, kind, project_id, session_id)
+            SELECT next_steps, 'next_steps', project_id, id FROM sessions
+            WHERE next_steps IS NOT NULL AND next_steps != '';
+        INSERT INTO search_index (content, kind, project_id, session_id)
+            SELECT h.content, 'highlight', s.project_id, s.id
+            FROM transcript_highlights h JOIN sessions s ON s.id = h.session_id;
+        INSERT INTO search_index (content, kind, project_id, session_id)
+            SELECT item_text, 'roadmap', project_id, NULL FROM roadmap_items;
+    ")?;
+    Ok(())
+}
+
+/// Migrate from schema v4 to v5: link decisions to sessions and record where they came from.
+fn migrate_v4_to_v5(conn: &Connection) -> Result<()> {
+    conn.execute_batch("
+        ALTER TABLE decisions ADD COLUMN session_id TEXT;
+        ALTER TABLE decisions ADD COLUMN source TEXT NOT NULL DEFAULT 'manual';
+        CREATE INDEX IF NOT EXISTS idx_decisions_project ON decisions(project_id);
+    ")?;
+    Ok(())
+}
+
+/// Migrate from schema v5 to v6: per-session git range, line counts and changed files.
+fn migrate_v5_to_v6(conn: &Connection) -> Result<()> {
+    conn.execute_batch("
+        ALTER TABLE sessions ADD COLUMN insertions INTEGER;
+        ALTER TABLE sessions ADD COLUMN deletions INTEGER;
+        ALTER TABLE sessions ADD COLUMN git_base TEXT;
+        ALTER TABLE sessions ADD COLUMN git_head TEXT;
+        ALTER TABLE sessions ADD COLUMN git_branch TEXT;
+        CREATE TABLE IF NOT EXISTS session_files (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id  TEXT NOT NULL REFERENCES sessions(id),
+            path        TEXT NOT NULL,
+            status      TEXT NOT NULL,
+            insertions  INTEGER NOT NULL DEFAULT 0,
+            deletions   INTEGER NOT NULL DEFAULT 0,
+            sort_order  INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS idx_session_files ON session_files(session_id);
+    ")?;
+    Ok(())
+}
+
+/// Migrate from schema v6 to v7: worktree and upstream tracking state per session.
+fn migrate_v6_to_v7(conn: &Connection) -> Result<()> {
+    conn.execute_batch("
+        ALTER TABLE sessions ADD COLUMN worktree TEXT;
+        ALTER TABLE sessions ADD COLUMN upstream TEXT;
+        ALTER TABLE sessions ADD COLUMN upstream_ahead INTEGER;
+        ALTER TABLE sessions ADD COLUMN upstream_behind INTEGER;
+        CREATE INDEX IF NOT EXISTS idx_sessions_branch ON sessions(project_id, git_branch);
+    ")?;
+    Ok(())
+}
+
+/// Migrate from schema v7 to v8: token totals per billing category (input, output, cache).
+fn migrate_v7_to_v8(conn: &Connection) -> Result<()> {
+    conn.execute_batch("
+        ALTER TABLE sessions ADD COLUMN input_tokens INTEGER;
+        ALTER TABLE sessions ADD COLUMN output_tokens INTEGER;
+        ALTER TABLE sessions ADD COLUMN cache_creation_tokens INTEGER;
+        ALTER TABLE sessions ADD COLUMN cache_read_tokens INTEGER;
+    ")?;
+    Ok(())
+}
+
+/// Migrate from schema v8 to v9: per-turn, per-model token usage.
+fn migrate_v8_to_v9(conn: &Connection) -> Result<()> {
+    conn.execute_batch("
+        CREATE TABLE IF NOT EXISTS session_usage (
+            id                      INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id              TEXT NOT NULL REFERENCES sessions(id),
+            turn                    INTEGER NOT NULL,
+            model                   TEXT NOT NULL,
+            input_tokens            INTEGER NOT NULL DEFAULT 0,
+            output_tokens           INTEGER NOT NULL DEFAULT 0,
+            cache_creation_tokens   INTEGER NOT NULL DEFAULT 0,
+            cache_read_tokens       INTEGER NOT NULL DEFAULT 0,
+            cost_usd                REAL
+        );
+        CREATE INDEX IF NOT EXISTS idx_session_usage ON session_usage(session_id);
+    ")?;
+    Ok(())
+}
+
+/// Migrate from schema v9 to v10: per-tool call counts and files touched by Claude.
+fn migrate_v9_to_v10(conn: &Connection) -> Result<()> {
+    conn.execute_batch("
+        CREATE TABLE IF NOT EXISTS session_tools (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id  TEXT NOT NULL REFERENCES sessions(id),
+            tool        TEXT NOT NULL,
+            calls       INTEGER NOT NULL DEFAULT 0,
+            errors      INTEGER NOT NULL DEFAULT 0,
+            sort_order  INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE TABLE IF NOT EXISTS session_touched_files (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id  TEXT NOT NULL REFERENCES sessions(id),
+            path        TEXT NOT NULL,
+            reads       INTEGER NOT NULL DEFAULT 0,
+            edits       INTEGER NOT NULL DEFAULT 0,
+            writes      INTEGER NOT NULL DEFAULT 0,
+            sort_order  INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS idx_session_tools ON session_tools(session_id);
+        CREATE INDEX IF NOT EXISTS idx_touched_session ON session_touched_files(session_id);
+        CREATE INDEX IF NOT EXISTS idx_touched_path ON session_touched_files(path);
+    ")?;
+    Ok(())
+}
+
+/// Migrate from schema v10 to v11: subagent runs, with their turns and tool calls tagged.
+fn migrate_v10_to_v11(conn: &Connection) -> Result<()> {
+    conn.execute_batch("
+        ALTER TABLE session_usage ADD COLUMN agent_id TEXT;
+        ALTER TABLE session_tools ADD COLUMN agent_id TEXT;
+        CREATE TABLE IF NOT EXISTS session_subagents (
+            id                      INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id              TEXT NOT NULL REFERENCES sessions(id),
+            agent_id                TEXT NOT NULL,
+            agent_type              TEXT,
+            description             TEXT NOT NULL DEFAULT '',
+            summaries               TEXT NOT NULL DEFAULT '',
+            model                   TEXT,
+            started_at              TEXT,
+            ended_at                TEXT,
+            turns                   INTEGER NOT NULL DEFAULT 0,
+            input_tokens            INTEGER NOT NULL DEFAULT 0,
+            output_tokens           INTEGER NOT NULL DEFAULT 0,
+            cache_creation_tokens   INTEGER NOT NULL DEFAULT 0,
+            cache_read_tokens       INTEGER NOT NULL DEFAULT 0,
+            cost_usd                REAL,
+            sort_order              INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS idx_subagents_session ON session_subagents(session_id);
+    ")?;
+    Ok(())
+}
+
+/// Migrate from schema v11 to v12: usage rows that stand for several folded turns.
+fn migrate_v11_to_v12(conn: &Connection) -> Result<()> {
+    conn.execute_batch("
+        ALTER TABLE session_usage ADD COLUMN turns INTEGER NOT NULL DEFAULT 1;
+    ")?;
+    Ok(())
+}
+
 /// Apply incremental migrations from `from_version` to `to_version`.
 pub fn apply_migration(conn: &Connection, from: u32, to: u32) -> Result<()> {
     for version in from..to {
         match version {
             1 => migrate_v1_to_v2(conn)?,
+            2 => migrate_v2_to_v3(conn)?,
+            3 => migrate_v3_to_v4(conn)?,
+            4 => migrate_v4_to_v5(conn)?,
+            5 => migrate_v5_to_v6(conn)?,
+            6 => migrate_v6_to_v7(conn)?,
+            7 => migrate_v7_to_v8(conn)?,
+            8 => migrate_v8_to_v9(conn)?,
+            9 => migrate_v9_to_v10(conn)?,
+            10 => migrate_v10_to_v11(conn)?,
+            11 => migrate_v11_to_v12(conn)?,
             _ => bail!(
                 "Unknown migration path: v{} -> v{}. \
                  Please update seslog-app to handle this migration.",
@@ -207,10 +489,16 @@ mod tests {
             "projects",
             "sessions",
             "transcript_highlights",
+            "session_files",
+            "session_usage",
+            "session_tools",
+            "session_touched_files",
+            "session_subagents",
             "roadmap_items",
             "decisions",
             "machines",
             "processed_events",
+            "search_index",
         ] {
             assert!(
                 tables.contains(&expected.to_string()),
@@ -240,7 +528,7 @@ mod tests {
         let v1: u32 = conn1
             .pragma_query_value(None, "user_version", |row| row.get(0))
             .unwrap();
-        assert_eq!(v1, 2);
+        assert_eq!(v1, CURRENT_SCHEMA_VERSION);
         drop(conn1);
 
         // Second call on the same file
@@ -248,7 +536,7 @@ mod tests {
         let v2: u32 = conn2
             .pragma_query_value(None, "user_version", |row| row.get(0))
             .unwrap();
-        assert_eq!(v2, 2);
+        assert_eq!(v2, CURRENT_SCHEMA_VERSION);
     }
 
     #[test]
@@ -381,12 +669,12 @@ GROUP BY p.id;
         conn.pragma_update(None, "user_version", 1u32).unwrap();
         drop(conn);
 
-        // Re-open with initialize_db which should detect v1 and migrate to v2
+        // Re-open with initialize_db which should detect v1 and migrate to current
         let conn = initialize_db(&db_path).unwrap();
         let version: u32 = conn
             .pragma_query_value(None, "user_version", |row| row.get(0))
             .unwrap();
-        assert_eq!(version, 2);
+        assert_eq!(version, CURRENT_SCHEMA_VERSION);
 
         // Verify new columns exist by inserting data that uses them
         conn.execute(
@@ -410,11 +698,251 @@ GROUP BY p.id;
     }
 
     #[test]
-    fn test_fresh_db_is_v2() {
+    fn test_migration_v2_to_v3_adds_active_minutes() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(SCHEMA_V1_ORIGINAL).unwrap();
+        migrate_v1_to_v2(&conn).unwrap();
+        conn.pragma_update(None, "user_version", 2u32).unwrap();
+        conn.execute("INSERT INTO projects (id, name) VALUES ('p1', 'Test')", []).unwrap();
+        conn.execute(
+            "INSERT INTO sessions (id, project_id, machine, started_at, duration_minutes) \
+             VALUES ('s1', 'p1', 'mac', '2024-01-01', 90)",
+            [],
+        )
+        .unwrap();
+        drop(conn);
+
+        let conn = initialize_db(&db_path).unwrap();
+        let (duration, active): (i64, Option<i64>) = conn
+            .query_row(
+                "SELECT duration_minutes, active_minutes FROM sessions WHERE id = 's1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(duration, 90);
+        assert_eq!(active, None);
+    }
+
+    #[test]
+    fn test_migration_v3_to_v4_backfills_search_index() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(SCHEMA_V1_ORIGINAL).unwrap();
+        migrate_v1_to_v2(&conn).unwrap();
+        migrate_v2_to_v3(&conn).unwrap();
+        conn.pragma_update(None, "user_version", 3u32).unwrap();
+        conn.execute_batch("
+            INSERT INTO projects (id, name) VALUES ('p1', 'Test');
+            INSERT INTO sessions (id, project_id, machine, started_at, summary, next_steps)
+                VALUES ('s1', 'p1', 'mac', '2024-01-01', 'Fixed OAuth refresh bug', '');
+            INSERT INTO transcript_highlights (session_id, content) VALUES ('s1', 'token expiry race');
+            INSERT INTO roadmap_items (project_id, item_text) VALUES ('p1', 'Ship billing export');
+        ").unwrap();
+        drop(conn);
+
+        let conn = initialize_db(&db_path).unwrap();
+        let count = |q: &str| -> i64 {
+            conn.query_row(
+                "SELECT COUNT(*) FROM search_index WHERE search_index MATCH ?1",
+                [q],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert_eq!(count("oauth"), 1);
+        assert_eq!(count("expiry"), 1);
+        assert_eq!(count("billing"), 1);
+        let total: i64 = conn.query_row("SELECT COUNT(*) FROM search_index", [], |r| r.get(0)).unwrap();
+        assert_eq!(total, 3);
+    }
+
+    #[test]
+    fn test_migration_v4_to_v5_adds_decision_columns() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(SCHEMA_V1_ORIGINAL).unwrap();
+        migrate_v1_to_v2(&conn).unwrap();
+        migrate_v2_to_v3(&conn).unwrap();
+        migrate_v3_to_v4(&conn).unwrap();
+        conn.pragma_update(None, "user_version", 4u32).unwrap();
+        conn.execute_batch("
+            INSERT INTO projects (id, name) VALUES ('p1', 'Test');
+            INSERT INTO decisions (project_id, title) VALUES ('p1', 'Use SQLite');
+        ").unwrap();
+        drop(conn);
+
+        let conn = initialize_db(&db_path).unwrap();
+        let (source, session_id): (String, Option<String>) = conn
+            .query_row("SELECT source, session_id FROM decisions", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!(source, "manual");
+        assert_eq!(session_id, None);
+    }
+
+    #[test]
+    fn test_migration_v5_to_v6_adds_git_range() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(SCHEMA_V1_ORIGINAL).unwrap();
+        migrate_v1_to_v2(&conn).unwrap();
+        migrate_v2_to_v3(&conn).unwrap();
+        migrate_v3_to_v4(&conn).unwrap();
+        migrate_v4_to_v5(&conn).unwrap();
+        conn.pragma_update(None, "user_version", 5u32).unwrap();
+        conn.execute_batch("
+            INSERT INTO projects (id, name) VALUES ('p1', 'Test');
+            INSERT INTO sessions (id, project_id, machine, started_at) VALUES ('s1', 'p1', 'mac', '2024-01-01');
+        ").unwrap();
+        drop(conn);
+
+        let conn = initialize_db(&db_path).unwrap();
+        let insertions: Option<i64> = conn
+            .query_row("SELECT insertions FROM sessions WHERE id = 's1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(insertions, None);
+        conn.execute(
+            "INSERT INTO session_files (session_id, path, status, insertions) VALUES ('s1', 'src/main.rs', 'modified', 4)",
+            [],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_migration_v6_to_v7_adds_worktree_columns() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(SCHEMA_V1_ORIGINAL).unwrap();
+        migrate_v1_to_v2(&conn).unwrap();
+        migrate_v2_to_v3(&conn).unwrap();
+        migrate_v3_to_v4(&conn).unwrap();
+        migrate_v4_to_v5(&conn).unwrap();
+        migrate_v5_to_v6(&conn).unwrap();
+        conn.pragma_update(None, "user_version", 6u32).unwrap();
+        drop(conn);
+
+        let conn = initialize_db(&db_path).unwrap();
+        conn.execute_batch("
+            INSERT INTO projects (id, name) VALUES ('p1', 'Test');
+            INSERT INTO sessions (id, project_id, machine, started_at, git_branch, worktree, upstream, upstream_ahead)
+                VALUES ('s1', 'p1', 'mac', '2024-01-01', 'main', '/src/p1', 'origin/main', 1);
+        ").unwrap();
+    }
+
+    #[test]
+    fn test_migration_v7_to_v8_adds_token_columns() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(SCHEMA_V1_ORIGINAL).unwrap();
+        migrate_v1_to_v2(&conn).unwrap();
+        migrate_v2_to_v3(&conn).unwrap();
+        migrate_v3_to_v4(&conn).unwrap();
+        migrate_v4_to_v5(&conn).unwrap();
+        migrate_v5_to_v6(&conn).unwrap();
+        migrate_v6_to_v7(&conn).unwrap();
+        conn.pragma_update(None, "user_version", 7u32).unwrap();
+        drop(conn);
+
+        let conn = initialize_db(&db_path).unwrap();
+        conn.execute_batch("
+            INSERT INTO projects (id, name) VALUES ('p1', 'Test');
+            INSERT INTO sessions (id, project_id, machine, started_at, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens)
+                VALUES ('s1', 'p1', 'mac', '2024-01-01', 10, 20, 30, 40);
+        ").unwrap();
+    }
+
+    #[test]
+    fn test_migration_v8_to_v9_adds_session_usage() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(SCHEMA_V1_ORIGINAL).unwrap();
+        apply_migration(&conn, 1, 8).unwrap();
+        conn.pragma_update(None, "user_version", 8u32).unwrap();
+        drop(conn);
+
+        let conn = initialize_db(&db_path).unwrap();
+        conn.execute_batch("
+            INSERT INTO projects (id, name) VALUES ('p1', 'Test');
+            INSERT INTO sessions (id, project_id, machine, started_at) VALUES ('s1', 'p1', 'mac', '2024-01-01');
+            INSERT INTO session_usage (session_id, turn, model, input_tokens, cost_usd)
+                VALUES ('s1', 0, 'claude-opus-4-6', 10, 0.5);
+        ").unwrap();
+    }
+
+    #[test]
+    fn test_migration_v9_to_v10_adds_tool_tables() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(SCHEMA_V1_ORIGINAL).unwrap();
+        apply_migration(&conn, 1, 9).unwrap();
+        conn.pragma_update(None, "user_version", 9u32).unwrap();
+        drop(conn);
+
+        let conn = initialize_db(&db_path).unwrap();
+        conn.execute_batch("
+            INSERT INTO projects (id, name) VALUES ('p1', 'Test');
+            INSERT INTO sessions (id, project_id, machine, started_at) VALUES ('s1', 'p1', 'mac', '2024-01-01');
+            INSERT INTO session_tools (session_id, tool, calls, errors) VALUES ('s1', 'Edit', 3, 1);
+            INSERT INTO session_touched_files (session_id, path, reads, edits) VALUES ('s1', 'src/main.rs', 1, 3);
+        ").unwrap();
+    }
+
+    #[test]
+    fn test_migration_v10_to_v11_adds_subagents() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(SCHEMA_V1_ORIGINAL).unwrap();
+        apply_migration(&conn, 1, 10).unwrap();
+        conn.pragma_update(None, "user_version", 10u32).unwrap();
+        drop(conn);
+
+        let conn = initialize_db(&db_path).unwrap();
+        conn.execute_batch("
+            INSERT INTO projects (id, name) VALUES ('p1', 'Test');
+            INSERT INTO sessions (id, project_id, machine, started_at) VALUES ('s1', 'p1', 'mac', '2024-01-01');
+            INSERT INTO session_subagents (session_id, agent_id, description, turns) VALUES ('s1', 'a1', 'Find callers', 2);
+            INSERT INTO session_usage (session_id, turn, model, agent_id) VALUES ('s1', 0, 'claude-haiku-4-5', 'a1');
+            INSERT INTO session_tools (session_id, tool, calls, agent_id) VALUES ('s1', 'Grep', 1, 'a1');
+        ").unwrap();
+    }
+
+    #[test]
+    fn test_migration_v11_to_v12_adds_usage_turns() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(SCHEMA_V1_ORIGINAL).unwrap();
+        apply_migration(&conn, 1, 11).unwrap();
+        conn.pragma_update(None, "user_version", 11u32).unwrap();
+        conn.execute_batch("
+            INSERT INTO projects (id, name) VALUES ('p1', 'Test');
+            INSERT INTO sessions (id, project_id, machine, started_at) VALUES ('s1', 'p1', 'mac', '2024-01-01');
+            INSERT INTO session_usage (session_id, turn, model) VALUES ('s1', 0, 'claude-opus-4-6');
+        ").unwrap();
+        drop(conn);
+
+        let conn = initialize_db(&db_path).unwrap();
+        let turns: i64 = conn.query_row("SELECT turns FROM session_usage", [], |row| row.get(0)).unwrap();
+        assert_eq!(turns, 1);
+        conn.execute("INSERT INTO session_usage (session_id, turn, model, turns) VALUES ('s1', 1, 'claude-opus-4-6', 40)", []).unwrap();
+    }
+
+    #[test]
+    fn test_fresh_db_is_current_version() {
         let (conn, _dir) = setup();
         let version: u32 = conn
             .pragma_query_value(None, "user_version", |row| row.get(0))
             .unwrap();
-        assert_eq!(version, 2);
+        assert_eq!(version, CURRENT_SCHEMA_VERSION);
     }
 }
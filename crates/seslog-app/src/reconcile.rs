@@ -3,6 +3,7 @@ use rusqlite::{params, Connection};
 use std::fs;
 use std::path::Path;
 
+use seslog_core::decisions::{self, DecisionSource};
 use seslog_core::models::{MachineProfile, ProjectMeta, Session};
 use seslog_core::roadmap::{self, ItemStatus};
 
@@ -27,7 +28,13 @@ pub fn full_rebuild(conn: &Connection, data_dir: &Path) -> Result<ReconcileRepor
 
         // 1. Clear all tables in reverse FK order.
         conn.execute_batch(
-            "DELETE FROM transcript_highlights;
+            "DELETE FROM search_index;
+             DELETE FROM transcript_highlights;
+             DELETE FROM session_files;
+             DELETE FROM session_usage;
+             DELETE FROM session_tools;
+             DELETE FROM session_touched_files;
+             DELETE FROM session_subagents;
              DELETE FROM decisions;
              DELETE FROM roadmap_items;
              DELETE FROM sessions;
@@ -81,6 +88,15 @@ pub fn full_rebuild(conn: &Connection, data_dir: &Path) -> Result<ReconcileRepor
                                 )),
                             }
                         }
+
+                        // 5. Import the decision log
+                        match import_decisions(conn, &slug_dir, &project_id) {
+                            Ok(count) => report.added += count,
+                            Err(e) => report.errors.push(format!(
+                                "decisions import for {}: {}",
+                                project_id, e
+                            )),
+                        }
                     }
                     Err(e) => {
                         report.errors.push(format!(
@@ -93,7 +109,7 @@ pub fn full_rebuild(conn: &Connection, data_dir: &Path) -> Result<ReconcileRepor
             }
         }
 
-        // 5. Scan machines/*.toml
+        // 6. Scan machines/*.toml
         let machines_dir = data_dir.join("machines");
         if machines_dir.is_dir() {
             let mut entries: Vec<_> = fs::read_dir(&machines_dir)
@@ -157,7 +173,7 @@ pub fn incremental_update(
             .map(|e| e == "json")
             .unwrap_or(false)
     {
-        let session_json = fs::read_to_string(changed_path)
+        let session_json = seslog_core::encryption::read_record(changed_path)
             .with_context(|| format!("Reading session file {}", changed_path.display()))?;
         let session: Session = serde_json::from_str(&session_json)
             .with_context(|| format!("Parsing session {}", changed_path.display()))?;
@@ -190,6 +206,24 @@ pub fn incremental_update(
         return Ok(());
     }
 
+    // Pattern: projects/{slug}/decisions.jsonl
+    if components.len() >= 3
+        && components[0] == "projects"
+        && changed_path
+            .file_name()
+            .map(|f| f == decisions::DECISIONS_FILE)
+            .unwrap_or(false)
+    {
+        let slug_dir = changed_path.parent().unwrap();
+        let meta_path = slug_dir.join("meta.toml");
+        if meta_path.exists() {
+            let meta_toml = fs::read_to_string(&meta_path)?;
+            let meta: ProjectMeta = toml::from_str(&meta_toml)?;
+            import_decisions(conn, slug_dir, &meta.project.id)?;
+        }
+        return Ok(());
+    }
+
     // Pattern: projects/{slug}/meta.toml
     if components.len() >= 3
         && components[0] == "projects"
@@ -262,7 +296,8 @@ fn import_sessions(conn: &Connection, sessions_dir: &Path, _project_id: &str) ->
 
     for entry in entries {
         let path = entry.path();
-        let content = fs::read_to_string(&path)?;
+        let content = seslog_core::encryption::read_record(&path)
+            .with_context(|| format!("Reading session {}", path.display()))?;
         let session: Session = serde_json::from_str(&content)
             .with_context(|| format!("Parsing session {}", path.display()))?;
         upsert_session(conn, &session, &path)?;
@@ -275,6 +310,9 @@ fn import_sessions(conn: &Connection, sessions_dir: &Path, _project_id: &str) ->
 /// INSERT OR REPLACE a single session and its transcript_highlights.
 fn upsert_session(conn: &Connection, session: &Session, source_path: &Path) -> Result<()> {
     let next_steps = &session.next_steps;
+    let range = session.git_range.as_ref();
+    let git = session.git_context.as_ref();
+    let usage = session.token_usage;
     let summary_source_str: Option<String> = session.summary_source.as_ref()
         .and_then(|s| serde_json::to_value(s).ok())
         .and_then(|v| v.as_str().map(|s| s.to_string()));
@@ -284,8 +322,12 @@ fn upsert_session(conn: &Connection, session: &Session, source_path: &Path) -> R
             (id, project_id, machine, started_at, ended_at,
              duration_minutes, end_reason, summary, summary_source,
              next_steps, files_changed, recovered, redaction_count, source_path,
-             token_count, estimated_cost_usd, model)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+             token_count, estimated_cost_usd, model, active_minutes,
+             insertions, deletions, git_base, git_head, git_branch,
+             worktree, upstream, upstream_ahead, upstream_behind,
+             input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18,
+                 ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31)",
         params![
             session.id,
             session.project_id,
@@ -304,6 +346,20 @@ fn upsert_session(conn: &Connection, session: &Session, source_path: &Path) -> R
             session.token_count.map(|t| t as i64),
             session.estimated_cost_usd,
             session.model,
+            session.active_minutes,
+            range.map(|r| r.insertions),
+            range.map(|r| r.deletions),
+            range.and_then(|r| r.base.as_deref()),
+            range.and_then(|r| r.head.as_deref()),
+            git.and_then(|g| g.branch.as_deref()).or(range.and_then(|r| r.branch.as_deref())),
+            git.and_then(|g| g.worktree.as_deref()),
+            git.and_then(|g| g.upstream.as_deref()),
+            git.filter(|g| g.upstream.is_some()).map(|g| g.ahead),
+            git.filter(|g| g.upstream.is_some()).map(|g| g.behind),
+            usage.map(|u| u.input as i64),
+            usage.map(|u| u.output as i64),
+            usage.map(|u| u.cache_creation as i64),
+            usage.map(|u| u.cache_read as i64),
         ],
     )?;
 
@@ -321,6 +377,170 @@ fn upsert_session(conn: &Connection, session: &Session, source_path: &Path) -> R
         )?;
     }
 
+    // Files changed during the session.
+    conn.execute(
+        "DELETE FROM session_files WHERE session_id = ?1",
+        params![session.id],
+    )?;
+    for (i, file) in range.map(|r| r.files.as_slice()).unwrap_or_default().iter().enumerate() {
+        conn.execute(
+            "INSERT INTO session_files (session_id, path, status, insertions, deletions, sort_order)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![session.id, file.path, file.status.as_str(), file.insertions, file.deletions, i as i32],
+        )?;
+    }
+
+    // Token usage per assistant turn. Folded entries cover several turns, so `turn` is
+    // the index of the first turn each row stands for.
+    conn.execute(
+        "DELETE FROM session_usage WHERE session_id = ?1",
+        params![session.id],
+    )?;
+    let mut turn_index = 0i64;
+    for turn in &session.usage_turns {
+        conn.execute(
+            "INSERT INTO session_usage
+                (session_id, turn, model, input_tokens, output_tokens,
+                 cache_creation_tokens, cache_read_tokens, cost_usd, agent_id, turns)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                session.id,
+                turn_index,
+                turn.model,
+                turn.usage.input as i64,
+                turn.usage.output as i64,
+                turn.usage.cache_creation as i64,
+                turn.usage.cache_read as i64,
+                turn.cost_usd,
+                turn.subagent,
+                turn.turns,
+            ],
+        )?;
+        turn_index += turn.turns as i64;
+    }
+
+    // Tool calls and the files Claude read or modified.
+    conn.execute(
+        "DELETE FROM session_tools WHERE session_id = ?1",
+        params![session.id],
+    )?;
+    for (i, tool) in session.tool_stats.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO session_tools (session_id, tool, calls, errors, sort_order)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![session.id, tool.name, tool.calls, tool.errors, i as i32],
+        )?;
+    }
+
+    // Subagent runs, each with its own tool calls.
+    conn.execute(
+        "DELETE FROM session_subagents WHERE session_id = ?1",
+        params![session.id],
+    )?;
+    for (i, subagent) in session.subagents.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO session_subagents
+                (session_id, agent_id, agent_type, description, summaries, model,
+                 started_at, ended_at, turns, input_tokens, output_tokens,
+                 cache_creation_tokens, cache_read_tokens, cost_usd, sort_order)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            params![
+                session.id,
+                subagent.id,
+                subagent.agent_type,
+                subagent.description,
+                subagent.summaries.join("\n"),
+                subagent.model,
+                subagent.started_at.map(|t| t.to_rfc3339()),
+                subagent.ended_at.map(|t| t.to_rfc3339()),
+                subagent.turns,
+                subagent.usage.input as i64,
+                subagent.usage.output as i64,
+                subagent.usage.cache_creation as i64,
+                subagent.usage.cache_read as i64,
+                subagent.cost_usd,
+                i as i32,
+            ],
+        )?;
+        for (j, tool) in subagent.tool_stats.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO session_tools (session_id, tool, calls, errors, sort_order, agent_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![session.id, tool.name, tool.calls, tool.errors, j as i32, subagent.id],
+            )?;
+        }
+    }
+    conn.execute(
+        "DELETE FROM session_touched_files WHERE session_id = ?1",
+        params![session.id],
+    )?;
+    for (i, file) in session.touched_files.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO session_touched_files (session_id, path, reads, edits, writes, sort_order)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![session.id, file.path, file.reads, file.edits, file.writes, i as i32],
+        )?;
+    }
+
+    // Decisions extracted from this session's transcript.
+    conn.execute(
+        "DELETE FROM decisions WHERE session_id = ?1 AND source = ?2",
+        params![session.id, DecisionSource::Transcript.as_str()],
+    )?;
+    for (i, decision) in session.decisions.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO decisions (project_id, date, title, sort_order, session_id, source)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                session.project_id,
+                session.started_at.to_rfc3339(),
+                decision,
+                i as i32,
+                session.id,
+                DecisionSource::Transcript.as_str(),
+            ],
+        )?;
+    }
+
+    index_session(conn, session)?;
+
+    Ok(())
+}
+
+/// Replace the full-text search rows for one session.
+fn index_session(conn: &Connection, session: &Session) -> Result<()> {
+    conn.execute(
+        "DELETE FROM search_index WHERE session_id = ?1",
+        params![session.id],
+    )?;
+
+    let fields = [("summary", &session.summary), ("next_steps", &session.next_steps)];
+    let docs = fields
+        .into_iter()
+        .chain(session.transcript_highlights.iter().map(|h| ("highlight", h)))
+        .filter(|(_, text)| !text.trim().is_empty());
+    for (kind, text) in docs {
+        conn.execute(
+            "INSERT INTO search_index (content, kind, project_id, session_id)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![text, kind, session.project_id, session.id],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Replace the full-text search rows for a project's roadmap items.
+fn index_roadmap(conn: &Connection, project_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM search_index WHERE kind = 'roadmap' AND project_id = ?1",
+        params![project_id],
+    )?;
+    conn.execute(
+        "INSERT INTO search_index (content, kind, project_id, session_id)
+         SELECT item_text, 'roadmap', project_id, NULL FROM roadmap_items WHERE project_id = ?1",
+        params![project_id],
+    )?;
     Ok(())
 }
 
@@ -362,9 +582,40 @@ fn import_roadmap(conn: &Connection, roadmap_path: &Path, project_id: &str) -> R
         params![progress, project_id],
     )?;
 
+    index_roadmap(conn, project_id)?;
+
     Ok(items.len() as u32)
 }
 
+/// Replace a project's logged decisions with the contents of its decisions.jsonl.
+/// Transcript-extracted decisions are owned by their session and left alone.
+/// Returns the number of decisions inserted.
+fn import_decisions(conn: &Connection, project_dir: &Path, project_id: &str) -> Result<u32> {
+    conn.execute(
+        "DELETE FROM decisions WHERE project_id = ?1 AND source != ?2",
+        params![project_id, DecisionSource::Transcript.as_str()],
+    )?;
+
+    let entries = decisions::load_decisions(project_dir);
+    for (i, d) in entries.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO decisions (project_id, date, title, description, sort_order, session_id, source)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                project_id,
+                d.date.to_rfc3339(),
+                d.title,
+                d.why,
+                i as i32,
+                d.session_id,
+                d.source.as_str(),
+            ],
+        )?;
+    }
+
+    Ok(entries.len() as u32)
+}
+
 /// Parse a machine TOML and INSERT OR REPLACE into machines table.
 fn import_machine(conn: &Connection, machine_path: &Path) -> Result<()> {
     let content = fs::read_to_string(machine_path)
@@ -450,7 +701,21 @@ created_at = "2026-01-01T00:00:00Z"
             "started_at": "2026-01-15T10:00:00Z",
             "summary": "Did some work",
             "summary_source": "transcript+git",
-            "transcript_highlights": ["highlight 1", "highlight 2"]
+            "transcript_highlights": ["highlight 1", "highlight 2"],
+            "token_count": 1110,
+            "token_usage": {"input": 10, "output": 100, "cache_creation": 200, "cache_read": 800},
+            "usage_turns": [
+                {"model": "claude-opus-4-6", "input": 4, "output": 60, "cache_creation": 200, "turns": 3, "cost_usd": 0.01},
+                {"model": "claude-haiku-4-5", "input": 6, "output": 40, "cache_read": 800, "subagent": "agent_1"}
+            ],
+            "subagents": [{
+                "id": "agent_1", "agent_type": "Explore", "description": "Find callers",
+                "summaries": ["Found two", "Both in auth"], "turns": 1,
+                "usage": {"input": 6, "output": 40, "cache_read": 800},
+                "tool_stats": [{"name": "Grep", "calls": 4}]
+            }],
+            "tool_stats": [{"name": "Read", "calls": 2}, {"name": "Edit", "calls": 3, "errors": 1}],
+            "touched_files": [{"path": "src/lib.rs", "reads": 2, "edits": 3}]
         }"#;
         let sessions_dir = dir.path().join("projects/test-project/sessions");
         fs::write(sessions_dir.join("ses_001.json"), session_json).unwrap();
@@ -471,6 +736,151 @@ created_at = "2026-01-01T00:00:00Z"
             )
             .unwrap();
         assert_eq!(highlight_count, 2);
+
+        let tokens: (i64, i64, i64, i64) = conn
+            .query_row(
+                "SELECT input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens FROM sessions",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(tokens, (10, 100, 200, 800));
+
+        let turns: Vec<(i64, i64, String, Option<f64>)> = conn
+            .prepare("SELECT turn, turns, model, cost_usd FROM session_usage ORDER BY turn")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(turns, vec![
+            (0, 3, "claude-opus-4-6".to_string(), Some(0.01)),
+            (3, 1, "claude-haiku-4-5".to_string(), None),
+        ]);
+
+        let tools: Vec<(String, i64, i64)> = conn
+            .prepare("SELECT tool, calls, errors FROM session_tools ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(tools, vec![("Read".to_string(), 2, 0), ("Edit".to_string(), 3, 1), ("Grep".to_string(), 4, 0)]);
+        let subagent: (String, String, i64, i64, String) = conn
+            .query_row(
+                "SELECT a.agent_id, a.summaries, a.cache_read_tokens, t.calls, u.model
+                 FROM session_subagents a
+                 JOIN session_tools t ON t.agent_id = a.agent_id
+                 JOIN session_usage u ON u.agent_id = a.agent_id",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            )
+            .unwrap();
+        assert_eq!(subagent, ("agent_1".to_string(), "Found two\nBoth in auth".to_string(), 800, 4, "claude-haiku-4-5".to_string()));
+        let touched: (String, i64, i64, i64) = conn
+            .query_row("SELECT path, reads, edits, writes FROM session_touched_files", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap();
+        assert_eq!(touched, ("src/lib.rs".to_string(), 2, 3, 0));
+    }
+
+    #[test]
+    fn test_full_rebuild_imports_git_range() {
+        let (dir, conn) = setup_test_env();
+        let session_json = r#"{
+            "schema_version": 1,
+            "id": "ses_git",
+            "project_id": "proj_test",
+            "machine": "macbook",
+            "started_at": "2026-01-15T10:00:00Z",
+            "summary": "+7 -2 across 2 file(s)",
+            "files_changed": 2,
+            "git_range": {
+                "base": "aaaaaaa", "head": "bbbbbbb", "branch": "main",
+                "insertions": 7, "deletions": 2,
+                "files": [
+                    {"path": "src/lib.rs", "status": "modified", "insertions": 5, "deletions": 2},
+                    {"path": "src/new.rs", "status": "added", "insertions": 2, "deletions": 0}
+                ]
+            }
+        }"#;
+        let sessions_dir = dir.path().join("projects/test-project/sessions");
+        fs::write(sessions_dir.join("ses_git.json"), session_json).unwrap();
+
+        full_rebuild(&conn, dir.path()).unwrap();
+        full_rebuild(&conn, dir.path()).unwrap();
+
+        let (insertions, base): (i64, String) = conn
+            .query_row("SELECT insertions, git_base FROM sessions WHERE id = 'ses_git'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((insertions, base.as_str()), (7, "aaaaaaa"));
+        let files: Vec<String> = conn
+            .prepare("SELECT status FROM session_files WHERE session_id = 'ses_git' ORDER BY sort_order")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(files, vec!["modified", "added"]);
+    }
+
+    #[test]
+    fn test_git_context_branch_wins_over_range_branch() {
+        let (dir, conn) = setup_test_env();
+        let session_json = r#"{
+            "schema_version": 1,
+            "id": "ses_wt",
+            "project_id": "proj_test",
+            "machine": "macbook",
+            "started_at": "2026-01-15T10:00:00Z",
+            "summary": "worktree work",
+            "git_range": {"base": "aaaaaaa", "head": "aaaaaaa", "branch": "main", "insertions": 0, "deletions": 0},
+            "git_context": {"branch": "feature/export", "worktree": "/src/export", "linked_worktree": true}
+        }"#;
+        let sessions_dir = dir.path().join("projects/test-project/sessions");
+        fs::write(sessions_dir.join("ses_wt.json"), session_json).unwrap();
+        full_rebuild(&conn, dir.path()).unwrap();
+
+        let (branch, worktree, ahead): (String, String, Option<i64>) = conn
+            .query_row("SELECT git_branch, worktree, upstream_ahead FROM sessions WHERE id = 'ses_wt'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+        assert_eq!((branch.as_str(), worktree.as_str(), ahead), ("feature/export", "/src/export", None));
+    }
+
+    #[test]
+    fn test_encrypted_sessions_are_decrypted_on_import() {
+        use seslog_core::config::{write_local_config, EncryptionConfig, LocalConfig};
+        let (dir, conn) = setup_test_env();
+
+        let encryption = EncryptionConfig { enabled: true, ..Default::default() };
+        seslog_core::encryption::init_identity(dir.path(), &encryption).unwrap();
+        write_local_config(&dir.path().join("local.toml"), &LocalConfig { encryption, ..Default::default() }).unwrap();
+
+        let session_path = dir.path().join("projects/test-project/sessions/ses_enc.json");
+        fs::write(&session_path, r#"{
+            "schema_version": 1,
+            "id": "ses_enc",
+            "project_id": "proj_test",
+            "machine": "macbook",
+            "started_at": "2026-01-15T10:00:00Z",
+            "summary": "Rotated the staging credentials"
+        }"#).unwrap();
+        assert_eq!(seslog_core::encryption::prepare_for_commit(dir.path()).unwrap(), 1);
+        assert!(!fs::read_to_string(&session_path).unwrap().contains("staging"));
+
+        let report = full_rebuild(&conn, dir.path()).unwrap();
+        assert!(report.errors.is_empty(), "errors: {:?}", report.errors);
+        incremental_update(&conn, &session_path, dir.path()).unwrap();
+
+        let summary: String = conn
+            .query_row("SELECT summary FROM sessions WHERE id = 'ses_enc'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(summary, "Rotated the staging credentials");
     }
 
     #[test]
@@ -578,4 +988,90 @@ created_at = "2026-01-01T00:00:00Z"
             .unwrap();
         assert_eq!(count, 1);
     }
+
+    #[test]
+    fn test_decisions_imported_from_log_and_sessions() {
+        let (dir, conn) = setup_test_env();
+        let project_dir = dir.path().join("projects/test-project");
+        fs::write(project_dir.join("sessions/ses_dec.json"), r#"{
+            "schema_version": 1,
+            "id": "ses_dec",
+            "project_id": "proj_test",
+            "machine": "macbook",
+            "started_at": "2026-02-01T10:00:00Z",
+            "summary": "s",
+            "decisions": ["We decided to keep JSONL"]
+        }"#).unwrap();
+        let log_path = project_dir.join(decisions::DECISIONS_FILE);
+        fs::write(&log_path, r#"{"date":"2026-02-02T09:00:00Z","title":"Use FTS5","why":"no extra service"}
+"#).unwrap();
+
+        full_rebuild(&conn, dir.path()).unwrap();
+        let rows = |conn: &Connection| -> Vec<(String, String)> {
+            conn.prepare("SELECT title, source FROM decisions ORDER BY date")
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap()
+                .map(|r| r.unwrap())
+                .collect()
+        };
+        assert_eq!(rows(&conn), vec![
+            ("We decided to keep JSONL".to_string(), "transcript".to_string()),
+            ("Use FTS5".to_string(), "manual".to_string()),
+        ]);
+
+        // Appending to the log re-imports it without touching transcript decisions.
+        fs::write(&log_path, format!("{}{}\n",
+            fs::read_to_string(&log_path).unwrap(),
+            r#"{"date":"2026-02-03T09:00:00Z","title":"Drop Tauri"}"#,
+        )).unwrap();
+        incremental_update(&conn, &log_path, dir.path()).unwrap();
+        assert_eq!(rows(&conn).len(), 3);
+    }
+
+    #[test]
+    fn test_search_index_follows_rebuild_and_incremental_update() {
+        let (dir, conn) = setup_test_env();
+        let project_dir = dir.path().join("projects/test-project");
+        let session_path = project_dir.join("sessions/ses_fts.json");
+        fs::write(&session_path, r#"{
+            "schema_version": 1,
+            "id": "ses_fts",
+            "project_id": "proj_test",
+            "machine": "macbook",
+            "started_at": "2026-02-01T10:00:00Z",
+            "summary": "Fixed the OAuth refresh bug",
+            "next_steps": "Add retry tests",
+            "transcript_highlights": ["token expiry race"]
+        }"#).unwrap();
+        fs::write(project_dir.join("roadmap.md"), "## Phase 1\n- [ ] Billing export\n").unwrap();
+
+        let hits = |q: &str| -> i64 {
+            conn.query_row(
+                "SELECT COUNT(*) FROM search_index WHERE search_index MATCH ?1",
+                params![q],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+
+        full_rebuild(&conn, dir.path()).unwrap();
+        full_rebuild(&conn, dir.path()).unwrap();
+        assert_eq!(hits("oauth"), 1);
+        assert_eq!(hits("retry"), 1);
+        assert_eq!(hits("expiry"), 1);
+        assert_eq!(hits("billing"), 1);
+
+        // Editing the session replaces its rows rather than appending
+        let updated = fs::read_to_string(&session_path).unwrap().replace("OAuth", "SAML");
+        fs::write(&session_path, updated).unwrap();
+        incremental_update(&conn, &session_path, dir.path()).unwrap();
+        assert_eq!(hits("oauth"), 0);
+        assert_eq!(hits("saml"), 1);
+
+        fs::write(project_dir.join("roadmap.md"), "## Phase 1\n- [ ] Invoice export\n").unwrap();
+        incremental_update(&conn, &project_dir.join("roadmap.md"), dir.path()).unwrap();
+        assert_eq!(hits("billing"), 0);
+        assert_eq!(hits("invoice"), 1);
+    }
 }
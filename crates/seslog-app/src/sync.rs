@@ -3,7 +3,6 @@
 // Uses seslog_core::git_ops for pull/push, adds status & repo init helpers
 
 use std::path::Path;
-use std::process::Command;
 
 pub use seslog_core::git_ops::SyncResult;
 
@@ -23,6 +22,11 @@ pub fn sync_on_startup(repo_path: &Path) -> SyncResult {
                 SyncResult::Offline(msg) => tracing::warn!("Network error during pull: {}", msg),
                 SyncResult::Conflict(msg) => tracing::warn!("Sync conflict: {}", msg),
             }
+            match seslog_core::git_ops::retry_pending_push(repo_path) {
+                Ok(Some(retry)) => tracing::info!("Retried pending push: {:?}", retry),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Pending push retry failed: {}", e),
+            }
             result
         }
         Err(e) => {
@@ -69,37 +73,22 @@ pub fn get_sync_status(repo_path: &Path) -> SyncStatus {
             has_remote: false,
             last_sync: None,
             pending_changes: false,
+            pending_push: None,
         };
     }
 
     let has_remote = seslog_core::git_ops::has_remote(repo_path);
 
-    let pending_changes = Command::new("git")
-        .args(["status", "--porcelain"])
-        .current_dir(repo_path)
-        .output()
-        .map(|o| !String::from_utf8_lossy(&o.stdout).trim().is_empty())
-        .unwrap_or(false);
-
-    let last_sync = Command::new("git")
-        .args(["log", "-1", "--format=%ci"])
-        .current_dir(repo_path)
-        .output()
-        .ok()
-        .and_then(|o| {
-            if o.status.success() {
-                let s = String::from_utf8_lossy(&o.stdout).trim().to_string();
-                if s.is_empty() { None } else { Some(s) }
-            } else {
-                None
-            }
-        });
+    let pending_changes = seslog_core::git_ops::has_uncommitted_changes(repo_path);
+    let last_sync = seslog_core::git_ops::last_commit_time(repo_path)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S %z").to_string());
 
     SyncStatus {
         is_repo: true,
         has_remote,
         last_sync,
         pending_changes,
+        pending_push: seslog_core::sync_queue::load_pending(repo_path),
     }
 }
 
@@ -110,42 +99,18 @@ pub struct SyncStatus {
     pub has_remote: bool,
     pub last_sync: Option<String>,
     pub pending_changes: bool,
+    /// A push that failed (e.g. offline) and is being retried with backoff.
+    pub pending_push: Option<seslog_core::sync_queue::PendingPush>,
 }
 
 /// Initialize git repo if not already one.
 pub fn init_repo(repo_path: &Path) -> Result<(), String> {
-    if seslog_core::git_ops::is_git_repo(repo_path) {
-        return Ok(());
-    }
-
-    Command::new("git")
-        .args(["init"])
-        .current_dir(repo_path)
-        .output()
-        .map_err(|e| e.to_string())
-        .and_then(|output| {
-            if output.status.success() {
-                Ok(())
-            } else {
-                Err(String::from_utf8_lossy(&output.stderr).to_string())
-            }
-        })
+    seslog_core::git_ops::init_repo(repo_path).map_err(|e| e.to_string())
 }
 
 /// Add a remote origin to the repo.
 pub fn add_remote(repo_path: &Path, remote_url: &str) -> Result<(), String> {
-    Command::new("git")
-        .args(["remote", "add", "origin", remote_url])
-        .current_dir(repo_path)
-        .output()
-        .map_err(|e| e.to_string())
-        .and_then(|output| {
-            if output.status.success() {
-                Ok(())
-            } else {
-                Err(String::from_utf8_lossy(&output.stderr).to_string())
-            }
-        })
+    seslog_core::git_ops::add_remote(repo_path, "origin", remote_url).map_err(|e| e.to_string())
 }
 
 /// Machine profile for sync identification.
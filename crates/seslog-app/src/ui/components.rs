@@ -1,4 +1,5 @@
 use dioxus::prelude::*;
+use crate::commands::{ModelUsageResponse, TurnUsageResponse};
 use crate::state::{Toast, ToastKind, View};
 
 pub fn format_minutes(total: i64) -> String {
@@ -35,6 +36,17 @@ pub fn format_cost(cost: f64) -> String {
     }
 }
 
+/// Compact token count: `950`, `12.3K`, `4.1M`.
+pub fn format_tokens(count: i64) -> String {
+    if count >= 1_000_000 {
+        format!("{:.1}M", count as f64 / 1_000_000.0)
+    } else if count >= 1_000 {
+        format!("{:.1}K", count as f64 / 1_000.0)
+    } else {
+        format!("{}", count)
+    }
+}
+
 /// Get progress bar color based on percentage
 fn progress_color(percent: f64) -> &'static str {
     if percent <= 33.0 {
@@ -101,6 +113,109 @@ pub fn GlassPanel(children: Element) -> Element {
     }
 }
 
+/// Number of distinct `usage-color-N` classes; models beyond it reuse colors.
+const USAGE_COLORS: usize = 6;
+
+/// Share of tokens per model as one stacked bar, with a legend of tokens, turns and cost.
+#[component]
+pub fn ModelUsageBar(models: Vec<ModelUsageResponse>) -> Element {
+    let total: i64 = models.iter().map(|m| m.total_tokens()).sum();
+    if total == 0 {
+        return rsx! {};
+    }
+    let segments: Vec<(String, f64, String)> = models
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let cost = m.cost_usd.map(|c| format!(" \u{b7} {}", format_cost(c))).unwrap_or_default();
+            let label = format!("{} \u{b7} {} tokens \u{b7} {} turn(s){}", m.model, format_tokens(m.total_tokens()), m.turns, cost);
+            (format!("usage-color-{}", i % USAGE_COLORS), m.total_tokens() as f64 * 100.0 / total as f64, label)
+        })
+        .collect();
+    rsx! {
+        div { class: "usage-bar",
+            for (class, percent, label) in segments.iter() {
+                div { class: "usage-segment {class}", style: "width: {percent}%;", title: "{label}" }
+            }
+        }
+        div { class: "usage-legend",
+            for (class, _, label) in segments.iter() {
+                span { class: "usage-legend-item",
+                    span { class: "usage-swatch {class}" }
+                    "{label}"
+                }
+            }
+        }
+    }
+}
+
+/// One column per assistant turn, stacked by token category, with a strip colored by
+/// model (in the order of `models`). Subagent turns are dimmed. Folded rows of older
+/// turns would dwarf the rest, so they are only counted below the chart.
+#[component]
+pub fn TurnUsageChart(turns: Vec<TurnUsageResponse>, models: Vec<String>) -> Element {
+    let folded: i64 = turns.iter().filter(|t| t.turns > 1).map(|t| t.turns).sum();
+    let turns: Vec<TurnUsageResponse> = turns.into_iter().filter(|t| t.turns <= 1).collect();
+    let max = turns
+        .iter()
+        .map(|t| t.input_tokens + t.output_tokens + t.cache_creation_tokens + t.cache_read_tokens)
+        .max()
+        .unwrap_or(0);
+    if max == 0 {
+        return rsx! {};
+    }
+    let columns: Vec<(&'static str, String, String, Vec<(&'static str, f64)>)> = turns
+        .iter()
+        .map(|t| {
+            let total = t.input_tokens + t.output_tokens + t.cache_creation_tokens + t.cache_read_tokens;
+            let model_class = models
+                .iter()
+                .position(|m| *m == t.model)
+                .map(|i| format!("usage-color-{}", i % USAGE_COLORS))
+                .unwrap_or_default();
+            let cost = t.cost_usd.map(|c| format!(", {}", format_cost(c))).unwrap_or_default();
+            let agent = t.agent_id.as_ref().map(|a| format!(" \u{b7} subagent {}", a)).unwrap_or_default();
+            let title = format!(
+                "Turn {} \u{b7} {}{}\n{} tokens (input {}, output {}, cache write {}, cache read {}){}",
+                t.turn + 1, t.model, agent, total, t.input_tokens, t.output_tokens,
+                t.cache_creation_tokens, t.cache_read_tokens, cost,
+            );
+            let column_class = if t.agent_id.is_some() { "turn-column subagent" } else { "turn-column" };
+            let scale = |n: i64| n as f64 * 100.0 / max as f64;
+            let stack = vec![
+                ("usage-cat-cache-read", scale(t.cache_read_tokens)),
+                ("usage-cat-cache-write", scale(t.cache_creation_tokens)),
+                ("usage-cat-input", scale(t.input_tokens)),
+                ("usage-cat-output", scale(t.output_tokens)),
+            ];
+            (column_class, model_class, title, stack)
+        })
+        .collect();
+    rsx! {
+        div { class: "turn-chart",
+            for (column_class, model_class, title, stack) in columns.iter() {
+                div { class: "{column_class}", title: "{title}",
+                    div { class: "turn-stack",
+                        for (class, height) in stack.iter() {
+                            div { class: "turn-segment {class}", style: "height: {height}%;" }
+                        }
+                    }
+                    div { class: "turn-model {model_class}" }
+                }
+            }
+        }
+        if folded > 0 {
+            div { class: "turn-chart-note", "{folded} earlier turns are kept as totals only" }
+        }
+        div { class: "usage-legend",
+            span { class: "usage-legend-item", span { class: "usage-swatch usage-cat-input" } "Input" }
+            span { class: "usage-legend-item", span { class: "usage-swatch usage-cat-output" } "Output" }
+            span { class: "usage-legend-item", span { class: "usage-swatch usage-cat-cache-write" } "Cache write" }
+            span { class: "usage-legend-item", span { class: "usage-swatch usage-cat-cache-read" } "Cache read" }
+        }
+    }
+}
+
 // Skeleton loading components
 
 #[component]
@@ -1,7 +1,7 @@
 use dioxus::prelude::*;
-use crate::commands::{self, OverviewRow};
+use crate::commands::{self, OverviewRow, TimeMetric};
 use crate::state::View;
-use super::components::{CostBadge, EmptyState, OverviewSkeleton, ProgressBar, format_minutes};
+use super::components::{CostBadge, EmptyState, GlassPanel, ModelUsageBar, OverviewSkeleton, ProgressBar, format_minutes};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum SortField {
@@ -24,6 +24,7 @@ pub fn OverviewPage() -> Element {
     let refresh: Signal<u64> = use_context();
 
     let mut include_archived = use_signal(|| false);
+    let mut time_metric = use_signal(TimeMetric::default);
     let mut sort_field = use_signal(|| SortField::LastActivity);
     let mut sort_dir = use_signal(|| SortDir::Desc);
 
@@ -31,10 +32,13 @@ pub fn OverviewPage() -> Element {
         refresh(); // track refresh dependency
         let pool = crate::get_db_pool();
         let archived = include_archived();
-        commands::get_overview_inner(pool, archived).unwrap_or_default()
+        let metric = time_metric();
+        let rows = commands::get_overview_inner(pool, archived, metric).unwrap_or_default();
+        let models = commands::get_model_usage_inner(pool, archived).unwrap_or_default();
+        (rows, models)
     });
 
-    let mut rows = match resource() {
+    let (mut rows, model_usage) = match resource() {
         None => return rsx! { OverviewSkeleton {} },
         Some(r) => r,
     };
@@ -86,7 +90,7 @@ pub fn OverviewPage() -> Element {
             }
 
             // Controls
-            div { style: "margin-bottom: 16px;",
+            div { style: "margin-bottom: 16px; display: flex; align-items: center; gap: 24px;",
                 label { style: "display: flex; align-items: center; gap: 8px; color: var(--text-secondary); cursor: pointer;",
                     input {
                         r#type: "checkbox",
@@ -97,6 +101,27 @@ pub fn OverviewPage() -> Element {
                     }
                     "Include Archived"
                 }
+                label { style: "display: flex; align-items: center; gap: 8px; color: var(--text-secondary);",
+                    "Time"
+                    select {
+                        class: "form-select",
+                        onchange: move |evt| {
+                            time_metric.set(if evt.value() == "active" { TimeMetric::Active } else { TimeMetric::WallClock });
+                        },
+                        option { value: "wall_clock", selected: time_metric() == TimeMetric::WallClock, "Wall Clock" }
+                        option { value: "active", selected: time_metric() == TimeMetric::Active, "Active" }
+                    }
+                }
+            }
+
+            // Usage by model across the listed projects
+            if !model_usage.is_empty() {
+                div { style: "margin-bottom: 16px;",
+                    GlassPanel {
+                        h3 { class: "section-header", "Usage by Model" }
+                        ModelUsageBar { models: model_usage }
+                    }
+                }
             }
 
             // Table
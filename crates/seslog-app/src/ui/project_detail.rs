@@ -1,6 +1,8 @@
 use dioxus::prelude::*;
 use crate::commands;
 use crate::state::{View, Toast, ToastKind};
+use seslog_core::roadmap::ItemStatus;
+use seslog_core::roadmap_edit::RoadmapEdit;
 use super::components::{Breadcrumb, CostBadge, Crumb, EmptyState, GlassPanel, ProjectDetailSkeleton, ProgressBar, StatusDot, show_toast, format_minutes, format_date};
 
 #[component]
@@ -8,13 +10,16 @@ pub fn ProjectDetail(project_id: String) -> Element {
     let mut current_view: Signal<View> = use_context();
     let refresh: Signal<u64> = use_context();
 
+    let mut branch_filter: Signal<Option<String>> = use_signal(|| None);
+
     let pid = project_id.clone();
     let resource = use_resource(move || {
         let pid = pid.clone();
         async move {
             refresh(); // track refresh dependency
+            let branch = branch_filter();
             let pool = crate::get_db_pool();
-            commands::get_project_detail_inner(pool, pid).ok()
+            commands::get_project_detail_inner(pool, pid, branch.as_deref()).ok()
         }
     });
 
@@ -43,6 +48,9 @@ pub fn ProjectDetail(project_id: String) -> Element {
     let summary = &detail.summary;
     let roadmap = &detail.roadmap;
     let sessions = &detail.recent_sessions;
+    let decisions = &detail.decisions;
+    let branches = detail.branches.clone();
+    let hotspots = detail.hotspots.clone();
 
     let project_name = summary.name.clone();
     let is_active = summary.status == "active";
@@ -70,8 +78,15 @@ pub fn ProjectDetail(project_id: String) -> Element {
             status: item.status.clone(),
             item_id: item.item_id.clone(),
             has_deps: !item.depends_on.is_empty(),
+            ready: item.ready,
+            waiting_on: item.waiting_on.clone(),
         });
     }
+    let graph = build_dependency_graph(&roadmap.items);
+    let critical_text = match roadmap.critical_path_length {
+        1 => "Critical path: 1 step left".to_string(),
+        n => format!("Critical path: {} steps left", n),
+    };
 
     // Count done items for progress display
     let done_count = roadmap.items.iter().filter(|i| i.status == "done").count();
@@ -90,12 +105,32 @@ pub fn ProjectDetail(project_id: String) -> Element {
             summary_text: truncate_summary(&s.summary, 120),
             machine: s.machine.clone(),
             duration: s.duration_minutes.map(format_minutes).unwrap_or_else(|| "N/A".to_string()),
+            branch: s.git_branch.clone(),
             files: s.files_changed,
             cost: s.estimated_cost_usd.filter(|c| *c > 0.0),
         })
         .collect();
 
+    let decision_rows: Vec<DecisionRow> = decisions
+        .iter()
+        .map(|d| DecisionRow {
+            date: d.date.as_deref().map(format_date).unwrap_or_default(),
+            title: d.title.clone(),
+            description: d.description.clone(),
+            from_transcript: d.source == "transcript",
+            session_id: d.session_id.clone(),
+        })
+        .collect();
+
     let has_roadmap = !roadmap.items.is_empty();
+    let mut phases: Vec<String> = Vec::new();
+    for item in &roadmap.items {
+        if let Some(phase) = &item.phase {
+            if !phases.contains(phase) {
+                phases.push(phase.clone());
+            }
+        }
+    }
     let has_warnings = !roadmap.warnings.is_empty();
     let warnings: Vec<String> = roadmap.warnings.clone();
 
@@ -126,32 +161,67 @@ pub fn ProjectDetail(project_id: String) -> Element {
                 // Left column
                 div {
                     // Roadmap section
-                    if has_roadmap {
-                        div { class: "roadmap glass-panel", style: "padding: 24px;",
-                            h3 { class: "section-header", "Roadmap" }
-                            for ri in phase_items.iter() {
-                                RoadmapRow {
-                                    phase_heading: ri.phase_heading.clone(),
-                                    text: ri.text.clone(),
-                                    status: ri.status.clone(),
-                                    item_id: ri.item_id.clone(),
-                                    has_deps: ri.has_deps,
-                                }
+                    div { class: "roadmap glass-panel", style: "padding: 24px;",
+                        h3 { class: "section-header", "Roadmap" }
+                        if !has_roadmap {
+                            p { style: "color: var(--text-muted);", "No roadmap yet. Add a phase or an item below." }
+                        }
+                        for ri in phase_items.iter() {
+                            RoadmapRow {
+                                project_id: project_id.clone(),
+                                phase_heading: ri.phase_heading.clone(),
+                                text: ri.text.clone(),
+                                status: ri.status.clone(),
+                                item_id: ri.item_id.clone(),
+                                has_deps: ri.has_deps,
+                                ready: ri.ready,
+                                waiting_on: ri.waiting_on.clone(),
                             }
-                            if has_warnings {
-                                for warning in warnings.iter() {
-                                    div {
-                                        style: "color: var(--warning-color, #f59e0b); font-size: 13px; margin-top: 8px; padding: 8px; background: rgba(245, 158, 11, 0.1); border-radius: 6px;",
-                                        "\u{26A0} {warning}"
-                                    }
+                        }
+                        if has_warnings {
+                            for warning in warnings.iter() {
+                                div {
+                                    style: "color: var(--warning-color, #f59e0b); font-size: 13px; margin-top: 8px; padding: 8px; background: rgba(245, 158, 11, 0.1); border-radius: 6px;",
+                                    "\u{26A0} {warning}"
                                 }
                             }
                         }
+                        RoadmapAddForm { project_id: project_id.clone(), phases }
+                    }
+
+                    // Dependency graph, only when items declare dependencies
+                    if let Some(graph) = graph {
+                        div { class: "glass-panel", style: "margin-top: 24px; padding: 24px;",
+                            div { style: "display: flex; align-items: center; justify-content: space-between;",
+                                h3 { class: "section-header", "Dependencies" }
+                                span { class: "dependency-critical-label", "{critical_text}" }
+                            }
+                            DependencyGraph { graph }
+                        }
                     }
 
                     // Recent Sessions timeline
                     div { class: "glass-panel", style: "margin-top: 24px; padding: 24px;",
-                        h3 { class: "section-header", "Recent Sessions" }
+                        div { style: "display: flex; align-items: center; justify-content: space-between;",
+                            h3 { class: "section-header", "Recent Sessions" }
+                            if !branches.is_empty() {
+                                select {
+                                    class: "form-select",
+                                    onchange: move |evt| {
+                                        let value = evt.value();
+                                        branch_filter.set(if value.is_empty() { None } else { Some(value) });
+                                    },
+                                    option { value: "", selected: branch_filter().is_none(), "All branches" }
+                                    for b in branches.iter() {
+                                        option {
+                                            value: "{b.branch}",
+                                            selected: branch_filter().as_deref() == Some(b.branch.as_str()),
+                                            "{b.branch} ({b.session_count})"
+                                        }
+                                    }
+                                }
+                            }
+                        }
                         if session_rows.is_empty() {
                             p { style: "color: var(--text-muted);", "No sessions recorded yet." }
                         } else {
@@ -164,6 +234,7 @@ pub fn ProjectDetail(project_id: String) -> Element {
                                         summary_text: sr.summary_text.clone(),
                                         machine: sr.machine.clone(),
                                         duration: sr.duration.clone(),
+                                        branch: sr.branch.clone(),
                                         files: sr.files,
                                         cost: sr.cost,
                                     }
@@ -171,6 +242,48 @@ pub fn ProjectDetail(project_id: String) -> Element {
                             }
                         }
                     }
+
+                    // Decision history timeline
+                    div { class: "glass-panel", style: "margin-top: 24px; padding: 24px;",
+                        h3 { class: "section-header", "Decisions" }
+                        if decision_rows.is_empty() {
+                            p { style: "color: var(--text-muted);",
+                                "No decisions logged yet. Record one with `seslog decision \"title\" --why \"...\"`."
+                            }
+                        } else {
+                            div { class: "timeline",
+                                for dr in decision_rows.iter() {
+                                    DecisionItem {
+                                        project_id: project_id.clone(),
+                                        date: dr.date.clone(),
+                                        title: dr.title.clone(),
+                                        description: dr.description.clone(),
+                                        from_transcript: dr.from_transcript,
+                                        session_id: dr.session_id.clone(),
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // Files Claude works on most
+                    if !hotspots.is_empty() {
+                        div { class: "glass-panel", style: "margin-top: 24px; padding: 24px;",
+                            h3 { class: "section-header", "Hotspot Files" }
+                            div { class: "diff-file-list",
+                                for file in hotspots.iter() {
+                                    div { class: "diff-file-row", title: "Last touched {format_date(&file.last_touched_at)}",
+                                        span { class: "diff-file-path", "{file.path}" }
+                                        span { class: "touch-counts", "{hotspot_sessions(file.sessions)}" }
+                                        span {
+                                            class: if file.modifications > 0 { "touch-counts modified" } else { "touch-counts" },
+                                            "{file.modifications} changes \u{b7} {file.reads} reads"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
                 }
 
                 // Right column
@@ -249,6 +362,122 @@ struct RoadmapItemRow {
     status: String,
     item_id: Option<String>,
     has_deps: bool,
+    ready: bool,
+    waiting_on: Vec<String>,
+}
+
+/// Laid-out dependency graph: one column per dependency depth, edges from dependency to dependent.
+#[derive(Clone, PartialEq)]
+struct GraphLayout {
+    width: usize,
+    height: usize,
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+}
+
+#[derive(Clone, PartialEq)]
+struct GraphNode {
+    x: usize,
+    y: usize,
+    label: String,
+    class: String,
+}
+
+#[derive(Clone, PartialEq)]
+struct GraphEdge {
+    path: String,
+    class: String,
+}
+
+const GRAPH_NODE_WIDTH: usize = 150;
+const GRAPH_NODE_HEIGHT: usize = 32;
+const GRAPH_COLUMN_GAP: usize = 48;
+const GRAPH_ROW_GAP: usize = 12;
+
+/// Lay out the items that take part in a dependency, or `None` if no item has one.
+fn build_dependency_graph(items: &[commands::RoadmapItemResponse]) -> Option<GraphLayout> {
+    let index_of = |id: &str| items.iter().position(|i| i.item_id.as_deref() == Some(id));
+    let mut links: Vec<(usize, usize)> = Vec::new();
+    for (idx, item) in items.iter().enumerate() {
+        for dep in &item.depends_on {
+            if let Some(from) = index_of(dep) {
+                links.push((from, idx));
+            }
+        }
+    }
+    if links.is_empty() {
+        return None;
+    }
+
+    let mut position: Vec<Option<(usize, usize)>> = vec![None; items.len()];
+    let mut rows_in_column: Vec<usize> = Vec::new();
+    for (idx, item) in items.iter().enumerate() {
+        if !links.iter().any(|&(a, b)| a == idx || b == idx) {
+            continue;
+        }
+        if rows_in_column.len() <= item.depth {
+            rows_in_column.resize(item.depth + 1, 0);
+        }
+        let x = item.depth * (GRAPH_NODE_WIDTH + GRAPH_COLUMN_GAP);
+        let y = rows_in_column[item.depth] * (GRAPH_NODE_HEIGHT + GRAPH_ROW_GAP);
+        rows_in_column[item.depth] += 1;
+        position[idx] = Some((x, y));
+    }
+
+    let nodes = items.iter().zip(&position)
+        .filter_map(|(item, pos)| {
+            let (x, y) = (*pos)?;
+            let state = if item.status == "done" {
+                "done"
+            } else if !item.waiting_on.is_empty() {
+                "waiting"
+            } else if item.ready {
+                "ready"
+            } else {
+                item.status.as_str()
+            };
+            let critical = if item.on_critical_path { " critical" } else { "" };
+            Some(GraphNode {
+                x,
+                y,
+                label: graph_label(&item.item_text),
+                class: format!("dependency-node {}{}", state, critical),
+            })
+        })
+        .collect();
+    let edges = links.iter()
+        .filter_map(|&(from, to)| {
+            let (fx, fy) = position[from]?;
+            let (tx, ty) = position[to]?;
+            let (x1, y1) = (fx + GRAPH_NODE_WIDTH, fy + GRAPH_NODE_HEIGHT / 2);
+            let (x2, y2) = (tx, ty + GRAPH_NODE_HEIGHT / 2);
+            let bend = (x1 + x2) / 2;
+            let critical = items[from].on_critical_path && items[to].on_critical_path;
+            Some(GraphEdge {
+                path: format!("M {} {} C {} {}, {} {}, {} {}", x1, y1, bend, y1, bend, y2, x2, y2),
+                class: if critical { "dependency-edge critical".into() } else { "dependency-edge".into() },
+            })
+        })
+        .collect();
+
+    let columns = rows_in_column.len();
+    let rows = rows_in_column.iter().copied().max().unwrap_or(0);
+    Some(GraphLayout {
+        width: columns * GRAPH_NODE_WIDTH + columns.saturating_sub(1) * GRAPH_COLUMN_GAP,
+        height: rows * GRAPH_NODE_HEIGHT + rows.saturating_sub(1) * GRAPH_ROW_GAP,
+        nodes,
+        edges,
+    })
+}
+
+/// Node label, cut on a character boundary to fit the node width.
+fn graph_label(text: &str) -> String {
+    const MAX_CHARS: usize = 20;
+    if text.chars().count() > MAX_CHARS {
+        format!("{}...", text.chars().take(MAX_CHARS - 3).collect::<String>())
+    } else {
+        text.to_string()
+    }
 }
 
 struct TimelineRow {
@@ -258,39 +487,242 @@ struct TimelineRow {
     summary_text: String,
     machine: String,
     duration: String,
+    branch: Option<String>,
     files: i64,
     cost: Option<f64>,
 }
 
+struct DecisionRow {
+    date: String,
+    title: String,
+    description: String,
+    from_transcript: bool,
+    session_id: Option<String>,
+}
+
 // Sub-components
 
+/// Statuses offered in the roadmap row select, in file-marker order.
+const ROADMAP_STATUSES: [ItemStatus; 5] = [
+    ItemStatus::Pending,
+    ItemStatus::Active,
+    ItemStatus::Done,
+    ItemStatus::Suspended,
+    ItemStatus::Blocked,
+];
+
+/// Apply a roadmap edit to the project's roadmap.md; rejected edits surface as a toast.
+fn apply_roadmap_edit(toasts: &mut Signal<Vec<Toast>>, project_id: &str, edit: RoadmapEdit) -> bool {
+    let pool = crate::get_db_pool();
+    match commands::edit_roadmap_inner(pool, project_id, &edit) {
+        Ok(_) => {
+            crate::trigger_refresh();
+            true
+        }
+        Err(e) => {
+            show_toast(toasts, format!("Error: {}", e), ToastKind::Error);
+            false
+        }
+    }
+}
+
 #[component]
 fn RoadmapRow(
+    project_id: String,
     phase_heading: Option<String>,
     text: String,
     status: String,
     item_id: Option<String>,
     has_deps: bool,
+    ready: bool,
+    waiting_on: Vec<String>,
 ) -> Element {
+    let mut toasts: Signal<Vec<Toast>> = use_context();
+    let mut editing: Signal<Option<String>> = use_signal(|| None);
+
     let is_done = status == "done";
-    let indent_class = if has_deps { "roadmap-item dependency-indent" } else { "roadmap-item" };
+    let is_waiting = !is_done && !waiting_on.is_empty();
+    let indent_class = match (has_deps, is_waiting) {
+        (true, true) => "roadmap-item dependency-indent waiting",
+        (true, false) => "roadmap-item dependency-indent",
+        (false, true) => "roadmap-item waiting",
+        (false, false) => "roadmap-item",
+    };
+    let waiting_text = format!("waiting on {}", waiting_on.join(", "));
     let checkbox_class = if is_done { "roadmap-checkbox checked" } else { "roadmap-checkbox" };
     let text_class = if is_done { "roadmap-text done" } else { "roadmap-text" };
-    let id_badge = item_id.map(|id| format!("[{}]", id)).unwrap_or_default();
+    let id_badge = item_id.as_ref().map(|id| format!("[{}]", id)).unwrap_or_default();
+
+    // Items are addressed by id when they have one, otherwise by their exact text.
+    let key = item_id.clone().unwrap_or_else(|| text.clone());
+    let (key_status, key_rename, key_remove, key_toggle) = (key.clone(), key.clone(), key.clone(), key);
+    let (pid_status, pid_rename, pid_remove, pid_toggle) = (project_id.clone(), project_id.clone(), project_id.clone(), project_id);
+    let text_for_edit = text.clone();
+    let toggle_title = if is_done { "Mark as pending" } else { "Mark as done" };
 
     rsx! {
         if let Some(heading) = phase_heading {
             h4 { class: "roadmap-phase-heading", "{heading}" }
         }
         div { class: "{indent_class}",
-            span { class: "{checkbox_class}" }
-            span { class: "{text_class}", "{text}" }
+            span {
+                class: "{checkbox_class}",
+                title: toggle_title,
+                onclick: move |_| {
+                    let status = if is_done { ItemStatus::Pending } else { ItemStatus::Done };
+                    apply_roadmap_edit(&mut toasts, &pid_toggle, RoadmapEdit::SetStatus { item: key_toggle.clone(), status });
+                },
+            }
+            if let Some(draft) = editing() {
+                input {
+                    class: "roadmap-input",
+                    r#type: "text",
+                    value: "{draft}",
+                    autofocus: true,
+                    oninput: move |evt| editing.set(Some(evt.value())),
+                    onkeydown: move |evt: KeyboardEvent| {
+                        evt.stop_propagation();
+                        match evt.key() {
+                            Key::Enter => {
+                                let new_text = editing().unwrap_or_default();
+                                let edit = RoadmapEdit::Rename { item: key_rename.clone(), text: new_text };
+                                if apply_roadmap_edit(&mut toasts, &pid_rename, edit) {
+                                    editing.set(None);
+                                }
+                            }
+                            Key::Escape => editing.set(None),
+                            _ => {}
+                        }
+                    },
+                }
+            } else {
+                span {
+                    class: "{text_class}",
+                    title: "Double-click to rename",
+                    ondoubleclick: move |_| editing.set(Some(text_for_edit.clone())),
+                    "{text}"
+                }
+            }
             if !id_badge.is_empty() {
                 span {
                     style: "font-size: 11px; color: var(--text-muted); background: var(--bg-tertiary); padding: 2px 6px; border-radius: 4px; margin-left: 8px; font-family: monospace;",
                     "{id_badge}"
                 }
             }
+            if is_waiting {
+                span { class: "roadmap-badge waiting", "{waiting_text}" }
+            } else if ready && has_deps {
+                span { class: "roadmap-badge ready", "ready" }
+            }
+            select {
+                class: "roadmap-status-select",
+                onchange: move |evt| {
+                    if let Ok(status) = evt.value().parse::<ItemStatus>() {
+                        apply_roadmap_edit(&mut toasts, &pid_status, RoadmapEdit::SetStatus { item: key_status.clone(), status });
+                    }
+                },
+                for s in ROADMAP_STATUSES.iter() {
+                    option { value: s.as_str(), selected: status == s.as_str(), "{s.as_str()}" }
+                }
+            }
+            button {
+                class: "roadmap-remove",
+                title: "Remove item",
+                onclick: move |_| {
+                    apply_roadmap_edit(&mut toasts, &pid_remove, RoadmapEdit::Remove { item: key_remove.clone() });
+                },
+                "\u{2715}"
+            }
+        }
+    }
+}
+
+#[component]
+fn DependencyGraph(graph: GraphLayout) -> Element {
+    let view_box = format!("0 0 {} {}", graph.width, graph.height);
+    let label_x = GRAPH_NODE_WIDTH / 2;
+    let label_y = GRAPH_NODE_HEIGHT / 2 + 4;
+
+    rsx! {
+        div { class: "dependency-graph",
+            svg {
+                width: "{graph.width}",
+                height: "{graph.height}",
+                view_box: "{view_box}",
+                for edge in graph.edges.iter() {
+                    path { class: "{edge.class}", d: "{edge.path}" }
+                }
+                for node in graph.nodes.iter() {
+                    g { class: "{node.class}", transform: "translate({node.x}, {node.y})",
+                        rect { width: "{GRAPH_NODE_WIDTH}", height: "{GRAPH_NODE_HEIGHT}", rx: "6" }
+                        text { x: "{label_x}", y: "{label_y}", text_anchor: "middle", "{node.label}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Add the drafted item to the selected phase (or the end of the roadmap), clearing the draft on success.
+fn add_roadmap_item(toasts: &mut Signal<Vec<Toast>>, project_id: &str, mut draft: Signal<String>, phase: Signal<String>) {
+    let selected = phase();
+    let edit = RoadmapEdit::AddItem {
+        text: draft(),
+        status: ItemStatus::Pending,
+        phase: if selected.is_empty() { None } else { Some(selected) },
+        id: None,
+        depends_on: Vec::new(),
+        after: None,
+    };
+    if apply_roadmap_edit(toasts, project_id, edit) {
+        draft.set(String::new());
+    }
+}
+
+/// Add an item (to the chosen phase, or the end of the roadmap) or a new phase heading.
+#[component]
+fn RoadmapAddForm(project_id: String, phases: Vec<String>) -> Element {
+    let mut toasts: Signal<Vec<Toast>> = use_context();
+    let mut draft: Signal<String> = use_signal(String::new);
+    let mut phase: Signal<String> = use_signal(String::new);
+    let (pid_enter, pid_click, pid_phase) = (project_id.clone(), project_id.clone(), project_id);
+
+    rsx! {
+        div { class: "roadmap-add",
+            input {
+                class: "roadmap-input",
+                r#type: "text",
+                placeholder: "New item or phase name",
+                value: "{draft}",
+                oninput: move |evt| draft.set(evt.value()),
+                onkeydown: move |evt: KeyboardEvent| {
+                    evt.stop_propagation();
+                    if evt.key() == Key::Enter {
+                        add_roadmap_item(&mut toasts, &pid_enter, draft, phase);
+                    }
+                },
+            }
+            if !phases.is_empty() {
+                select {
+                    class: "roadmap-status-select",
+                    onchange: move |evt| phase.set(evt.value()),
+                    option { value: "", selected: phase().is_empty(), "End of roadmap" }
+                    for p in phases.iter() {
+                        option { value: "{p}", selected: phase() == *p, "{p}" }
+                    }
+                }
+            }
+            button { class: "btn btn-secondary", onclick: move |_| add_roadmap_item(&mut toasts, &pid_click, draft, phase), "Add item" }
+            button {
+                class: "btn btn-secondary",
+                onclick: move |_| {
+                    let edit = RoadmapEdit::AddPhase { name: draft() };
+                    if apply_roadmap_edit(&mut toasts, &pid_phase, edit) {
+                        draft.set(String::new());
+                    }
+                },
+                "Add phase"
+            }
         }
     }
 }
@@ -303,6 +735,7 @@ fn TimelineItem(
     summary_text: String,
     machine: String,
     duration: String,
+    branch: Option<String>,
     files: i64,
     cost: Option<f64>,
 ) -> Element {
@@ -326,6 +759,9 @@ fn TimelineItem(
                     style: "display: flex; gap: 12px; font-size: 12px; color: var(--text-muted); margin-top: 4px;",
                     span { "{machine}" }
                     span { "{duration}" }
+                    if let Some(b) = branch {
+                        span { class: "timeline-branch", "{b}" }
+                    }
                     span { "{files_text}" }
                     if let Some(c) = cost {
                         CostBadge { cost: c }
@@ -336,6 +772,45 @@ fn TimelineItem(
     }
 }
 
+#[component]
+fn DecisionItem(
+    project_id: String,
+    date: String,
+    title: String,
+    description: String,
+    from_transcript: bool,
+    session_id: Option<String>,
+) -> Element {
+    let mut current_view: Signal<View> = use_context();
+    let item_class = if session_id.is_some() { "timeline-item decision-item linked" } else { "timeline-item decision-item" };
+    let source_label = if from_transcript { "from transcript" } else { "logged" };
+
+    rsx! {
+        div {
+            class: "{item_class}",
+            onclick: move |_| {
+                if let Some(sid) = session_id.clone() {
+                    current_view.set(View::Session {
+                        project_id: project_id.clone(),
+                        session_id: sid,
+                    });
+                }
+            },
+            div { class: "timeline-dot decision-dot" }
+            div { class: "timeline-date", "{date}" }
+            div { class: "timeline-content",
+                p { class: "timeline-summary", "{title}" }
+                if !description.is_empty() {
+                    p { class: "decision-why", "{description}" }
+                }
+                div { class: "timeline-meta",
+                    span { class: "decision-source", "{source_label}" }
+                }
+            }
+        }
+    }
+}
+
 #[component]
 fn StatItem(label: String, value: String) -> Element {
     rsx! {
@@ -354,3 +829,10 @@ fn truncate_summary(text: &str, max_len: usize) -> String {
         first_line.to_string()
     }
 }
+
+fn hotspot_sessions(count: i64) -> String {
+    match count {
+        1 => "1 session".to_string(),
+        n => format!("{} sessions", n),
+    }
+}
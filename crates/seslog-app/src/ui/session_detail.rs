@@ -1,7 +1,10 @@
 use dioxus::prelude::*;
 use crate::commands;
 use crate::state::View;
-use super::components::{Breadcrumb, CostBadge, Crumb, EmptyState, GlassPanel, SessionDetailSkeleton, format_minutes, format_date};
+use super::components::{
+    Breadcrumb, CostBadge, Crumb, EmptyState, GlassPanel, ModelUsageBar, SessionDetailSkeleton, TurnUsageChart,
+    format_cost, format_date, format_minutes, format_tokens,
+};
 
 #[component]
 pub fn SessionDetail(project_id: String, session_id: String) -> Element {
@@ -58,7 +61,13 @@ pub fn SessionDetail(project_id: String, session_id: String) -> Element {
         .duration_minutes
         .map(format_minutes)
         .unwrap_or_else(|| "N/A".to_string());
+    let active = session
+        .active_minutes
+        .map(format_minutes)
+        .unwrap_or_else(|| "N/A".to_string());
     let files = session.files_changed;
+    let branch = session.git_branch.clone().unwrap_or_else(|| "N/A".to_string());
+    let worktree = session.worktree.clone();
     let model = session.model.clone().unwrap_or_else(|| "Unknown".to_string());
     let recovered = session.recovered;
     let summary_text = session.summary.clone();
@@ -66,6 +75,19 @@ pub fn SessionDetail(project_id: String, session_id: String) -> Element {
     let highlights = session.transcript_highlights.clone();
     let token_count = session.token_count;
     let cost = session.estimated_cost_usd;
+    let token_breakdown = token_breakdown(&session);
+    let model_usage = session.model_usage.clone();
+    let model_names: Vec<String> = model_usage.iter().map(|m| m.model.clone()).collect();
+    let turn_usage = session.turn_usage.clone();
+    let turn_count: i64 = turn_usage.iter().map(|t| t.turns).sum();
+    let changed_files = session.changed_files.clone();
+    let tool_stats = session.tool_stats.clone();
+    let touched_files = session.touched_files.clone();
+    let subagents = session.subagents.clone();
+    let subagent_count = subagents.len();
+    let delegated_line = format_delegated_share(&session);
+    let diff_line = format_diff_line(session.insertions, session.deletions, changed_files.len());
+    let range_line = format_range(session.git_base.as_deref(), session.git_head.as_deref(), session.git_branch.as_deref());
 
     let pid_for_breadcrumb = project_id.clone();
     let breadcrumbs = vec![
@@ -89,7 +111,12 @@ pub fn SessionDetail(project_id: String, session_id: String) -> Element {
                 MetaCard { label: "Date".to_string(), value: date.clone() }
                 MetaCard { label: "Machine".to_string(), value: machine }
                 MetaCard { label: "Duration".to_string(), value: duration }
+                MetaCard { label: "Active Time".to_string(), value: active }
                 MetaCard { label: "Files Changed".to_string(), value: format!("{}", files) }
+                MetaCard { label: "Branch".to_string(), value: branch }
+                if let Some(w) = worktree {
+                    MetaCard { label: "Worktree".to_string(), value: w }
+                }
                 MetaCard { label: "Model".to_string(), value: model.clone() }
                 MetaCard {
                     label: "Recovered".to_string(),
@@ -108,6 +135,12 @@ pub fn SessionDetail(project_id: String, session_id: String) -> Element {
                                 div { class: "cost-token-value", "{format_tokens(tokens)}" }
                             }
                         }
+                        for (label, count) in token_breakdown {
+                            div {
+                                div { class: "cost-token-label", "{label}" }
+                                div { class: "cost-breakdown-value", "{format_tokens(count)}" }
+                            }
+                        }
                         if let Some(c) = cost {
                             div {
                                 div { class: "cost-token-label", "Estimated Cost" }
@@ -124,6 +157,18 @@ pub fn SessionDetail(project_id: String, session_id: String) -> Element {
                 }
             }
 
+            // Usage breakdown
+            if !model_usage.is_empty() {
+                GlassPanel {
+                    h3 { class: "section-header", "Usage by Model" }
+                    ModelUsageBar { models: model_usage }
+                    if turn_count > 1 {
+                        div { class: "cost-token-label", style: "margin-top: 16px;", "Per Turn ({turn_count})" }
+                        TurnUsageChart { turns: turn_usage, models: model_names }
+                    }
+                }
+            }
+
             // Summary section
             if !summary_text.is_empty() {
                 div { class: "section-gap",
@@ -148,6 +193,78 @@ pub fn SessionDetail(project_id: String, session_id: String) -> Element {
                 }
             }
 
+            // Changes section
+            if !changed_files.is_empty() {
+                div { class: "section-gap-sm",
+                    GlassPanel {
+                        h3 { class: "section-header", "Changes" }
+                        div { class: "diff-summary",
+                            span { "{diff_line}" }
+                            if let Some(range) = range_line {
+                                span { class: "diff-range", "{range}" }
+                            }
+                        }
+                        div { class: "diff-file-list",
+                            for file in changed_files.iter() {
+                                div { class: "diff-file-row",
+                                    span { class: "diff-file-status {file.status}", "{file.status}" }
+                                    span { class: "diff-file-path", "{file.path}" }
+                                    span { class: "diff-added", "+{file.insertions}" }
+                                    span { class: "diff-removed", "-{file.deletions}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Tool calls
+            if !tool_stats.is_empty() {
+                div { class: "section-gap-sm",
+                    GlassPanel {
+                        h3 { class: "section-header", "Tools" }
+                        ToolStatList { tools: tool_stats }
+                    }
+                }
+            }
+
+            // Work delegated to subagents, as nested sub-sessions
+            if !subagents.is_empty() {
+                div { class: "section-gap-sm",
+                    GlassPanel {
+                        h3 { class: "section-header", "Subagents ({subagent_count})" }
+                        if let Some(line) = delegated_line {
+                            p { class: "subagent-share", "{line}" }
+                        }
+                        div { class: "subagent-list",
+                            for subagent in subagents.iter() {
+                                SubagentCard { subagent: subagent.clone() }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Files Claude read or modified
+            if !touched_files.is_empty() {
+                div { class: "section-gap-sm",
+                    GlassPanel {
+                        h3 { class: "section-header", "Files Touched by Claude" }
+                        div { class: "diff-file-list",
+                            for file in touched_files.iter() {
+                                div { class: "diff-file-row",
+                                    span { class: "diff-file-path", title: "{file.path}", "{file.path}" }
+                                    span {
+                                        class: if file.edits + file.writes > 0 { "touch-counts modified" } else { "touch-counts" },
+                                        "{format_touch_counts(file.reads, file.edits, file.writes)}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
             // Highlights section
             if !highlights.is_empty() {
                 div { class: "section-gap-sm",
@@ -175,12 +292,120 @@ fn MetaCard(label: String, value: String) -> Element {
     }
 }
 
-fn format_tokens(count: i64) -> String {
-    if count >= 1_000_000 {
-        format!("{:.1}M", count as f64 / 1_000_000.0)
-    } else if count >= 1_000 {
-        format!("{:.1}K", count as f64 / 1_000.0)
-    } else {
-        format!("{}", count)
+#[component]
+fn ToolStatList(tools: Vec<commands::ToolStatsResponse>) -> Element {
+    rsx! {
+        div { class: "tool-stat-list",
+            for tool in tools.iter() {
+                span { class: "tool-stat",
+                    "{tool.tool}"
+                    span { class: "tool-stat-calls", "\u{d7}{tool.calls}" }
+                    if tool.errors > 0 {
+                        span { class: "tool-stat-errors", "{tool.errors} failed" }
+                    }
+                }
+            }
+        }
     }
 }
+
+#[component]
+fn SubagentCard(subagent: commands::SubagentResponse) -> Element {
+    let title = if subagent.description.is_empty() { subagent.agent_id.clone() } else { subagent.description.clone() };
+    let mut meta = Vec::new();
+    if let Some(model) = &subagent.model {
+        meta.push(model.clone());
+    }
+    if let Some(minutes) = span_minutes(subagent.started_at.as_deref(), subagent.ended_at.as_deref()) {
+        meta.push(format_minutes(minutes));
+    }
+    meta.push(format!("{} turn(s)", subagent.turns));
+    meta.push(format!("{} tokens", format_tokens(subagent.total_tokens())));
+    let meta = meta.join(" \u{b7} ");
+
+    rsx! {
+        div { class: "subagent-card",
+            div { class: "subagent-header",
+                if let Some(agent_type) = &subagent.agent_type {
+                    span { class: "subagent-type", "{agent_type}" }
+                }
+                span { class: "subagent-title", "{title}" }
+                if let Some(c) = subagent.cost_usd {
+                    CostBadge { cost: c }
+                }
+            }
+            div { class: "subagent-meta", "{meta}" }
+            if !subagent.summaries.is_empty() {
+                div { class: "subagent-summaries",
+                    for summary in subagent.summaries.iter() {
+                        div { "{summary}" }
+                    }
+                }
+            }
+            if !subagent.tool_stats.is_empty() {
+                ToolStatList { tools: subagent.tool_stats.clone() }
+            }
+        }
+    }
+}
+
+/// Whole minutes between two RFC 3339 timestamps, or `None` if either is missing.
+fn span_minutes(start: Option<&str>, end: Option<&str>) -> Option<i64> {
+    let start = chrono::DateTime::parse_from_rfc3339(start?).ok()?;
+    let end = chrono::DateTime::parse_from_rfc3339(end?).ok()?;
+    Some((end - start).num_minutes().max(0))
+}
+
+/// `12.3K of 40.1K tokens · $0.42 of $1.20` for the subagents' share of the session.
+fn format_delegated_share(session: &commands::SessionResponse) -> Option<String> {
+    let tokens: i64 = session.subagents.iter().map(|a| a.total_tokens()).sum();
+    let total = session.token_count.filter(|t| *t > 0)?;
+    let mut line = format!("{} of {} tokens", format_tokens(tokens), format_tokens(total));
+    let cost: Option<f64> = session.subagents.iter().filter_map(|a| a.cost_usd).reduce(|a, b| a + b);
+    if let (Some(cost), Some(session_cost)) = (cost, session.estimated_cost_usd) {
+        line.push_str(&format!(" \u{b7} {} of {}", format_cost(cost), format_cost(session_cost)));
+    }
+    Some(line)
+}
+
+fn format_diff_line(insertions: Option<i64>, deletions: Option<i64>, files: usize) -> String {
+    format!("+{} -{} across {} file(s)", insertions.unwrap_or(0), deletions.unwrap_or(0), files)
+}
+
+/// `abc1234..def5678 on main`, or `None` when the session has no recorded range.
+fn format_range(base: Option<&str>, head: Option<&str>, branch: Option<&str>) -> Option<String> {
+    let short = |sha: &str| sha.chars().take(7).collect::<String>();
+    let head = short(head?);
+    let mut range = match base {
+        Some(base) if short(base) == head => head,
+        Some(base) => format!("{}..{}", short(base), head),
+        None => format!("root..{}", head),
+    };
+    if let Some(branch) = branch {
+        range.push_str(&format!(" on {}", branch));
+    }
+    Some(range)
+}
+
+/// `read 2 · edited 3`, listing only the kinds of access that happened.
+fn format_touch_counts(reads: i64, edits: i64, writes: i64) -> String {
+    [("read", reads), ("edited", edits), ("written", writes)]
+        .iter()
+        .filter(|(_, n)| *n > 0)
+        .map(|(label, n)| format!("{} {}", label, n))
+        .collect::<Vec<_>>()
+        .join(" \u{b7} ")
+}
+
+/// Non-zero token categories, labelled. Empty for sessions without a per-category split.
+fn token_breakdown(session: &commands::SessionResponse) -> Vec<(&'static str, i64)> {
+    [
+        ("Input", session.input_tokens),
+        ("Output", session.output_tokens),
+        ("Cache Write", session.cache_creation_tokens),
+        ("Cache Read", session.cache_read_tokens),
+    ]
+    .into_iter()
+    .filter_map(|(label, count)| count.filter(|c| *c > 0).map(|c| (label, c)))
+    .collect()
+}
@@ -13,13 +13,18 @@ pub fn SettingsPage() -> Element {
 
     let privacy_mode = config
         .as_ref()
-        .map(|c| format!("{:?}", c.privacy_mode).to_lowercase())
+        .and_then(|c| serde_json::to_value(c.privacy_mode).ok())
+        .and_then(|v| v.as_str().map(String::from))
         .unwrap_or_else(|| "full".to_string());
     let sanitize_secrets = config.as_ref().map(|c| c.sanitize_secrets).unwrap_or(true);
     let checkpoint_interval = config
         .as_ref()
         .map(|c| c.checkpoint_interval_minutes)
         .unwrap_or(10);
+    let idle_threshold = config
+        .as_ref()
+        .map(|c| c.idle_threshold_minutes)
+        .unwrap_or(15);
 
     // Check if seslog hook binary is installed
     let hook_installed = std::process::Command::new("which")
@@ -46,7 +51,7 @@ pub fn SettingsPage() -> Element {
                         div {
                             div { class: "settings-label", "Privacy Mode" }
                             div { class: "settings-description",
-                                "Controls what data is collected from sessions."
+                                "Full keeps summaries and transcript highlights. Metadata Only keeps timing, cost and git stats but nothing from the transcript. Off also hashes project names and paths and stores no summaries."
                             }
                         }
                         select {
@@ -62,8 +67,8 @@ pub fn SettingsPage() -> Element {
                                 }
                             },
                             option { value: "full", selected: privacy_val() == "full", "Full" }
-                            option { value: "summary-only", selected: privacy_val() == "summary-only", "Summary Only" }
                             option { value: "metadata-only", selected: privacy_val() == "metadata-only", "Metadata Only" }
+                            option { value: "off", selected: privacy_val() == "off", "Off" }
                         }
                     }
 
@@ -101,6 +106,16 @@ pub fn SettingsPage() -> Element {
                         }
                         span { class: "checkpoint-value", "{checkpoint_interval} min" }
                     }
+
+                    div { class: "settings-item", style: "margin-top: 16px;",
+                        div {
+                            div { class: "settings-label", "Idle Threshold" }
+                            div { class: "settings-description",
+                                "Gaps between messages longer than this are not counted as active time."
+                            }
+                        }
+                        span { class: "checkpoint-value", "{idle_threshold} min" }
+                    }
                 }
             }
 
@@ -247,13 +262,15 @@ fn SyncStatusPanel() -> Element {
         "Not a git repository"
     } else if !status.has_remote {
         "Local only (no remote)"
+    } else if status.pending_push.is_some() {
+        "Push failed, retrying"
     } else if status.pending_changes {
         "Changes pending push"
     } else {
         "Synced"
     };
 
-    let is_ok = status.is_repo && status.has_remote && !status.pending_changes;
+    let is_ok = status.is_repo && status.has_remote && !status.pending_changes && status.pending_push.is_none();
 
     rsx! {
         div { class: "settings-item",
@@ -268,6 +285,18 @@ fn SyncStatusPanel() -> Element {
             if let Some(last) = &status.last_sync {
                 div { class: "settings-description", "Last commit: {last}" }
             }
+            if let Some(pending) = &status.pending_push {
+                div { class: "settings-description",
+                    {
+                        format!(
+                            "{} attempt(s), next at {}: {}",
+                            pending.attempts,
+                            pending.next_attempt_at.with_timezone(&chrono::Local).format("%H:%M"),
+                            pending.last_error
+                        )
+                    }
+                }
+            }
         }
 
         div { class: "settings-item", style: "margin-top: 12px;",
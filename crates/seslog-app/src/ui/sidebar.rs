@@ -19,6 +19,18 @@ pub fn Sidebar() -> Element {
 
     let projects = resource().unwrap_or_default();
 
+    // Full-text search over sessions and roadmap items
+    let search_resource = use_resource(move || async move {
+        refresh();
+        let query = search_query();
+        if query.trim().chars().count() < 2 {
+            return Vec::new();
+        }
+        let pool = crate::get_db_pool();
+        commands::search_inner(pool, &query, 20).unwrap_or_default()
+    });
+    let search_results = search_resource().unwrap_or_default();
+
     // Filter by search query
     let query = search_query().to_lowercase();
     let filtered_projects: Vec<_> = if query.is_empty() {
@@ -64,7 +76,7 @@ pub fn Sidebar() -> Element {
                 input {
                     class: "sidebar-search",
                     r#type: "text",
-                    placeholder: "Search projects...",
+                    placeholder: "Search projects & sessions...",
                     value: "{search_query}",
                     oninput: move |evt| {
                         search_query.set(evt.value().to_string());
@@ -76,7 +88,7 @@ pub fn Sidebar() -> Element {
 
                 // Project list (scrollable, filtered)
                 div { class: "sidebar-projects",
-                    if filtered_projects.is_empty() && !query.is_empty() {
+                    if filtered_projects.is_empty() && !query.is_empty() && search_results.is_empty() {
                         div { class: "sidebar-empty", "No matches" }
                     }
                     for p in filtered_projects.iter() {
@@ -101,6 +113,16 @@ pub fn Sidebar() -> Element {
                     }
                 }
 
+                // Session / roadmap search hits
+                if !search_results.is_empty() {
+                    div { class: "sidebar-section-label", "Matches" }
+                    div { class: "sidebar-search-results",
+                        for r in search_results.iter() {
+                            SearchResultItem { result: r.clone() }
+                        }
+                    }
+                }
+
                 div { class: "sidebar-divider" }
 
                 button {
@@ -130,3 +152,66 @@ pub fn Sidebar() -> Element {
         }
     }
 }
+
+#[component]
+fn SearchResultItem(result: commands::SearchResult) -> Element {
+    let mut current_view: Signal<View> = use_context();
+    let date = result
+        .started_at
+        .as_deref()
+        .and_then(|d| d.get(..10))
+        .unwrap_or("")
+        .to_string();
+    let label = match result.kind.as_str() {
+        "roadmap" => "Roadmap",
+        "next_steps" => "Next steps",
+        "highlight" => "Highlight",
+        _ => "Summary",
+    };
+    let segments = snippet_segments(&result.snippet);
+
+    rsx! {
+        button {
+            class: "search-result",
+            onclick: move |_| {
+                let project_id = result.project_id.clone();
+                match result.session_id.clone() {
+                    Some(session_id) => current_view.set(View::Session { project_id, session_id }),
+                    None => current_view.set(View::Project(project_id)),
+                }
+            },
+            div { class: "search-result-meta",
+                span { "{result.project_name}" }
+                span { "{label} {date}" }
+            }
+            div { class: "search-result-snippet",
+                for (text, is_match) in segments {
+                    if is_match {
+                        mark { "{text}" }
+                    } else {
+                        span { "{text}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Split a search snippet into (text, is_match) runs using the FTS highlight delimiters.
+fn snippet_segments(snippet: &str) -> Vec<(String, bool)> {
+    let mut segments = Vec::new();
+    let mut rest = snippet;
+    while let Some(start) = rest.find(commands::SNIPPET_MATCH_START) {
+        if start > 0 {
+            segments.push((rest[..start].to_string(), false));
+        }
+        let after = &rest[start + commands::SNIPPET_MATCH_START.len_utf8()..];
+        let end = after.find(commands::SNIPPET_MATCH_END).unwrap_or(after.len());
+        segments.push((after[..end].to_string(), true));
+        rest = after.get(end + commands::SNIPPET_MATCH_END.len_utf8()..).unwrap_or("");
+    }
+    if !rest.is_empty() {
+        segments.push((rest.to_string(), false));
+    }
+    segments
+}
@@ -264,7 +264,7 @@ fn test_roadmap_shows_in_project_detail() {
 
     // Fetch project detail
     let detail =
-        seslog_app::commands::get_project_detail_inner(&pool, "e2e_proj".into()).unwrap();
+        seslog_app::commands::get_project_detail_inner(&pool, "e2e_proj".into(), None).unwrap();
 
     // Summary has correct name
     assert_eq!(detail.summary.name, "E2E Test Project");
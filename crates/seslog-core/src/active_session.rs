@@ -0,0 +1,264 @@
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use crate::models::ActiveSession;
+use crate::storage;
+
+/// Resolved wall-clock bounds of a session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionTiming {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub duration_minutes: u32,
+}
+
+pub fn marker_path(active_dir: &Path, session_id: &str) -> PathBuf {
+    active_dir.join(format!("{}.json", session_id))
+}
+
+pub fn write_marker(marker: &ActiveSession) -> Result<()> {
+    let base = storage::seslog_dir()?;
+    // Markers carry local paths and the machine name; they must never reach the remote
+    storage::ensure_gitignored(&base, &[".active/"])?;
+    write_marker_to(&base.join(".active"), marker)
+}
+
+pub fn write_marker_to(active_dir: &Path, marker: &ActiveSession) -> Result<()> {
+    storage::write_json(&marker_path(active_dir, &marker.session_id), marker)
+}
+
+/// Whether a SessionStart should record a new in-flight marker.
+///
+/// SessionStart also fires on resume, /clear and /compact with the same session id; an
+/// existing marker is kept so the real start survives. Without one, a fresh startup or a
+/// resume begins a marker: resuming a session that already ended (its marker was removed
+/// by the enrichment pass) starts a new stretch of work at the resume. Payloads without
+/// a `source` predate the field and count as startups.
+pub fn should_write_marker(existing: Option<&ActiveSession>, source: Option<&str>) -> bool {
+    existing.is_none() && matches!(source, None | Some("startup") | Some("resume"))
+}
+
+pub fn read_marker(session_id: &str) -> Result<Option<ActiveSession>> {
+    let base = storage::seslog_dir()?;
+    read_marker_from(&base.join(".active"), &base.join("quarantine"), session_id)
+}
+
+pub fn read_marker_from(active_dir: &Path, quarantine_dir: &Path, session_id: &str) -> Result<Option<ActiveSession>> {
+    storage::safe_read_json_with_quarantine(&marker_path(active_dir, session_id), quarantine_dir)
+}
+
+pub fn remove_marker(session_id: &str) -> Result<()> {
+    let active_dir = storage::seslog_dir()?.join(".active");
+    remove_marker_from(&active_dir, session_id)
+}
+
+pub fn remove_marker_from(active_dir: &Path, session_id: &str) -> Result<()> {
+    match std::fs::remove_file(marker_path(active_dir, session_id)) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The most recently started in-flight session for a project, if any.
+pub fn find_marker_for_project(active_dir: &Path, project_slug: &str) -> Option<ActiveSession> {
+    std::fs::read_dir(active_dir)
+        .ok()?
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| serde_json::from_str::<ActiveSession>(&std::fs::read_to_string(e.path()).ok()?).ok())
+        .filter(|m| m.project_slug == project_slug)
+        .max_by_key(|m| m.started_at)
+}
+
+/// Reconcile the hook-recorded start/end with the transcript's first/last entry timestamps.
+///
+/// The marker start wins when present (resumed transcripts can carry older history);
+/// otherwise the first transcript entry is used. The end is whichever is later of the
+/// SessionEnd hook time and the last transcript entry.
+pub fn resolve_timing(
+    marker_start: Option<DateTime<Utc>>,
+    hook_end: DateTime<Utc>,
+    transcript_bounds: Option<(DateTime<Utc>, DateTime<Utc>)>,
+) -> SessionTiming {
+    let ended_at = match transcript_bounds {
+        Some((_, last)) if last > hook_end => last,
+        _ => hook_end,
+    };
+    let started_at = marker_start
+        .or(transcript_bounds.map(|(first, _)| first))
+        .unwrap_or(ended_at)
+        .min(ended_at);
+    let duration_minutes = (ended_at - started_at).num_minutes().max(0) as u32;
+    SessionTiming { started_at, ended_at, duration_minutes }
+}
+
+/// Active minutes within the resolved session bounds.
+///
+/// Entries before `started_at` (history carried over by a resumed transcript) are ignored.
+pub fn active_minutes_in(
+    timestamps: &[DateTime<Utc>],
+    timing: &SessionTiming,
+    idle_threshold_minutes: u32,
+) -> Option<u32> {
+    let in_session: Vec<DateTime<Utc>> = timestamps
+        .iter()
+        .copied()
+        .filter(|ts| *ts >= timing.started_at && *ts <= timing.ended_at)
+        .collect();
+    crate::transcript::active_minutes(&in_session, idle_threshold_minutes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn ts(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn sample_marker() -> ActiveSession {
+        ActiveSession {
+            schema_version: crate::models::SCHEMA_VERSION,
+            session_id: "abc-123".into(),
+            project_slug: "my-project".into(),
+            cwd: "/home/user/my-project".into(),
+            machine: "mac".into(),
+            started_at: ts("2026-01-01T10:00:00Z"),
+            transcript_path: "/tmp/t.jsonl".into(),
+            git_start: None,
+            pending_summary: None,
+            pending_next_steps: None,
+        }
+    }
+
+    #[test]
+    fn test_find_marker_for_project_picks_latest() {
+        let tmp = TempDir::new().unwrap();
+        let active = tmp.path().join(".active");
+        let older = sample_marker();
+        let newer = ActiveSession { session_id: "def-456".into(), started_at: ts("2026-01-01T11:00:00Z"), ..sample_marker() };
+        let other = ActiveSession { session_id: "ghi-789".into(), project_slug: "other".into(), started_at: ts("2026-01-01T12:00:00Z"), ..sample_marker() };
+        for m in [&older, &newer, &other] {
+            write_marker_to(&active, m).unwrap();
+        }
+        assert_eq!(find_marker_for_project(&active, "my-project").unwrap().session_id, "def-456");
+        assert!(find_marker_for_project(&active, "nope").is_none());
+        assert!(find_marker_for_project(&tmp.path().join("missing"), "my-project").is_none());
+    }
+
+    #[test]
+    fn test_marker_write_read_remove() {
+        let tmp = TempDir::new().unwrap();
+        let active = tmp.path().join(".active");
+        let quarantine = tmp.path().join("quarantine");
+        write_marker_to(&active, &sample_marker()).unwrap();
+        let loaded = read_marker_from(&active, &quarantine, "abc-123").unwrap().unwrap();
+        assert_eq!(loaded.project_slug, "my-project");
+        assert_eq!(loaded.started_at, ts("2026-01-01T10:00:00Z"));
+
+        remove_marker_from(&active, "abc-123").unwrap();
+        assert!(read_marker_from(&active, &quarantine, "abc-123").unwrap().is_none());
+        // Removing twice is not an error
+        remove_marker_from(&active, "abc-123").unwrap();
+    }
+
+    #[test]
+    fn test_should_write_marker_only_when_missing() {
+        let existing = sample_marker();
+        assert!(should_write_marker(None, Some("startup")));
+        assert!(should_write_marker(None, Some("resume")));
+        assert!(should_write_marker(None, None));
+        for source in ["clear", "compact"] {
+            assert!(!should_write_marker(None, Some(source)));
+        }
+        for source in [Some("startup"), Some("resume"), Some("compact"), None] {
+            assert!(!should_write_marker(Some(&existing), source));
+        }
+    }
+
+    #[test]
+    fn test_resume_after_end_starts_a_new_marker() {
+        let tmp = TempDir::new().unwrap();
+        let active = tmp.path().join(".active");
+        let quarantine = tmp.path().join("quarantine");
+        write_marker_to(&active, &sample_marker()).unwrap();
+        // SessionEnd's enrichment pass removes the marker
+        remove_marker_from(&active, "abc-123").unwrap();
+
+        let existing = read_marker_from(&active, &quarantine, "abc-123").unwrap();
+        assert!(should_write_marker(existing.as_ref(), Some("resume")));
+        let resumed_at = ts("2026-01-02T09:00:00Z");
+        write_marker_to(&active, &ActiveSession { started_at: resumed_at, ..sample_marker() }).unwrap();
+
+        // The resumed stretch is timed from the resume, not from the transcript's first entry
+        let marker = read_marker_from(&active, &quarantine, "abc-123").unwrap().unwrap();
+        let timing = resolve_timing(
+            Some(marker.started_at),
+            ts("2026-01-02T09:45:00Z"),
+            Some((ts("2026-01-01T10:00:00Z"), ts("2026-01-02T09:44:00Z"))),
+        );
+        assert_eq!(timing.started_at, resumed_at);
+        assert_eq!(timing.duration_minutes, 45);
+    }
+
+    #[test]
+    fn test_compacted_session_keeps_git_start() {
+        let tmp = TempDir::new().unwrap();
+        let active = tmp.path().join(".active");
+        let quarantine = tmp.path().join("quarantine");
+        let base = crate::models::HeadState { sha: Some("a".repeat(40)), branch: Some("main".into()) };
+        write_marker_to(&active, &ActiveSession { git_start: Some(base.clone()), ..sample_marker() }).unwrap();
+
+        let existing = read_marker_from(&active, &quarantine, "abc-123").unwrap();
+        assert!(!should_write_marker(existing.as_ref(), Some("compact")));
+        assert_eq!(existing.unwrap().git_start, Some(base));
+    }
+
+    #[test]
+    fn test_resolve_timing_prefers_marker_start() {
+        let timing = resolve_timing(
+            Some(ts("2026-01-01T10:00:00Z")),
+            ts("2026-01-01T11:30:00Z"),
+            Some((ts("2025-12-31T09:00:00Z"), ts("2026-01-01T11:29:00Z"))),
+        );
+        assert_eq!(timing.started_at, ts("2026-01-01T10:00:00Z"));
+        assert_eq!(timing.ended_at, ts("2026-01-01T11:30:00Z"));
+        assert_eq!(timing.duration_minutes, 90);
+    }
+
+    #[test]
+    fn test_resolve_timing_falls_back_to_transcript() {
+        let timing = resolve_timing(
+            None,
+            ts("2026-01-01T11:00:00Z"),
+            Some((ts("2026-01-01T10:15:00Z"), ts("2026-01-01T11:05:00Z"))),
+        );
+        assert_eq!(timing.started_at, ts("2026-01-01T10:15:00Z"));
+        assert_eq!(timing.ended_at, ts("2026-01-01T11:05:00Z"));
+        assert_eq!(timing.duration_minutes, 50);
+    }
+
+    #[test]
+    fn test_resolve_timing_no_data_is_zero_length() {
+        let end = ts("2026-01-01T11:00:00Z");
+        let timing = resolve_timing(None, end, None);
+        assert_eq!(timing.started_at, end);
+        assert_eq!(timing.duration_minutes, 0);
+    }
+
+    #[test]
+    fn test_active_minutes_in_ignores_resumed_history() {
+        let timing = resolve_timing(Some(ts("2026-01-01T10:00:00Z")), ts("2026-01-01T11:00:00Z"), None);
+        let timestamps = vec![
+            ts("2025-12-31T09:00:00Z"),
+            ts("2025-12-31T09:10:00Z"),
+            ts("2026-01-01T10:00:00Z"),
+            ts("2026-01-01T10:12:00Z"),
+            ts("2026-01-01T10:50:00Z"),
+        ];
+        assert_eq!(active_minutes_in(&timestamps, &timing, 15), Some(12));
+        assert_eq!(active_minutes_in(&timestamps[..2], &timing, 15), None);
+    }
+}
@@ -0,0 +1,169 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::models::{ProjectMeta, Session};
+
+/// A project directory under `projects/` with its parsed `meta.toml`.
+#[derive(Debug, Clone)]
+pub struct ProjectEntry {
+    pub slug: String,
+    pub dir: PathBuf,
+    pub meta: ProjectMeta,
+}
+
+/// All projects with a readable `meta.toml`, sorted by slug.
+///
+/// Read-only: unlike `safe_read_json`, unreadable files are skipped, never quarantined.
+pub fn list_projects(data_dir: &Path) -> Vec<ProjectEntry> {
+    let mut projects: Vec<ProjectEntry> = fs::read_dir(data_dir.join("projects"))
+        .map(|entries| entries.flatten().collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter_map(|e| {
+            let content = fs::read_to_string(e.path().join("meta.toml")).ok()?;
+            let meta: ProjectMeta = toml::from_str(&content).ok()?;
+            Some(ProjectEntry { slug: e.file_name().to_string_lossy().to_string(), dir: e.path(), meta })
+        })
+        .collect();
+    projects.sort_by(|a, b| a.slug.cmp(&b.slug));
+    projects
+}
+
+/// Find a project by slug, project id, or case-insensitive name.
+pub fn find_project(data_dir: &Path, query: &str) -> Option<ProjectEntry> {
+    let projects = list_projects(data_dir);
+    let lower = query.to_lowercase();
+    projects.iter().find(|p| p.slug == query || p.meta.project.id == query)
+        .or_else(|| projects.iter().find(|p| p.meta.project.name.to_lowercase() == lower))
+        .cloned()
+}
+
+/// Sessions of one project, newest first.
+pub fn load_sessions(project_dir: &Path) -> Vec<Session> {
+    let mut sessions: Vec<Session> = fs::read_dir(project_dir.join("sessions"))
+        .map(|entries| entries.flatten().collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| serde_json::from_str(&crate::encryption::read_record(&p).ok()?).ok())
+        .collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.started_at));
+    sessions
+}
+
+/// Find a session by id, with or without the `ses_` prefix. A unique prefix also matches.
+pub fn find_session(data_dir: &Path, query: &str) -> Option<(ProjectEntry, Session)> {
+    let wanted = if query.starts_with("ses_") { query.to_string() } else { format!("ses_{}", query) };
+    let mut matches = Vec::new();
+    for project in list_projects(data_dir) {
+        for session in load_sessions(&project.dir) {
+            if session.id == wanted {
+                return Some((project, session));
+            }
+            if session.id.starts_with(&wanted) {
+                matches.push((project.clone(), session));
+            }
+        }
+    }
+    if matches.len() == 1 { matches.pop() } else { None }
+}
+
+/// Sessions whose summary, next steps, highlights, decisions or commits contain `query`
+/// (case-insensitive), newest first, optionally limited to the project with slug `project`.
+pub fn search_sessions(data_dir: &Path, query: &str, project: Option<&str>, limit: usize) -> Vec<(ProjectEntry, Session)> {
+    let needle = query.to_lowercase();
+    let mut hits: Vec<(ProjectEntry, Session)> = list_projects(data_dir).into_iter()
+        .filter(|p| project.is_none_or(|slug| p.slug == slug))
+        .flat_map(|p| load_sessions(&p.dir).into_iter().map(move |s| (p.clone(), s)))
+        .filter(|(_, s)| {
+            std::iter::once(&s.summary)
+                .chain(std::iter::once(&s.next_steps))
+                .chain(&s.transcript_highlights)
+                .chain(&s.decisions)
+                .chain(&s.git_commits)
+                .any(|text| text.to_lowercase().contains(&needle))
+        })
+        .collect();
+    hits.sort_by_key(|(_, s)| std::cmp::Reverse(s.started_at));
+    hits.truncate(limit);
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_project(data_dir: &Path, slug: &str, id: &str, name: &str) {
+        let dir = data_dir.join("projects").join(slug);
+        fs::create_dir_all(dir.join("sessions")).unwrap();
+        fs::write(dir.join("meta.toml"), format!(
+            "schema_version = 1\n\n[project]\nid = \"{}\"\nname = \"{}\"\nstatus = \"active\"\ncreated_at = \"2026-01-01T00:00:00Z\"\n\n[paths]\n",
+            id, name
+        )).unwrap();
+    }
+
+    fn write_session(data_dir: &Path, slug: &str, id: &str, started_at: &str) {
+        let json = serde_json::json!({
+            "schema_version": 1, "id": id, "project_id": "p", "machine": "mac",
+            "started_at": started_at, "summary": "s",
+        });
+        fs::write(data_dir.join("projects").join(slug).join("sessions").join(format!("{}.json", id)), json.to_string()).unwrap();
+    }
+
+    #[test]
+    fn test_find_project_by_slug_id_or_name() {
+        let tmp = TempDir::new().unwrap();
+        write_project(tmp.path(), "api", "proj_1", "Billing API");
+        write_project(tmp.path(), "web", "proj_2", "Web");
+        assert_eq!(list_projects(tmp.path()).len(), 2);
+        assert_eq!(find_project(tmp.path(), "api").unwrap().meta.project.id, "proj_1");
+        assert_eq!(find_project(tmp.path(), "proj_2").unwrap().slug, "web");
+        assert_eq!(find_project(tmp.path(), "billing api").unwrap().slug, "api");
+        assert!(find_project(tmp.path(), "nope").is_none());
+    }
+
+    #[test]
+    fn test_sessions_newest_first_and_find_by_prefix() {
+        let tmp = TempDir::new().unwrap();
+        write_project(tmp.path(), "api", "proj_1", "API");
+        write_session(tmp.path(), "api", "ses_aaa111", "2026-01-01T10:00:00Z");
+        write_session(tmp.path(), "api", "ses_aab222", "2026-01-03T10:00:00Z");
+        fs::write(tmp.path().join("projects/api/sessions/broken.json"), "{").unwrap();
+
+        let sessions = load_sessions(&tmp.path().join("projects/api"));
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].id, "ses_aab222");
+
+        assert_eq!(find_session(tmp.path(), "aaa").unwrap().1.id, "ses_aaa111");
+        assert_eq!(find_session(tmp.path(), "ses_aab222").unwrap().1.id, "ses_aab222");
+        // Ambiguous prefix
+        assert!(find_session(tmp.path(), "aa").is_none());
+        // Broken files are skipped, not quarantined
+        assert!(tmp.path().join("projects/api/sessions/broken.json").exists());
+    }
+
+    #[test]
+    fn test_search_sessions_matches_text_fields() {
+        let tmp = TempDir::new().unwrap();
+        write_project(tmp.path(), "api", "proj_1", "API");
+        write_project(tmp.path(), "web", "proj_2", "Web");
+        write_session(tmp.path(), "api", "ses_a1", "2026-01-01T10:00:00Z");
+        write_session(tmp.path(), "web", "ses_w1", "2026-01-02T10:00:00Z");
+        let json = serde_json::json!({
+            "schema_version": 1, "id": "ses_a2", "project_id": "p", "machine": "mac",
+            "started_at": "2026-01-03T10:00:00Z", "summary": "Fixed OAuth refresh",
+            "git_commits": ["abc1234 Retry token refresh"],
+        });
+        fs::write(tmp.path().join("projects/api/sessions/ses_a2.json"), json.to_string()).unwrap();
+
+        let hits = search_sessions(tmp.path(), "REFRESH", None, 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].0.slug.as_str(), hits[0].1.id.as_str()), ("api", "ses_a2"));
+        assert_eq!(search_sessions(tmp.path(), "s", None, 10).len(), 3);
+        let web = search_sessions(tmp.path(), "s", Some("web"), 10);
+        assert_eq!((web.len(), web[0].1.id.as_str()), (1, "ses_w1"));
+        assert_eq!(search_sessions(tmp.path(), "s", None, 1)[0].1.id, "ses_a2");
+    }
+}
@@ -3,11 +3,35 @@ use serde::{Deserialize, Serialize};
 use std::path::Path;
 use crate::models::SCHEMA_VERSION;
 
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+/// What session content may be written to the (synced) data directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum PrivacyMode {
+    /// Everything: summaries, transcript highlights, next steps, decisions.
     #[default]
     Full,
+    /// Timing, cost, tools, git stats and manual summaries; nothing taken from the transcript.
+    MetadataOnly,
+    /// Metadata only, with project names, paths and the machine name replaced by keyed
+    /// hashes and no summaries at all.
+    Off,
+}
+
+impl PrivacyMode {
+    /// Whether text derived from the transcript (prompts, highlights, generated summaries) is kept.
+    pub fn keeps_transcript_text(self) -> bool {
+        self == PrivacyMode::Full
+    }
+
+    /// Whether summaries, next steps and commit messages are kept.
+    pub fn keeps_summaries(self) -> bool {
+        self != PrivacyMode::Off
+    }
+
+    /// Whether project names, paths and the machine name are replaced by keyed hashes.
+    pub fn hashes_identifiers(self) -> bool {
+        self == PrivacyMode::Off
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,20 +44,162 @@ pub struct AppConfig {
     pub checkpoint_interval_minutes: u32,
     #[serde(default = "default_additional_context_max")]
     pub additional_context_max_chars: u32,
+    /// How many recent sessions the session-start context draws next steps from.
+    #[serde(default = "default_context_recent_sessions")]
+    pub context_recent_sessions: u32,
+    /// Caps on the user messages stored with a session. The whole transcript is still
+    /// parsed, so token counts and cost are unaffected.
     #[serde(default = "default_transcript_max_messages")]
     pub transcript_max_messages: u32,
     #[serde(default = "default_transcript_max_tokens")]
     pub transcript_max_tokens: u32,
     #[serde(default = "default_true")]
     pub sanitize_secrets: bool,
+    /// Gaps between transcript entries longer than this are treated as idle time.
+    #[serde(default = "default_idle_threshold")]
+    pub idle_threshold_minutes: u32,
+    /// Extra redaction rules layered on top of the built-in secret patterns.
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    /// Phrases Claude is told to react to in the session-start context.
+    #[serde(default)]
+    pub triggers: TriggerConfig,
+}
+
+/// `[redaction]` section of `config.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedactionConfig {
+    /// User-defined patterns, applied before the built-in ones.
+    #[serde(default)]
+    pub patterns: Vec<CustomPattern>,
+    /// Literal strings that are never redacted; a match containing one is kept as-is.
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+    /// Redact long random-looking tokens that no pattern recognised.
+    #[serde(default = "default_true")]
+    pub entropy_detection: bool,
+    /// Minimum Shannon entropy (bits per character). Hex strings such as commit
+    /// hashes top out at 4.0, so they stay below the default.
+    #[serde(default = "default_entropy_threshold")]
+    pub entropy_threshold: f64,
+    #[serde(default = "default_entropy_min_length")]
+    pub entropy_min_length: usize,
+}
+
+/// Language of the default trigger phrases and of summaries Claude writes for them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Tr,
+}
+
+impl Language {
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::En => "English",
+            Language::Tr => "Turkish",
+        }
+    }
+}
+
+/// `[triggers]` section of `config.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TriggerConfig {
+    #[serde(default)]
+    pub language: Language,
+    /// Creating a roadmap when the project has none.
+    #[serde(default)]
+    pub roadmap: TriggerOverride,
+    /// Summarizing the session and updating the roadmap.
+    #[serde(default)]
+    pub summary: TriggerOverride,
+}
+
+/// Replaces the language defaults of one trigger. Empty fields keep the default.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TriggerOverride {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phrases: Vec<String>,
+    /// `{slug}` and `{language}` are substituted.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub instructions: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomPattern {
+    pub label: String,
+    pub regex: String,
 }
 
+/// Machine-local settings from `local.toml` in the data directory. Git-ignored,
+/// so each machine keeps its own values (and its own keys).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocalConfig {
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
+    #[serde(default)]
+    pub privacy: LocalPrivacyConfig,
+}
+
+/// `[privacy]` section of `local.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocalPrivacyConfig {
+    /// Hex secret that keys identifier hashes in `privacy_mode = "off"`. Generated on
+    /// first use; copy it to the other machines so their hashes match.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub hash_key: String,
+}
+
+/// `[encryption]` section of `local.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    /// Encrypt session and checkpoint JSON before it is committed by sync. Everything
+    /// else that syncs stays plaintext, decision logs included; see
+    /// [`crate::encryption::PLAINTEXT_FILES`].
+    #[serde(default)]
+    pub enabled: bool,
+    /// age identity file. Relative paths resolve against the data directory.
+    #[serde(default = "default_identity_file")]
+    pub identity_file: String,
+    /// Extra `age1…` public keys to encrypt to, typically the other machines
+    /// that sync this repository. This machine's own key is always included.
+    #[serde(default)]
+    pub recipients: Vec<String>,
+}
+
+pub const LOCAL_CONFIG_FILE: &str = "local.toml";
+
 fn default_schema_version() -> u32 { SCHEMA_VERSION }
 fn default_checkpoint_interval() -> u32 { 10 }
 fn default_additional_context_max() -> u32 { 1500 }
+fn default_context_recent_sessions() -> u32 { 3 }
 fn default_transcript_max_messages() -> u32 { 100 }
 fn default_transcript_max_tokens() -> u32 { 6000 }
 fn default_true() -> bool { true }
+fn default_idle_threshold() -> u32 { 15 }
+fn default_entropy_threshold() -> f64 { 4.3 }
+fn default_entropy_min_length() -> usize { 24 }
+fn default_identity_file() -> String { ".keys/identity.txt".into() }
+
+impl Default for EncryptionConfig {
+    fn default() -> Self {
+        Self { enabled: false, identity_file: default_identity_file(), recipients: Vec::new() }
+    }
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            patterns: Vec::new(),
+            allowlist: Vec::new(),
+            entropy_detection: true,
+            entropy_threshold: default_entropy_threshold(),
+            entropy_min_length: default_entropy_min_length(),
+        }
+    }
+}
 
 impl Default for AppConfig {
     fn default() -> Self {
@@ -42,9 +208,13 @@ impl Default for AppConfig {
             privacy_mode: PrivacyMode::default(),
             checkpoint_interval_minutes: default_checkpoint_interval(),
             additional_context_max_chars: default_additional_context_max(),
+            context_recent_sessions: default_context_recent_sessions(),
             transcript_max_messages: default_transcript_max_messages(),
             transcript_max_tokens: default_transcript_max_tokens(),
             sanitize_secrets: true,
+            idle_threshold_minutes: default_idle_threshold(),
+            redaction: RedactionConfig::default(),
+            triggers: TriggerConfig::default(),
         }
     }
 }
@@ -62,6 +232,19 @@ pub fn load_config(path: &Path) -> Result<AppConfig> {
     }
 }
 
+pub fn load_local_config(path: &Path) -> Result<LocalConfig> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(toml::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(LocalConfig::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn write_local_config(path: &Path, config: &LocalConfig) -> Result<()> {
+    let content = toml::to_string_pretty(config)?;
+    crate::storage::atomic_write(path, content.as_bytes())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,7 +256,22 @@ mod tests {
         assert_eq!(cfg.privacy_mode, PrivacyMode::Full);
         assert_eq!(cfg.checkpoint_interval_minutes, 10);
         assert_eq!(cfg.additional_context_max_chars, 1500);
+        assert_eq!(cfg.context_recent_sessions, 3);
         assert!(cfg.sanitize_secrets);
+        assert_eq!(cfg.triggers.language, Language::En);
+    }
+
+    #[test]
+    fn test_triggers_section_parses() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        std::fs::write(&path, "[triggers]\nlanguage = \"tr\"\n\n[triggers.summary]\nphrases = [\"wrap up\"]\n").unwrap();
+        let cfg = load_config(&path).unwrap();
+        assert_eq!(cfg.triggers.language, Language::Tr);
+        assert_eq!(cfg.triggers.summary.phrases, vec!["wrap up"]);
+        assert!(cfg.triggers.roadmap.phrases.is_empty());
+        write_config(&path, &cfg).unwrap();
+        assert_eq!(load_config(&path).unwrap().triggers, cfg.triggers);
     }
 
     #[test]
@@ -101,5 +299,43 @@ mod tests {
         std::fs::write(&path, "privacy_mode = \"full\"\n").unwrap();
         let cfg = load_config(&path).unwrap();
         assert_eq!(cfg.checkpoint_interval_minutes, 10);
+        assert_eq!(cfg.idle_threshold_minutes, 15);
+    }
+
+    #[test]
+    fn test_privacy_modes_parse_kebab_case() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        for (raw, mode) in [("full", PrivacyMode::Full), ("metadata-only", PrivacyMode::MetadataOnly), ("off", PrivacyMode::Off)] {
+            std::fs::write(&path, format!("privacy_mode = \"{}\"\n", raw)).unwrap();
+            assert_eq!(load_config(&path).unwrap().privacy_mode, mode);
+        }
+        assert!(PrivacyMode::Full.keeps_transcript_text());
+        assert!(!PrivacyMode::MetadataOnly.keeps_transcript_text() && PrivacyMode::MetadataOnly.keeps_summaries());
+        assert!(!PrivacyMode::Off.keeps_summaries() && PrivacyMode::Off.hashes_identifiers());
+    }
+
+    #[test]
+    fn test_redaction_section_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        std::fs::write(&path, r#"
+sanitize_secrets = true
+
+[redaction]
+allowlist = ["sk-test-fixture"]
+entropy_detection = false
+
+[[redaction.patterns]]
+label = "Internal token"
+regex = "acme_[a-z0-9]{12}"
+"#).unwrap();
+        let cfg = load_config(&path).unwrap();
+        assert_eq!(cfg.redaction.patterns[0].label, "Internal token");
+        assert!(!cfg.redaction.entropy_detection);
+        assert_eq!(cfg.redaction.entropy_min_length, 24);
+
+        write_config(&path, &cfg).unwrap();
+        assert_eq!(load_config(&path).unwrap().redaction, cfg.redaction);
     }
 }
@@ -0,0 +1,423 @@
+use std::path::Path;
+use serde::Serialize;
+use crate::config::{AppConfig, TriggerConfig};
+use crate::decisions::Decision;
+use crate::models::{Checkpoint, GitContext, Session};
+use crate::roadmap::{self, ItemStatus};
+use crate::triggers::{self, TriggerAction};
+
+/// A titled block of session-start context produced by one [`ContextSource`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContextSection {
+    /// Rendered as `Title: line` for one line, or a `Title:` heading over `- line` bullets.
+    /// Empty for free-standing text such as instructions.
+    pub title: String,
+    pub lines: Vec<String>,
+    /// Higher priorities are kept first when the character budget runs out.
+    pub priority: u8,
+}
+
+impl ContextSection {
+    pub fn new(title: &str, priority: u8, lines: Vec<String>) -> Self {
+        Self { title: title.to_string(), lines, priority }
+    }
+
+    fn render_line(&self, line: &str) -> String {
+        match (self.title.is_empty(), self.lines.len()) {
+            (true, _) => line.to_string(),
+            (false, 1) => format!("{}: {}", self.title, line),
+            (false, _) => format!("- {}", line),
+        }
+    }
+}
+
+/// Everything the sources can draw on, loaded once per SessionStart.
+#[derive(Debug, Clone, Default)]
+pub struct ContextInput {
+    pub slug: String,
+    /// Recent sessions, newest first.
+    pub sessions: Vec<Session>,
+    pub roadmap: String,
+    /// Decision log in file order (oldest first).
+    pub decisions: Vec<Decision>,
+    pub last_checkpoint: Option<Checkpoint>,
+    pub git: Option<GitContext>,
+    /// `+N -M across K file(s)` for the working tree, if it has uncommitted changes.
+    pub uncommitted_stat: Option<String>,
+    pub uncommitted_files: Vec<String>,
+}
+
+impl ContextInput {
+    /// Load the inputs for `slug` from its project directory and the working tree at `cwd`.
+    pub fn load(slug: &str, project_dir: &Path, cwd: &Path, recent_sessions: usize) -> Self {
+        let mut sessions = crate::catalog::load_sessions(project_dir);
+        sessions.truncate(recent_sessions.max(1));
+        Self {
+            slug: slug.to_string(),
+            sessions,
+            roadmap: std::fs::read_to_string(project_dir.join("roadmap.md")).unwrap_or_default(),
+            decisions: crate::decisions::load_decisions(project_dir),
+            last_checkpoint: load_last_checkpoint(project_dir),
+            git: crate::git_ops::git_context(cwd),
+            uncommitted_stat: crate::git_ops::diff_stat(cwd).unwrap_or(None),
+            uncommitted_files: crate::git_ops::changed_files(cwd).unwrap_or_default(),
+        }
+    }
+}
+
+/// Checkpoint files are named `%Y%m%d_%H%M%S_<id>.json`, so the last name is the newest.
+fn load_last_checkpoint(project_dir: &Path) -> Option<Checkpoint> {
+    let mut paths: Vec<_> = std::fs::read_dir(project_dir.join("checkpoints")).ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    serde_json::from_str(&crate::encryption::read_record(paths.last()?).ok()?).ok()
+}
+
+/// One kind of context. Implement this to plug a new source into [`ContextComposer`].
+pub trait ContextSource {
+    fn collect(&self, input: &ContextInput) -> Option<ContextSection>;
+}
+
+/// Collects sections from its sources, keeps the highest-priority ones that fit in
+/// `max_chars`, and renders them in priority order.
+pub struct ContextComposer {
+    sources: Vec<Box<dyn ContextSource>>,
+    max_chars: usize,
+}
+
+const HEADER: &str = "[Seslog] Project context:";
+/// Below this many remaining characters a line is dropped rather than cut.
+const MIN_TRUNCATED_LINE: usize = 40;
+
+impl ContextComposer {
+    pub fn new(max_chars: usize) -> Self {
+        Self { sources: Vec::new(), max_chars }
+    }
+
+    /// The built-in sources: last summary, instructions, branch, roadmap state, pending
+    /// next steps, blocked items, uncommitted changes, recent decisions, last checkpoint.
+    pub fn with_defaults(max_chars: usize, triggers: &TriggerConfig) -> Self {
+        Self::new(max_chars)
+            .with_source(LastSummary)
+            .with_source(Instructions { triggers: triggers.clone() })
+            .with_source(Branch)
+            .with_source(RoadmapStatus)
+            .with_source(NextSteps)
+            .with_source(BlockedItems)
+            .with_source(UncommittedChanges)
+            .with_source(RecentDecisions { max: 5 })
+            .with_source(LastCheckpointFiles { max: 10 })
+    }
+
+    /// [`with_defaults`](Self::with_defaults) with the budget and triggers from `config.toml`.
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::with_defaults(config.additional_context_max_chars as usize, &config.triggers)
+    }
+
+    pub fn with_source(mut self, source: impl ContextSource + 'static) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    /// Every non-empty section, highest priority first, before the budget is applied.
+    pub fn sections(&self, input: &ContextInput) -> Vec<ContextSection> {
+        let mut sections: Vec<ContextSection> = self.sources.iter()
+            .filter_map(|s| s.collect(input))
+            .filter(|s| !s.lines.is_empty())
+            .collect();
+        // Stable sort: equal priorities keep source order.
+        sections.sort_by_key(|s| std::cmp::Reverse(s.priority));
+        sections
+    }
+
+    pub fn max_chars(&self) -> usize {
+        self.max_chars
+    }
+
+    pub fn compose(&self, input: &ContextInput) -> String {
+        let sections = self.sections(input);
+        let mut out = vec![HEADER.to_string()];
+        let mut used = HEADER.chars().count();
+        for section in &sections {
+            let heading = (section.lines.len() > 1 && !section.title.is_empty())
+                .then(|| format!("{}:", section.title));
+            let heading_len = heading.as_ref().map_or(0, |h| h.chars().count() + 1);
+            let mut rendered = Vec::new();
+            let mut section_used = heading_len;
+            for line in &section.lines {
+                let text = section.render_line(line);
+                let cost = text.chars().count() + 1;
+                if used + section_used + cost <= self.max_chars {
+                    section_used += cost;
+                    rendered.push(text);
+                    continue;
+                }
+                // Cut the first line to fit rather than losing the whole section.
+                let room = self.max_chars.saturating_sub(used + section_used + 1);
+                if rendered.is_empty() && room >= MIN_TRUNCATED_LINE {
+                    let cut: String = text.chars().take(room - 3).collect::<String>() + "...";
+                    section_used += cut.chars().count() + 1;
+                    rendered.push(cut);
+                }
+                break;
+            }
+            if rendered.is_empty() {
+                continue;
+            }
+            used += section_used;
+            out.extend(heading);
+            out.extend(rendered);
+        }
+        out.join("\n")
+    }
+}
+
+pub struct LastSummary;
+
+impl ContextSource for LastSummary {
+    fn collect(&self, input: &ContextInput) -> Option<ContextSection> {
+        let summary = input.sessions.first()?.summary.trim();
+        if summary.is_empty() {
+            return None;
+        }
+        let text: String = summary.chars().take(500).collect();
+        Some(ContextSection::new("Last session", 100, vec![text]))
+    }
+}
+
+/// Roadmap-creation and session-summary instructions with their configured triggers.
+pub struct Instructions {
+    pub triggers: TriggerConfig,
+}
+
+impl ContextSource for Instructions {
+    fn collect(&self, input: &ContextInput) -> Option<ContextSection> {
+        let trigger = |action| triggers::resolve(&self.triggers, action, &input.slug).render();
+        let mut lines = Vec::new();
+        if input.roadmap.trim().is_empty() {
+            lines.push(format!("No roadmap yet. {}", trigger(TriggerAction::CreateRoadmap)));
+        }
+        lines.push(trigger(TriggerAction::SessionSummary));
+        Some(ContextSection::new("", 95, lines))
+    }
+}
+
+/// `Branch: feature/x (worktree /src/x; 2 ahead, 1 behind origin/feature/x)`.
+pub struct Branch;
+
+impl ContextSource for Branch {
+    fn collect(&self, input: &ContextInput) -> Option<ContextSection> {
+        let git = input.git.as_ref()?;
+        let branch = git.branch.as_deref()?;
+        let mut notes = Vec::new();
+        if git.linked_worktree {
+            if let Some(w) = &git.worktree { notes.push(format!("worktree {}", w)); }
+        }
+        match &git.upstream {
+            Some(u) if git.ahead > 0 || git.behind > 0 => notes.push(format!("{} ahead, {} behind {}", git.ahead, git.behind, u)),
+            Some(u) => notes.push(format!("up to date with {}", u)),
+            None => notes.push("no upstream".into()),
+        }
+        Some(ContextSection::new("Branch", 90, vec![format!("{} ({})", branch, notes.join("; "))]))
+    }
+}
+
+/// Active roadmap step and overall progress.
+pub struct RoadmapStatus;
+
+impl ContextSource for RoadmapStatus {
+    fn collect(&self, input: &ContextInput) -> Option<ContextSection> {
+        if input.roadmap.trim().is_empty() {
+            return None;
+        }
+        let progress = format!("{}%", roadmap::progress_percent(&input.roadmap));
+        let line = match roadmap::active_item(&input.roadmap) {
+            Some(item) => format!("{} (progress {})", item.text, progress),
+            None => format!("none active (progress {})", progress),
+        };
+        Some(ContextSection::new("Active roadmap step", 85, vec![line]))
+    }
+}
+
+/// Next steps left by recent sessions, newest first, without repeats.
+pub struct NextSteps;
+
+impl ContextSource for NextSteps {
+    fn collect(&self, input: &ContextInput) -> Option<ContextSection> {
+        let mut lines: Vec<String> = Vec::new();
+        for session in &input.sessions {
+            let step: String = session.next_steps.trim().chars().take(300).collect();
+            if !step.is_empty() && !lines.contains(&step) {
+                lines.push(step);
+            }
+        }
+        Some(ContextSection::new("Pending next steps", 80, lines))
+    }
+}
+
+pub struct BlockedItems;
+
+impl ContextSource for BlockedItems {
+    fn collect(&self, input: &ContextInput) -> Option<ContextSection> {
+        let lines = roadmap::parse_roadmap(&input.roadmap).into_iter()
+            .filter(|i| i.status == ItemStatus::Blocked)
+            .map(|i| i.text)
+            .collect();
+        Some(ContextSection::new("Blocked roadmap items", 70, lines))
+    }
+}
+
+pub struct UncommittedChanges;
+
+impl ContextSource for UncommittedChanges {
+    fn collect(&self, input: &ContextInput) -> Option<ContextSection> {
+        let stat = input.uncommitted_stat.as_ref()?;
+        let mut line = stat.clone();
+        if !input.uncommitted_files.is_empty() {
+            let shown: Vec<&str> = input.uncommitted_files.iter().take(8).map(String::as_str).collect();
+            let more = input.uncommitted_files.len().saturating_sub(shown.len());
+            line.push_str(&format!(": {}", shown.join(", ")));
+            if more > 0 { line.push_str(&format!(" and {} more", more)); }
+        }
+        Some(ContextSection::new("Uncommitted changes", 60, vec![line]))
+    }
+}
+
+pub struct RecentDecisions {
+    pub max: usize,
+}
+
+impl ContextSource for RecentDecisions {
+    fn collect(&self, input: &ContextInput) -> Option<ContextSection> {
+        let lines = input.decisions.iter().rev().take(self.max)
+            .map(|d| format!("{} ({})", d.title, d.date.format("%Y-%m-%d")))
+            .collect();
+        Some(ContextSection::new("Recent decisions", 50, lines))
+    }
+}
+
+pub struct LastCheckpointFiles {
+    pub max: usize,
+}
+
+impl ContextSource for LastCheckpointFiles {
+    fn collect(&self, input: &ContextInput) -> Option<ContextSection> {
+        let files = &input.last_checkpoint.as_ref()?.files_changed;
+        if files.is_empty() {
+            return None;
+        }
+        Some(ContextSection::new("Files touched at last checkpoint", 40, vec![files.iter().take(self.max).cloned().collect::<Vec<_>>().join(", ")]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(summary: &str, next_steps: &str) -> Session {
+        serde_json::from_value(serde_json::json!({
+            "schema_version": 1, "id": "ses_1", "project_id": "p", "machine": "mac",
+            "started_at": "2026-01-01T10:00:00Z", "summary": summary, "next_steps": next_steps,
+        })).unwrap()
+    }
+
+    fn input() -> ContextInput {
+        ContextInput {
+            slug: "api".into(),
+            sessions: vec![session("Fixed auth bug", "Add retry tests"), session("Setup", "Add retry tests"), session("Init", "Write docs")],
+            roadmap: "## Phase 1\n- [x] Setup\n- [>] Feature engineering\n- [!] Deploy to prod\n".into(),
+            decisions: vec![Decision {
+                date: "2026-01-02T00:00:00Z".parse().unwrap(),
+                title: "Use SQLite".into(),
+                why: String::new(),
+                session_id: None,
+                source: Default::default(),
+            }],
+            uncommitted_stat: Some("+3 -1 across 1 file(s)".into()),
+            uncommitted_files: vec!["src/lib.rs".into()],
+            ..ContextInput::default()
+        }
+    }
+
+    #[test]
+    fn test_compose_includes_all_sources() {
+        let ctx = ContextComposer::with_defaults(5000, &TriggerConfig::default()).compose(&input());
+        assert!(ctx.starts_with(HEADER));
+        assert!(ctx.contains("Last session: Fixed auth bug"));
+        assert!(ctx.contains("Active roadmap step: Feature engineering (progress 33%)"));
+        assert!(ctx.contains("Pending next steps:\n- Add retry tests\n- Write docs"));
+        assert!(ctx.contains("Blocked roadmap items: Deploy to prod"));
+        assert!(ctx.contains("Uncommitted changes: +3 -1 across 1 file(s): src/lib.rs"));
+        assert!(ctx.contains("Recent decisions: Use SQLite (2026-01-02)"));
+        assert!(ctx.contains("When the user says 'session summary' (or runs /seslog-summary):"));
+        assert!(!ctx.contains("No roadmap yet"));
+    }
+
+    #[test]
+    fn test_compose_without_roadmap_explains_format() {
+        let ctx = ContextComposer::with_defaults(5000, &TriggerConfig::default()).compose(&ContextInput { slug: "api-1a2b3c4d".into(), ..ContextInput::default() });
+        assert!(ctx.contains("~/.seslog/projects/api-1a2b3c4d/roadmap.md"));
+    }
+
+    #[test]
+    fn test_compose_drops_lowest_priority_first() {
+        let full = ContextComposer::with_defaults(5000, &TriggerConfig::default()).compose(&input());
+        let budget = full.chars().count() - 10;
+        let ctx = ContextComposer::with_defaults(budget, &TriggerConfig::default()).compose(&input());
+        assert!(ctx.chars().count() <= budget);
+        assert!(!ctx.contains("Recent decisions"));
+        assert!(ctx.contains("Uncommitted changes"));
+    }
+
+    #[test]
+    fn test_compose_truncates_long_lines_to_budget() {
+        let mut long = input();
+        long.sessions[0].summary = "x".repeat(2000);
+        let ctx = ContextComposer::new(300).with_source(LastSummary).with_source(NextSteps).compose(&long);
+        assert!(ctx.chars().count() <= 300);
+        assert!(ctx.contains("Last session: xxx"));
+        assert!(!ctx.contains("Pending next steps"));
+    }
+
+    #[test]
+    fn test_next_steps_dedupes_long_steps() {
+        let long = "y".repeat(400);
+        let mut repeated = input();
+        repeated.sessions = vec![session("a", &long), session("b", &format!("{}\n", long))];
+        let section = NextSteps.collect(&repeated).unwrap();
+        assert_eq!(section.lines, vec!["y".repeat(300)]);
+    }
+
+    #[test]
+    fn test_custom_source_is_pluggable() {
+        struct Fixed;
+        impl ContextSource for Fixed {
+            fn collect(&self, _: &ContextInput) -> Option<ContextSection> {
+                Some(ContextSection::new("Reminder", 255, vec!["run migrations".into()]))
+            }
+        }
+        let ctx = ContextComposer::new(200).with_source(LastSummary).with_source(Fixed).compose(&input());
+        assert_eq!(ctx, format!("{}\nReminder: run migrations\nLast session: Fixed auth bug", HEADER));
+    }
+
+    #[test]
+    fn test_branch_line() {
+        let mut with_git = input();
+        with_git.git = Some(GitContext {
+            branch: Some("feature/export".into()),
+            worktree: Some("/src/api-export".into()),
+            linked_worktree: true,
+            upstream: Some("origin/feature/export".into()),
+            ahead: 2,
+            behind: 0,
+        });
+        let ctx = ContextComposer::new(500).with_source(Branch).compose(&with_git);
+        assert!(ctx.contains("Branch: feature/export (worktree /src/api-export; 2 ahead, 0 behind origin/feature/export)"));
+
+        with_git.git.as_mut().unwrap().branch = None;
+        assert!(!ContextComposer::new(500).with_source(Branch).compose(&with_git).contains("Branch:"));
+    }
+}
@@ -0,0 +1,140 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Per-project decision log, one JSON object per line. Append-only so that
+/// concurrent machines merge cleanly through git sync.
+pub const DECISIONS_FILE: &str = "decisions.jsonl";
+
+/// How a decision entered the log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecisionSource {
+    /// Recorded explicitly with `seslog decision`.
+    #[default]
+    Manual,
+    /// Extracted from "we decided to…" statements in a session transcript.
+    Transcript,
+}
+
+impl DecisionSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DecisionSource::Manual => "manual",
+            DecisionSource::Transcript => "transcript",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decision {
+    pub date: DateTime<Utc>,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub why: String,
+    /// Session that was in flight when the decision was recorded, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub source: DecisionSource,
+}
+
+pub fn append_decision(project_dir: &Path, decision: &Decision) -> Result<()> {
+    fs::create_dir_all(project_dir)?;
+    let mut line = serde_json::to_string(decision)?;
+    line.push('\n');
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(project_dir.join(DECISIONS_FILE))?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// All decisions of a project in file order. Malformed lines (e.g. a half-merged
+/// sync conflict) are skipped rather than failing the whole log.
+pub fn load_decisions(project_dir: &Path) -> Vec<Decision> {
+    fs::read_to_string(project_dir.join(DECISIONS_FILE))
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+const DECISION_MARKERS: &[&str] = &[
+    "we decided to",
+    "we've decided to",
+    "we have decided to",
+    "i decided to",
+    "decided to go with",
+    "decision:",
+    "we'll go with",
+    "we will go with",
+    "karar verdik",
+    "karar:",
+];
+
+const MAX_DECISION_CHARS: usize = 200;
+
+/// Pull "we decided to…" style statements out of free text, one per sentence.
+/// Repeats that differ only in case are dropped.
+pub fn extract_decisions(text: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    for sentence in text.split(['\n', '.', '!', '?']) {
+        let trimmed = sentence.trim().trim_start_matches(['-', '*', ' ']);
+        let lower = trimmed.to_lowercase();
+        if !DECISION_MARKERS.iter().any(|m| lower.contains(m)) {
+            continue;
+        }
+        let decision: String = trimmed.chars().take(MAX_DECISION_CHARS).collect();
+        if !found.iter().any(|f: &String| f.to_lowercase() == decision.to_lowercase()) {
+            found.push(decision);
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_and_load_skips_bad_lines() {
+        let tmp = TempDir::new().unwrap();
+        let first = Decision {
+            date: "2026-03-01T10:00:00Z".parse().unwrap(),
+            title: "Use SQLite FTS5 for search".into(),
+            why: "No extra service to run".into(),
+            session_id: Some("ses_abc".into()),
+            source: DecisionSource::Manual,
+        };
+        append_decision(tmp.path(), &first).unwrap();
+        fs::OpenOptions::new().append(true).open(tmp.path().join(DECISIONS_FILE)).unwrap()
+            .write_all(b"<<<<<<< HEAD\n").unwrap();
+        let second = Decision { title: "Drop Tauri".into(), why: String::new(), session_id: None, ..first.clone() };
+        append_decision(tmp.path(), &second).unwrap();
+
+        let loaded = load_decisions(tmp.path());
+        assert_eq!(loaded, vec![first, second]);
+        assert!(load_decisions(&tmp.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_extract_decisions_from_text() {
+        let text = "Looked at both options. We decided to keep JSONL for the log. \
+                    Tests pass!\n- Decision: retry pushes three times\nwe decided to keep JSONL for the log";
+        assert_eq!(extract_decisions(text), vec![
+            "We decided to keep JSONL for the log".to_string(),
+            "Decision: retry pushes three times".to_string(),
+        ]);
+        assert_eq!(extract_decisions("Postgres yerine SQLite kullanmaya karar verdik"), vec![
+            "Postgres yerine SQLite kullanmaya karar verdik".to_string(),
+        ]);
+        assert!(extract_decisions("Fixed the flaky test").is_empty());
+    }
+}
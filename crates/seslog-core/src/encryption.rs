@@ -0,0 +1,323 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use age::armor::{ArmoredReader, ArmoredWriter, Format};
+use age::secrecy::ExposeSecret;
+use age::x25519;
+use anyhow::{Context, Result};
+use crate::config::{self, EncryptionConfig, LOCAL_CONFIG_FILE};
+
+const ARMOR_BEGIN: &[u8] = b"-----BEGIN AGE ENCRYPTED FILE-----";
+const BINARY_BEGIN: &[u8] = b"age-encryption.org/";
+
+/// Machine-local files that must never reach the sync remote.
+pub const IGNORED_ENTRIES: &[&str] = &[LOCAL_CONFIG_FILE, ".keys/"];
+
+/// Record directories under `projects/<slug>/` whose JSON files are encrypted.
+const RECORD_DIRS: &[&str] = &["sessions", "checkpoints"];
+
+/// Synced files that stay plaintext with encryption on, relative to the data directory.
+/// Decision logs are appended to on every machine and merged line by line on sync,
+/// which ciphertext can't be.
+pub const PLAINTEXT_FILES: &[&str] = &[
+    "config.toml",
+    "projects.toml",
+    "pricing.toml",
+    "machines/",
+    "templates/",
+    "projects/*/meta.toml",
+    "projects/*/roadmap.md",
+    "projects/*/decisions.jsonl",
+    "projects/*/templates/",
+];
+
+/// Whether `bytes` is an age file (armored or binary) rather than plaintext JSON.
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    let start = bytes.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(bytes.len());
+    bytes[start..].starts_with(ARMOR_BEGIN) || bytes[start..].starts_with(BINARY_BEGIN)
+}
+
+/// This machine's age identities plus everyone records are encrypted to.
+pub struct Keyring {
+    identities: Vec<x25519::Identity>,
+    recipients: Vec<x25519::Recipient>,
+}
+
+impl Keyring {
+    /// The keyring configured in `<data_dir>/local.toml`, or `None` when encryption is disabled.
+    pub fn load(data_dir: &Path) -> Result<Option<Self>> {
+        let local = config::load_local_config(&data_dir.join(LOCAL_CONFIG_FILE))?;
+        if !local.encryption.enabled {
+            return Ok(None);
+        }
+        Self::from_config(data_dir, &local.encryption).map(Some)
+    }
+
+    pub fn from_config(data_dir: &Path, cfg: &EncryptionConfig) -> Result<Self> {
+        let identity_path = identity_path(data_dir, cfg);
+        let content = fs::read_to_string(&identity_path)
+            .with_context(|| format!("reading age identity {}", identity_path.display()))?;
+        let identities = parse_identities(&content)?;
+        if identities.is_empty() {
+            anyhow::bail!("no age identity found in {}", identity_path.display());
+        }
+        let recipients = cfg.recipients.iter()
+            .map(|r| x25519::Recipient::from_str(r.trim())
+                .map_err(|e| anyhow::anyhow!("invalid recipient '{}': {}", r, e)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(identities, recipients))
+    }
+
+    /// Own public keys are always added to `recipients`, so this machine can read what it writes.
+    pub fn new(identities: Vec<x25519::Identity>, recipients: Vec<x25519::Recipient>) -> Self {
+        let mut all: Vec<x25519::Recipient> = identities.iter().map(|i| i.to_public()).collect();
+        for r in recipients {
+            if !all.iter().any(|a| a.to_string() == r.to_string()) {
+                all.push(r);
+            }
+        }
+        Self { identities, recipients: all }
+    }
+
+    /// Public keys records are encrypted to, own keys first.
+    pub fn recipients(&self) -> Vec<String> {
+        self.recipients.iter().map(|r| r.to_string()).collect()
+    }
+
+    /// ASCII-armored age ciphertext, so encrypted records still diff as text in git.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let encryptor = age::Encryptor::with_recipients(
+            self.recipients.iter().map(|r| r as &dyn age::Recipient),
+        )?;
+        let mut out = Vec::new();
+        let armored = ArmoredWriter::wrap_output(&mut out, Format::AsciiArmor)?;
+        let mut writer = encryptor.wrap_output(armored)?;
+        writer.write_all(plaintext)?;
+        writer.finish()?.finish()?;
+        Ok(out)
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let decryptor = age::Decryptor::new_buffered(ArmoredReader::new(ciphertext))?;
+        let mut reader = decryptor.decrypt(self.identities.iter().map(|i| i as &dyn age::Identity))?;
+        let mut plaintext = Vec::new();
+        reader.read_to_end(&mut plaintext)?;
+        Ok(plaintext)
+    }
+}
+
+fn identity_path(data_dir: &Path, cfg: &EncryptionConfig) -> PathBuf {
+    let path = PathBuf::from(&cfg.identity_file);
+    if path.is_absolute() { path } else { data_dir.join(path) }
+}
+
+/// age identity file format: one `AGE-SECRET-KEY-…` per line, `#` comments allowed.
+fn parse_identities(content: &str) -> Result<Vec<x25519::Identity>> {
+    content.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| x25519::Identity::from_str(l).map_err(|e| anyhow::anyhow!("invalid age identity: {}", e)))
+        .collect()
+}
+
+/// Write a fresh identity to the configured identity file (owner-only permissions) and
+/// return its public key. An existing identity file is kept and its key returned.
+pub fn init_identity(data_dir: &Path, cfg: &EncryptionConfig) -> Result<String> {
+    let path = identity_path(data_dir, cfg);
+    if path.exists() {
+        let identities = parse_identities(&fs::read_to_string(&path)?)?;
+        let first = identities.first()
+            .ok_or_else(|| anyhow::anyhow!("no age identity found in {}", path.display()))?;
+        return Ok(first.to_public().to_string());
+    }
+    let identity = x25519::Identity::generate();
+    let public = identity.to_public().to_string();
+    let content = format!(
+        "# created: {}\n# public key: {}\n{}\n",
+        chrono::Utc::now().to_rfc3339(),
+        public,
+        identity.to_string().expose_secret()
+    );
+    crate::storage::atomic_write(&path, content.as_bytes())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(public)
+}
+
+/// Data directory that owns a record at `<data>/projects/<slug>/<dir>/<file>.json`.
+fn record_data_dir(path: &Path) -> Option<&Path> {
+    path.ancestors().nth(4)
+}
+
+/// Decrypt a record's bytes with the keyring of the data directory it lives in.
+/// Plaintext is returned unchanged.
+pub fn decode_record(path: &Path, bytes: Vec<u8>) -> Result<String> {
+    if !is_encrypted(&bytes) {
+        return Ok(String::from_utf8(bytes)?);
+    }
+    let data_dir = record_data_dir(path)
+        .ok_or_else(|| anyhow::anyhow!("encrypted file outside a data directory: {}", path.display()))?;
+    let keyring = Keyring::load(data_dir)?.ok_or_else(|| anyhow::anyhow!(
+        "{} is encrypted but encryption is not configured on this machine (see `seslog encryption init`)",
+        path.display()
+    ))?;
+    let plaintext = keyring.decrypt(&bytes)
+        .with_context(|| format!("decrypting {}", path.display()))?;
+    Ok(String::from_utf8(plaintext)?)
+}
+
+/// Read a session or checkpoint file, decrypting it if needed.
+pub fn read_record(path: &Path) -> Result<String> {
+    decode_record(path, fs::read(path)?)
+}
+
+/// Every session and checkpoint JSON file under `<data_dir>/projects`.
+pub fn record_files(data_dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let Ok(projects) = fs::read_dir(data_dir.join("projects")) else { return files };
+    for project in projects.flatten() {
+        for dir in RECORD_DIRS {
+            let Ok(entries) = fs::read_dir(project.path().join(dir)) else { continue };
+            files.extend(entries.flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json")));
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Encrypt plaintext records in place. Already-encrypted files are left alone.
+/// Returns the number of files encrypted.
+pub fn encrypt_records(data_dir: &Path, keyring: &Keyring) -> Result<u32> {
+    let mut count = 0;
+    for path in record_files(data_dir) {
+        let bytes = fs::read(&path)?;
+        if is_encrypted(&bytes) {
+            continue;
+        }
+        crate::storage::atomic_write(&path, &keyring.encrypt(&bytes)?)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Re-encrypt every record to the keyring's current recipients, e.g. after adding a machine.
+pub fn rekey_records(data_dir: &Path, keyring: &Keyring) -> Result<u32> {
+    rewrite_records(data_dir, keyring, |plaintext| keyring.encrypt(&plaintext))
+}
+
+/// Turn every record back into plaintext, e.g. before disabling encryption.
+pub fn decrypt_records(data_dir: &Path, keyring: &Keyring) -> Result<u32> {
+    rewrite_records(data_dir, keyring, Ok)
+}
+
+fn rewrite_records(
+    data_dir: &Path,
+    keyring: &Keyring,
+    output: impl Fn(Vec<u8>) -> Result<Vec<u8>>,
+) -> Result<u32> {
+    let mut count = 0;
+    for path in record_files(data_dir) {
+        let bytes = fs::read(&path)?;
+        let plaintext = if is_encrypted(&bytes) {
+            keyring.decrypt(&bytes).with_context(|| format!("decrypting {}", path.display()))?
+        } else {
+            bytes
+        };
+        crate::storage::atomic_write(&path, &output(plaintext)?)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Append any missing [`IGNORED_ENTRIES`] to the data directory's `.gitignore`.
+pub fn ensure_ignored(data_dir: &Path) -> Result<()> {
+    crate::storage::ensure_gitignored(data_dir, IGNORED_ENTRIES)
+}
+
+/// Called by sync right before staging: when encryption is enabled, make sure keys
+/// stay local and no plaintext record gets committed. Returns the number of files encrypted.
+pub fn prepare_for_commit(data_dir: &Path) -> Result<u32> {
+    let Some(keyring) = Keyring::load(data_dir)? else { return Ok(0) };
+    ensure_ignored(data_dir)?;
+    encrypt_records(data_dir, &keyring)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{write_local_config, LocalConfig};
+    use tempfile::TempDir;
+
+    fn enable(data_dir: &Path, recipients: Vec<String>) -> String {
+        let cfg = EncryptionConfig { enabled: true, recipients, ..Default::default() };
+        let public = init_identity(data_dir, &cfg).unwrap();
+        write_local_config(&data_dir.join(LOCAL_CONFIG_FILE), &LocalConfig { encryption: cfg, ..Default::default() }).unwrap();
+        public
+    }
+
+    fn write_record(data_dir: &Path, rel: &str, body: &str) -> PathBuf {
+        let path = data_dir.join("projects/api").join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, body).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_round_trip_and_detection() {
+        let keyring = Keyring::new(vec![x25519::Identity::generate()], vec![]);
+        let ciphertext = keyring.encrypt(br#"{"summary":"secret plans"}"#).unwrap();
+        assert!(is_encrypted(&ciphertext));
+        assert!(!String::from_utf8_lossy(&ciphertext).contains("secret plans"));
+        assert_eq!(keyring.decrypt(&ciphertext).unwrap(), br#"{"summary":"secret plans"}"#);
+        assert!(!is_encrypted(b"{\"id\": 1}"));
+
+        let stranger = Keyring::new(vec![x25519::Identity::generate()], vec![]);
+        assert!(stranger.decrypt(&ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_other_machine_reads_records_encrypted_to_it() {
+        let laptop = TempDir::new().unwrap();
+        let desktop = TempDir::new().unwrap();
+        let desktop_key = enable(desktop.path(), vec![]);
+        enable(laptop.path(), vec![desktop_key.clone()]);
+
+        let keyring = Keyring::load(laptop.path()).unwrap().unwrap();
+        assert_eq!(keyring.recipients().len(), 2);
+        assert!(keyring.recipients().contains(&desktop_key));
+
+        let session = write_record(laptop.path(), "sessions/ses_1.json", r#"{"id":"ses_1"}"#);
+        let checkpoint = write_record(laptop.path(), "checkpoints/chk_1.json", r#"{"id":"chk_1"}"#);
+        assert_eq!(prepare_for_commit(laptop.path()).unwrap(), 2);
+        assert_eq!(prepare_for_commit(laptop.path()).unwrap(), 0, "already encrypted");
+        assert!(is_encrypted(&fs::read(&session).unwrap()));
+        assert_eq!(read_record(&checkpoint).unwrap(), r#"{"id":"chk_1"}"#);
+
+        let synced = desktop.path().join("projects/api/sessions/ses_1.json");
+        fs::create_dir_all(synced.parent().unwrap()).unwrap();
+        fs::copy(&session, &synced).unwrap();
+        assert_eq!(read_record(&synced).unwrap(), r#"{"id":"ses_1"}"#);
+
+        let gitignore = fs::read_to_string(laptop.path().join(".gitignore")).unwrap();
+        assert!(gitignore.contains("local.toml") && gitignore.contains(".keys/"));
+    }
+
+    #[test]
+    fn test_disabled_leaves_plaintext_and_rejects_ciphertext() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_record(tmp.path(), "sessions/ses_1.json", r#"{"id":"ses_1"}"#);
+        assert_eq!(prepare_for_commit(tmp.path()).unwrap(), 0);
+        assert_eq!(read_record(&path).unwrap(), r#"{"id":"ses_1"}"#);
+
+        let keyring = Keyring::new(vec![x25519::Identity::generate()], vec![]);
+        fs::write(&path, keyring.encrypt(b"{}").unwrap()).unwrap();
+        let err = read_record(&path).unwrap_err().to_string();
+        assert!(err.contains("not configured"), "{}", err);
+    }
+}
@@ -19,7 +19,7 @@ pub enum SeslogError {
     Hook(String),
 
     #[error("git error: {0}")]
-    Git(String),
+    Git(#[from] GitError),
 
     #[error("schema migration needed: found v{found}, expected v{expected}")]
     SchemaMismatch { found: u32, expected: u32 },
@@ -36,6 +36,49 @@ pub enum SeslogError {
 
 pub type Result<T> = std::result::Result<T, SeslogError>;
 
+/// Failure of a libgit2 operation, classified so callers can tell a flaky network
+/// from a credential problem or a diverged remote.
+#[derive(Debug, Error)]
+pub enum GitError {
+    #[error("not a git repository: {0}")]
+    NotARepo(String),
+
+    #[error("authentication failed: {0}")]
+    Auth(String),
+
+    #[error("network error: {0}")]
+    Network(String),
+
+    #[error("push rejected (remote has newer commits): {0}")]
+    Rejected(String),
+
+    #[error("unresolved merge conflict in {0}")]
+    Conflict(String),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+impl From<git2::Error> for GitError {
+    fn from(e: git2::Error) -> Self {
+        let msg = e.message().to_string();
+        match (e.code(), e.class()) {
+            (git2::ErrorCode::Auth, _) => GitError::Auth(msg),
+            (git2::ErrorCode::NotFastForward, _) => GitError::Rejected(msg),
+            (git2::ErrorCode::Conflict | git2::ErrorCode::MergeConflict, _) => GitError::Conflict(msg),
+            (git2::ErrorCode::NotFound, git2::ErrorClass::Repository) => GitError::NotARepo(msg),
+            (_, git2::ErrorClass::Net | git2::ErrorClass::Http | git2::ErrorClass::Ssl | git2::ErrorClass::Ssh) => GitError::Network(msg),
+            _ => GitError::Other(msg),
+        }
+    }
+}
+
+impl From<git2::Error> for SeslogError {
+    fn from(e: git2::Error) -> Self {
+        SeslogError::Git(e.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,6 +95,17 @@ mod tests {
         assert!(err.to_string().contains("invalid json"));
     }
 
+    #[test]
+    fn test_git_error_classification() {
+        let auth = git2::Error::new(git2::ErrorCode::Auth, git2::ErrorClass::Http, "bad token");
+        assert!(matches!(GitError::from(auth), GitError::Auth(_)));
+        let net = git2::Error::new(git2::ErrorCode::GenericError, git2::ErrorClass::Net, "could not resolve host");
+        assert!(matches!(GitError::from(net), GitError::Network(_)));
+        let nff = git2::Error::new(git2::ErrorCode::NotFastForward, git2::ErrorClass::Reference, "cannot push");
+        let err: SeslogError = nff.into();
+        assert!(err.to_string().contains("push rejected"));
+    }
+
     #[test]
     fn test_config_error_display() {
         let err = SeslogError::Config("missing field".into());
@@ -1,6 +1,10 @@
-use std::path::Path;
-use std::process::Command;
-use anyhow::Result;
+use std::path::{Path, PathBuf};
+use git2::{
+    AnnotatedCommit, BranchType, Cred, CredentialType, FetchOptions, IndexAddOption, PushOptions,
+    RemoteCallbacks, Repository, Signature,
+};
+use crate::errors::{GitError, Result, SeslogError};
+use crate::models::{FileChange, FileStatus, GitContext, GitRange, HeadState};
 
 /// Outcome of a git sync operation.
 ///
@@ -12,7 +16,7 @@ pub enum SyncResult {
     LocalOnly,
     /// Working tree is clean, nothing to commit.
     NothingToCommit,
-    /// Network unreachable or transient git error. Contains stderr.
+    /// Network unreachable, authentication failed or another transient error. Contains the message.
     Offline(String),
     /// Rebase conflict that requires manual resolution. Contains instructions.
     Conflict(String),
@@ -20,7 +24,7 @@ pub enum SyncResult {
 
 /// Returns `true` if the git repository at `path` has at least one remote configured.
 pub fn has_remote(path: &Path) -> bool {
-    let repo = match git2::Repository::discover(path) {
+    let repo = match Repository::discover(path) {
         Ok(r) => r,
         Err(_) => return false,
     };
@@ -30,105 +34,430 @@ pub fn has_remote(path: &Path) -> bool {
     }
 }
 
-/// Pulls latest changes from the remote using `git pull --rebase --autostash`.
+fn open(path: &Path) -> Result<Repository> {
+    Repository::discover(path).map_err(|_| GitError::NotARepo(path.display().to_string()).into())
+}
+
+fn workdir(repo: &Repository) -> Result<PathBuf> {
+    repo.workdir()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| GitError::Other("bare repository has no working tree".into()).into())
+}
+
+/// Configured identity, or a fixed fallback so sync works on machines without `user.name`.
+fn signature(repo: &Repository) -> Result<Signature<'static>> {
+    match repo.signature() {
+        Ok(sig) => Ok(sig.to_owned()),
+        Err(_) => Ok(Signature::now("seslog", "seslog@localhost")?),
+    }
+}
+
+/// Name of the checked-out branch, also for an unborn `HEAD` in a fresh repository.
+fn current_branch(repo: &Repository) -> Option<String> {
+    let head = repo.find_reference("HEAD").ok()?;
+    let target = head.symbolic_target()?;
+    target.strip_prefix("refs/heads/").map(String::from)
+}
+
+/// The branch's configured remote, else `origin`, else the first remote.
+fn remote_name(repo: &Repository, branch: &str) -> Result<String> {
+    if let Ok(name) = repo.config()?.get_string(&format!("branch.{}.remote", branch)) {
+        return Ok(name);
+    }
+    let remotes = repo.remotes()?;
+    let names: Vec<&str> = remotes.iter().flatten().collect();
+    names.iter().find(|n| **n == "origin").or(names.first())
+        .map(|n| n.to_string())
+        .ok_or_else(|| GitError::Other("no remote configured".into()).into())
+}
+
+/// One way of answering a credential request, tried in order until the server accepts.
+#[derive(Debug, Clone, PartialEq)]
+enum CredentialSource {
+    SshAgent,
+    SshKey(PathBuf),
+    /// `SESLOG_GIT_TOKEN`, sent as the password for HTTPS remotes.
+    Token(String),
+    /// git's configured credential helpers (`credential.helper`).
+    Helper,
+    /// Kerberos/NTLM negotiation.
+    Default,
+}
+
+const SSH_KEY_NAMES: &[&str] = &["id_ed25519", "id_ecdsa", "id_rsa"];
+
+fn credential_sources(allowed: CredentialType, ssh_dir: Option<&Path>, token: Option<String>) -> Vec<CredentialSource> {
+    let mut sources = Vec::new();
+    if allowed.contains(CredentialType::SSH_KEY) {
+        sources.push(CredentialSource::SshAgent);
+        if let Some(dir) = ssh_dir {
+            sources.extend(SSH_KEY_NAMES.iter()
+                .map(|name| dir.join(name))
+                .filter(|p| p.exists())
+                .map(CredentialSource::SshKey));
+        }
+    }
+    if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+        sources.extend(token.map(CredentialSource::Token));
+        sources.push(CredentialSource::Helper);
+    }
+    if allowed.contains(CredentialType::DEFAULT) {
+        sources.push(CredentialSource::Default);
+    }
+    sources
+}
+
+/// Callbacks answering SSH (agent, then `~/.ssh` keys) and HTTPS (token, then credential
+/// helpers) authentication. libgit2 calls back after each rejected attempt, so every call
+/// moves on to the next source; when they run out the operation fails with an auth error.
+fn remote_callbacks(config: Option<git2::Config>) -> RemoteCallbacks<'static> {
+    let mut callbacks = RemoteCallbacks::new();
+    let mut attempt = 0;
+    let ssh_dir = dirs::home_dir().map(|h| h.join(".ssh"));
+    callbacks.credentials(move |url, username, allowed| {
+        let token = std::env::var("SESLOG_GIT_TOKEN").ok().filter(|t| !t.is_empty());
+        let sources = credential_sources(allowed, ssh_dir.as_deref(), token);
+        let Some(source) = sources.get(attempt) else {
+            return Err(git2::Error::new(
+                git2::ErrorCode::Auth,
+                git2::ErrorClass::Callback,
+                format!("no accepted credentials for {}", url),
+            ));
+        };
+        attempt += 1;
+        let user = username.unwrap_or("git");
+        match source {
+            CredentialSource::SshAgent => Cred::ssh_key_from_agent(user),
+            CredentialSource::SshKey(path) => Cred::ssh_key(user, None, path, None),
+            CredentialSource::Token(token) => Cred::userpass_plaintext(username.unwrap_or("x-access-token"), token),
+            CredentialSource::Helper => match &config {
+                Some(cfg) => Cred::credential_helper(cfg, url, username),
+                None => Err(git2::Error::from_str("no git config for credential helper")),
+            },
+            CredentialSource::Default => Cred::default(),
+        }
+    });
+    callbacks
+}
+
+fn fetch(repo: &Repository, remote: &str) -> Result<()> {
+    let mut remote = repo.find_remote(remote)?;
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(remote_callbacks(repo.config().ok()));
+    remote.fetch::<&str>(&[], Some(&mut options), None)?;
+    Ok(())
+}
+
+/// Stages every change (honouring `.gitignore`, including deletions) and commits it.
+/// Returns `false` when there was nothing to commit.
+///
+/// With encryption enabled in `local.toml`, plaintext session and checkpoint
+/// records are encrypted in place before anything is staged.
+fn commit_all(repo: &Repository, message: &str) -> Result<bool> {
+    crate::encryption::prepare_for_commit(&workdir(repo)?)?;
+
+    let mut index = repo.index()?;
+    index.add_all(["*"], IndexAddOption::DEFAULT, None)?;
+    index.update_all(["*"], None)?;
+    index.write()?;
+    let tree_id = index.write_tree()?;
+
+    let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let unchanged = match &parent {
+        Some(p) => p.tree_id() == tree_id,
+        None => index.is_empty(),
+    };
+    if unchanged {
+        return Ok(false);
+    }
+    let tree = repo.find_tree(tree_id)?;
+    let sig = signature(repo)?;
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)?;
+    Ok(true)
+}
+
+/// The remote-tracking commit for `branch`, or `None` if the remote does not have it yet.
+fn upstream_commit<'r>(repo: &'r Repository, remote: &str, branch: &str) -> Result<Option<AnnotatedCommit<'r>>> {
+    let name = format!("refs/remotes/{}/{}", remote, branch);
+    match repo.find_reference(&name) {
+        Ok(reference) => Ok(Some(repo.reference_to_annotated_commit(&reference)?)),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Whether the local branch has commits the remote does not.
+fn is_ahead(repo: &Repository, remote: &str, branch: &str) -> Result<bool> {
+    let Some(local) = repo.head().ok().and_then(|h| h.target()) else { return Ok(false) };
+    match upstream_commit(repo, remote, branch)? {
+        Some(upstream) => Ok(repo.graph_ahead_behind(local, upstream.id())?.0 > 0),
+        None => Ok(true),
+    }
+}
+
+/// Pulls latest changes from the remote: fetch, then fast-forward or rebase local
+/// commits onto the remote branch. Uncommitted local changes are committed first, so
+/// they take part in the rebase instead of blocking it.
 ///
-/// Returns `LocalOnly` when no remote is configured, `Conflict` when rebase hits conflicts,
-/// and `Offline` for any network or other non-fatal errors.
+/// Rebase conflicts are resolved file by file with [`crate::merge`] (roadmap and
+/// `meta.toml` are merged structurally, config files take the newest side). Only if
+/// that fails is the rebase aborted and `Conflict` returned. Returns `LocalOnly` when
+/// no remote is configured and `Offline` for network or authentication failures.
 pub fn sync_pull(repo_path: &Path) -> Result<SyncResult> {
     if !has_remote(repo_path) {
         return Ok(SyncResult::LocalOnly);
     }
+    let repo = open(repo_path)?;
+    let Some(branch) = current_branch(&repo) else {
+        return Ok(SyncResult::Offline("HEAD is detached".into()));
+    };
+    let remote = remote_name(&repo, &branch)?;
 
-    let output = Command::new("git")
-        .args(["pull", "--rebase", "--autostash"])
-        .current_dir(repo_path)
-        .output()?;
-
-    if output.status.success() {
-        return Ok(SyncResult::Synced);
+    match fetch(&repo, &remote) {
+        Ok(()) => {}
+        Err(SeslogError::Git(e)) => return Ok(SyncResult::Offline(e.to_string())),
+        Err(e) => return Err(e),
     }
+    let Some(upstream) = upstream_commit(&repo, &remote, &branch)? else {
+        return Ok(SyncResult::Synced);
+    };
 
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    commit_all(&repo, "seslog: local changes")?;
 
-    if stderr.contains("CONFLICT") || stderr.contains("conflict") {
-        // Abort the failed rebase so the repo is not left in a broken state.
-        let _ = Command::new("git")
-            .args(["rebase", "--abort"])
-            .current_dir(repo_path)
-            .output();
+    let (analysis, _) = repo.merge_analysis(&[&upstream])?;
+    if analysis.is_up_to_date() {
+        return Ok(SyncResult::Synced);
+    }
+    if analysis.is_unborn() || analysis.is_fast_forward() {
+        let refname = format!("refs/heads/{}", branch);
+        repo.reference(&refname, upstream.id(), true, "seslog: fast-forward")?;
+        repo.set_head(&refname)?;
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+        return Ok(SyncResult::Synced);
+    }
+    rebase_onto(&repo, &upstream)
+}
 
-        return Ok(SyncResult::Conflict(format!(
-            "Sync conflict. Fix manually:\n  cd {} && git status",
-            repo_path.display()
-        )));
+/// Upper bound on replayed commits resolved in one pull, so a broken rebase cannot loop.
+const MAX_REBASE_STEPS: usize = 200;
+
+fn rebase_onto(repo: &Repository, upstream: &AnnotatedCommit) -> Result<SyncResult> {
+    let conflict = || SyncResult::Conflict(format!(
+        "Sync conflict. Fix manually:\n  cd {} && git status",
+        repo.workdir().unwrap_or(repo.path()).display()
+    ));
+    let sig = signature(repo)?;
+    let upstream_time = repo.find_commit(upstream.id())?.time().seconds();
+    let mut rebase = repo.rebase(None, Some(upstream), None, None)?;
+
+    let mut steps = 0;
+    while let Some(op) = rebase.next() {
+        let op = op?;
+        steps += 1;
+        if steps > MAX_REBASE_STEPS {
+            rebase.abort()?;
+            return Ok(conflict());
+        }
+        let mut index = repo.index()?;
+        if index.has_conflicts() {
+            let local_is_newer = repo.find_commit(op.id())?.time().seconds() >= upstream_time;
+            if let Err(e) = resolve_conflicts(repo, &mut index, local_is_newer) {
+                eprintln!("[seslog] WARN: automatic merge failed: {}", e);
+                rebase.abort()?;
+                return Ok(conflict());
+            }
+        }
+        match rebase.commit(None, &sig, None) {
+            Ok(_) => {}
+            // The resolution made the replayed commit empty; drop it.
+            Err(e) if e.code() == git2::ErrorCode::Applied => {}
+            Err(e) => {
+                rebase.abort()?;
+                return Err(e.into());
+            }
+        }
     }
+    rebase.finish(Some(&sig))?;
+    Ok(SyncResult::Synced)
+}
 
-    Ok(SyncResult::Offline(stderr))
+/// Resolve every conflicted index entry with [`crate::merge::merge_file`]. While
+/// rebasing, "our" side is the upstream being rebased onto and "their" side is the
+/// local commit being replayed.
+fn resolve_conflicts(repo: &Repository, index: &mut git2::Index, local_is_newer: bool) -> Result<()> {
+    let root = workdir(repo)?;
+    let conflicts = index.conflicts()?.collect::<std::result::Result<Vec<_>, _>>()?;
+    let content = |entry: &Option<git2::IndexEntry>| -> Result<Option<String>> {
+        match entry {
+            Some(e) => Ok(Some(String::from_utf8_lossy(repo.find_blob(e.id)?.content()).into_owned())),
+            None => Ok(None),
+        }
+    };
+    for conflict in conflicts {
+        let Some(entry) = conflict.our.as_ref().or(conflict.their.as_ref()).or(conflict.ancestor.as_ref()) else {
+            continue;
+        };
+        let path = PathBuf::from(String::from_utf8_lossy(&entry.path).into_owned());
+        let merged = crate::merge::merge_file(
+            &path,
+            content(&conflict.ancestor)?.as_deref(),
+            content(&conflict.their)?.as_deref(),
+            content(&conflict.our)?.as_deref(),
+            local_is_newer,
+        )?;
+        match merged {
+            Some(text) => {
+                crate::storage::atomic_write(&root.join(&path), text.as_bytes())?;
+                index.add_path(&path)?;
+            }
+            None => {
+                let _ = std::fs::remove_file(root.join(&path));
+                index.remove_path(&path)?;
+            }
+        }
+    }
+    if index.has_conflicts() {
+        return Err(GitError::Conflict("unmerged paths remain".into()).into());
+    }
+    index.write()?;
+    Ok(())
 }
 
 /// Stages all changes, commits with `commit_msg`, and pushes to the remote.
 ///
 /// Returns `LocalOnly` when no remote is configured, `NothingToCommit` when the
-/// working tree is clean, and `Offline` for any transient failure (the commit
-/// remains local and will be pushed on the next startup pull).
+/// working tree is clean and nothing is waiting to be pushed, and `Offline` for any
+/// transient failure. A failed push is recorded in [`crate::sync_queue`] and retried
+/// with backoff by [`retry_pending_push`]; a push rejected because the remote moved
+/// on pulls (merging conflicts) and pushes again.
 pub fn sync_push(repo_path: &Path, commit_msg: &str) -> Result<SyncResult> {
     if !has_remote(repo_path) {
         return Ok(SyncResult::LocalOnly);
     }
+    let repo = open(repo_path)?;
+    let Some(branch) = current_branch(&repo) else {
+        return Ok(SyncResult::Offline("HEAD is detached".into()));
+    };
+    let remote = remote_name(&repo, &branch)?;
 
-    // Stage all changes.
-    let add_output = Command::new("git")
-        .args(["add", "."])
-        .current_dir(repo_path)
-        .output()?;
+    // Nothing new, but an earlier commit may still be waiting for the network.
+    if !commit_all(&repo, commit_msg)? && !is_ahead(&repo, &remote, &branch)? {
+        crate::sync_queue::clear(repo_path)?;
+        return Ok(SyncResult::NothingToCommit);
+    }
 
-    if !add_output.status.success() {
-        let stderr = String::from_utf8_lossy(&add_output.stderr).to_string();
-        return Ok(SyncResult::Offline(stderr));
+    push_tracked(repo_path)
+}
+
+/// Push a commit that failed to push earlier, if its backoff has elapsed.
+/// Returns `None` when nothing is pending or the next attempt is not due yet.
+pub fn retry_pending_push(repo_path: &Path) -> Result<Option<SyncResult>> {
+    match crate::sync_queue::load_pending(repo_path) {
+        Some(pending) if pending.is_due(chrono::Utc::now()) => push_tracked(repo_path).map(Some),
+        _ => Ok(None),
     }
+}
 
-    // Check if anything is staged.
-    let diff_output = Command::new("git")
-        .args(["diff", "--cached", "--quiet"])
-        .current_dir(repo_path)
-        .output()?;
+/// Push, pulling and retrying once if the remote has moved on. The outcome is
+/// recorded in the retry queue.
+fn push_tracked(repo_path: &Path) -> Result<SyncResult> {
+    let repo = open(repo_path)?;
+    let Some(branch) = current_branch(&repo) else {
+        return Ok(SyncResult::Offline("HEAD is detached".into()));
+    };
+    let remote = remote_name(&repo, &branch)?;
+
+    let mut outcome = push(&repo, &remote, &branch);
+    if matches!(outcome, Err(SeslogError::Git(GitError::Rejected(_)))) {
+        outcome = match sync_pull(repo_path)? {
+            SyncResult::Synced => push(&repo, &remote, &branch),
+            other => return track(repo_path, other),
+        };
+    }
+    let result = match outcome {
+        Ok(()) => SyncResult::Synced,
+        Err(SeslogError::Git(e)) => SyncResult::Offline(e.to_string()),
+        Err(e) => return Err(e),
+    };
+    track(repo_path, result)
+}
 
-    if diff_output.status.success() {
-        // Exit code 0 means no differences — nothing to commit.
-        return Ok(SyncResult::NothingToCommit);
+fn track(repo_path: &Path, result: SyncResult) -> Result<SyncResult> {
+    match &result {
+        SyncResult::Synced => crate::sync_queue::clear(repo_path)?,
+        SyncResult::Offline(e) | SyncResult::Conflict(e) => {
+            crate::sync_queue::record_failure(repo_path, e, chrono::Utc::now())?;
+        }
+        _ => {}
     }
+    Ok(result)
+}
 
-    // Commit.
-    let commit_output = Command::new("git")
-        .args(["commit", "-m", commit_msg])
-        .current_dir(repo_path)
-        .output()?;
+fn push(repo: &Repository, remote_name: &str, branch: &str) -> Result<()> {
+    let mut remote = repo.find_remote(remote_name)?;
+    let rejected = std::cell::RefCell::new(None);
+    {
+        let mut callbacks = remote_callbacks(repo.config().ok());
+        callbacks.push_update_reference(|refname, status| {
+            if let Some(msg) = status {
+                *rejected.borrow_mut() = Some(format!("{}: {}", refname, msg));
+            }
+            Ok(())
+        });
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+        let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch);
+        remote.push(&[refspec.as_str()], Some(&mut options))?;
+    }
+    if let Some(msg) = rejected.into_inner() {
+        return Err(GitError::Rejected(msg).into());
+    }
 
-    if !commit_output.status.success() {
-        let stderr = String::from_utf8_lossy(&commit_output.stderr).to_string();
-        return Ok(SyncResult::Offline(stderr));
+    // Track the remote branch so the next pull knows what to rebase onto.
+    if let Ok(mut local) = repo.find_branch(branch, BranchType::Local) {
+        if local.upstream().is_err() {
+            let _ = local.set_upstream(Some(&format!("{}/{}", remote_name, branch)));
+        }
     }
+    Ok(())
+}
 
-    // Push.
-    let push_output = Command::new("git")
-        .args(["push"])
-        .current_dir(repo_path)
-        .output()?;
+/// Whether the working tree has uncommitted changes (ignored files excluded).
+pub fn has_uncommitted_changes(path: &Path) -> bool {
+    let Ok(repo) = Repository::discover(path) else { return false };
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    repo.statuses(Some(&mut options)).is_ok_and(|s| !s.is_empty())
+}
+
+/// Commit time of `HEAD`, in the committer's timezone.
+pub fn last_commit_time(path: &Path) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    let repo = Repository::discover(path).ok()?;
+    let time = repo.head().ok()?.peel_to_commit().ok()?.time();
+    let offset = chrono::FixedOffset::east_opt(time.offset_minutes() * 60)?;
+    chrono::DateTime::from_timestamp(time.seconds(), 0).map(|t| t.with_timezone(&offset))
+}
 
-    if !push_output.status.success() {
-        let stderr = String::from_utf8_lossy(&push_output.stderr).to_string();
-        return Ok(SyncResult::Offline(stderr));
+/// Create a repository at `path` unless it already is one.
+pub fn init_repo(path: &Path) -> Result<()> {
+    if !is_git_repo(path) {
+        Repository::init(path)?;
     }
+    Ok(())
+}
 
-    Ok(SyncResult::Synced)
+pub fn add_remote(path: &Path, name: &str, url: &str) -> Result<()> {
+    open(path)?.remote(name, url)?;
+    Ok(())
 }
 
 pub fn is_git_repo(path: &Path) -> bool {
-    git2::Repository::discover(path).is_ok()
+    Repository::discover(path).is_ok()
 }
 
 pub fn diff_stat(cwd: &Path) -> Result<Option<String>> {
-    let repo = match git2::Repository::discover(cwd) {
+    let repo = match Repository::discover(cwd) {
         Ok(r) => r,
         Err(_) => return Ok(None),
     };
@@ -145,7 +474,7 @@ pub fn diff_stat(cwd: &Path) -> Result<Option<String>> {
 }
 
 pub fn recent_commits(cwd: &Path, max: usize) -> Result<Vec<String>> {
-    let repo = git2::Repository::discover(cwd)?;
+    let repo = open(cwd)?;
     let mut revwalk = repo.revwalk()?;
     revwalk.push_head()?;
     revwalk.set_sorting(git2::Sort::TIME)?;
@@ -158,8 +487,112 @@ pub fn recent_commits(cwd: &Path, max: usize) -> Result<Vec<String>> {
     Ok(commits)
 }
 
+/// `HEAD` commit and branch of the repository containing `cwd`, or `None` outside a repository.
+pub fn head_state(cwd: &Path) -> Option<HeadState> {
+    let repo = Repository::discover(cwd).ok()?;
+    let sha = repo.head().ok().and_then(|h| h.target()).map(|oid| oid.to_string());
+    Some(HeadState { sha, branch: current_branch(&repo) })
+}
+
+/// Branch, worktree and upstream tracking state of the repository containing `cwd`,
+/// or `None` outside a repository.
+pub fn git_context(cwd: &Path) -> Option<GitContext> {
+    let repo = Repository::discover(cwd).ok()?;
+    let branch = current_branch(&repo);
+    let mut context = GitContext {
+        branch: branch.clone(),
+        worktree: repo.workdir().map(|p| p.to_string_lossy().trim_end_matches(['/', '\\']).to_string()),
+        linked_worktree: repo.is_worktree(),
+        ..GitContext::default()
+    };
+    let upstream = branch
+        .and_then(|b| repo.find_branch(&b, BranchType::Local).ok())
+        .and_then(|b| b.upstream().ok());
+    if let Some(upstream) = upstream {
+        context.upstream = upstream.name().ok().flatten().map(String::from);
+        let local = repo.head().ok().and_then(|h| h.target());
+        if let (Some(local), Some(remote)) = (local, upstream.get().target()) {
+            if let Ok((ahead, behind)) = repo.graph_ahead_behind(local, remote) {
+                context.ahead = ahead as u32;
+                context.behind = behind as u32;
+            }
+        }
+    }
+    Some(context)
+}
+
+/// Upper bound on commits listed for one session range.
+const MAX_RANGE_COMMITS: usize = 100;
+
+/// Commits reachable from `HEAD` but not from `base`, newest first, as `<short sha> <summary>`.
+/// With no `base` (the repository had no commits when the session started) every commit counts.
+pub fn commits_since(cwd: &Path, base: Option<&str>) -> Result<Vec<String>> {
+    let repo = open(cwd)?;
+    if repo.head().is_err() {
+        return Ok(vec![]);
+    }
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    if let Some(base) = base {
+        revwalk.hide(git2::Oid::from_str(base)?)?;
+    }
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    let mut commits = Vec::new();
+    for oid in revwalk.take(MAX_RANGE_COMMITS) {
+        let commit = repo.find_commit(oid?)?;
+        let short = commit.id().to_string()[..7].to_string();
+        commits.push(format!("{} {}", short, commit.summary().unwrap_or("(no message)")));
+    }
+    Ok(commits)
+}
+
+/// Everything changed since `base`: committed, staged, unstaged and untracked (ignored
+/// files excluded), with per-file line counts. Renames are detected.
+pub fn session_range(cwd: &Path, base: Option<&str>) -> Result<GitRange> {
+    let repo = open(cwd)?;
+    let base_tree = match base {
+        Some(sha) => Some(repo.find_commit(git2::Oid::from_str(sha)?)?.tree()?),
+        None => None,
+    };
+    let mut options = git2::DiffOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true).show_untracked_content(true);
+    let mut diff = repo.diff_tree_to_workdir_with_index(base_tree.as_ref(), Some(&mut options))?;
+    diff.find_similar(Some(git2::DiffFindOptions::new().renames(true).for_untracked(true)))?;
+
+    let mut files = Vec::new();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let status = match delta.status() {
+            git2::Delta::Added | git2::Delta::Untracked | git2::Delta::Copied => FileStatus::Added,
+            git2::Delta::Deleted => FileStatus::Deleted,
+            git2::Delta::Renamed => FileStatus::Renamed,
+            _ => FileStatus::Modified,
+        };
+        let Some(path) = delta.new_file().path().or(delta.old_file().path()) else { continue };
+        let (_, insertions, deletions) = match git2::Patch::from_diff(&diff, idx)? {
+            Some(patch) => patch.line_stats()?,
+            None => (0, 0, 0),
+        };
+        files.push(FileChange {
+            path: path.to_string_lossy().to_string(),
+            status,
+            insertions: insertions as u32,
+            deletions: deletions as u32,
+        });
+    }
+
+    let head = head_state(cwd).unwrap_or_default();
+    Ok(GitRange {
+        base: base.map(String::from),
+        head: head.sha,
+        branch: head.branch,
+        insertions: files.iter().map(|f| f.insertions).sum(),
+        deletions: files.iter().map(|f| f.deletions).sum(),
+        files,
+    })
+}
+
 pub fn changed_files(cwd: &Path) -> Result<Vec<String>> {
-    let repo = match git2::Repository::discover(cwd) {
+    let repo = match Repository::discover(cwd) {
         Ok(r) => r,
         Err(_) => return Ok(vec![]),
     };
@@ -183,19 +616,46 @@ pub fn changed_files(cwd: &Path) -> Result<Vec<String>> {
 mod tests {
     use super::*;
     use tempfile::TempDir;
-    use std::process::Command;
+
+    fn configure(repo: &Repository) {
+        let mut config = repo.config().unwrap();
+        config.set_str("user.email", "test@test.com").unwrap();
+        config.set_str("user.name", "Test").unwrap();
+    }
 
     fn init_test_repo() -> TempDir {
         let tmp = TempDir::new().unwrap();
-        Command::new("git").args(["init"]).current_dir(tmp.path()).output().unwrap();
-        Command::new("git").args(["config", "user.email", "test@test.com"]).current_dir(tmp.path()).output().unwrap();
-        Command::new("git").args(["config", "user.name", "Test"]).current_dir(tmp.path()).output().unwrap();
+        let repo = Repository::init(tmp.path()).unwrap();
+        configure(&repo);
         std::fs::write(tmp.path().join("file.txt"), "hello").unwrap();
-        Command::new("git").args(["add", "."]).current_dir(tmp.path()).output().unwrap();
-        Command::new("git").args(["commit", "-m", "initial"]).current_dir(tmp.path()).output().unwrap();
+        assert!(commit_all(&repo, "initial").unwrap());
         tmp
     }
 
+    fn init_bare() -> TempDir {
+        let bare = TempDir::new().unwrap();
+        Repository::init_bare(bare.path()).unwrap();
+        bare
+    }
+
+    /// Equivalent of `git clone`: an empty repo with `origin` set, then a pull.
+    fn clone_of(bare: &Path) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        configure(&repo);
+        repo.remote("origin", &bare.to_string_lossy()).unwrap();
+        assert_eq!(sync_pull(dir.path()).unwrap(), SyncResult::Synced);
+        dir
+    }
+
+    /// Commit summaries on every branch of `bare`.
+    fn remote_log(bare: &Path) -> Vec<String> {
+        let repo = Repository::open_bare(bare).unwrap();
+        let mut revwalk = repo.revwalk().unwrap();
+        revwalk.push_glob("refs/heads/*").unwrap();
+        revwalk.map(|oid| repo.find_commit(oid.unwrap()).unwrap().summary().unwrap().to_string()).collect()
+    }
+
     #[test]
     fn test_is_git_repo_true() {
         let tmp = init_test_repo();
@@ -221,6 +681,7 @@ mod tests {
         std::fs::write(tmp.path().join("file.txt"), "modified").unwrap();
         let stat = diff_stat(tmp.path()).unwrap();
         assert!(stat.is_some());
+        assert!(has_uncommitted_changes(tmp.path()));
     }
 
     #[test]
@@ -229,6 +690,68 @@ mod tests {
         let commits = recent_commits(tmp.path(), 5).unwrap();
         assert!(!commits.is_empty());
         assert!(commits[0].contains("initial"));
+        assert!(last_commit_time(tmp.path()).is_some());
+    }
+
+    #[test]
+    fn test_recent_commits_not_a_repo() {
+        let tmp = TempDir::new().unwrap();
+        assert!(matches!(recent_commits(tmp.path(), 5), Err(SeslogError::Git(GitError::NotARepo(_)))));
+    }
+
+    #[test]
+    fn test_session_range_covers_commits_and_worktree() {
+        let tmp = init_test_repo();
+        let repo = Repository::open(tmp.path()).unwrap();
+        let start = head_state(tmp.path()).unwrap();
+        assert!(start.sha.is_some());
+
+        std::fs::write(tmp.path().join("file.txt"), "hello\nworld\n").unwrap();
+        assert!(commit_all(&repo, "extend file").unwrap());
+        std::fs::write(tmp.path().join("notes.md"), "one\ntwo\n").unwrap();
+
+        let commits = commits_since(tmp.path(), start.sha.as_deref()).unwrap();
+        assert_eq!(commits.len(), 1);
+        assert!(commits[0].ends_with(" extend file"));
+
+        let range = session_range(tmp.path(), start.sha.as_deref()).unwrap();
+        assert_eq!(range.base, start.sha);
+        assert_ne!(range.head, start.sha);
+        assert_eq!(range.branch, start.branch);
+        assert_eq!(range.files.len(), 2);
+        let notes = range.files.iter().find(|f| f.path == "notes.md").unwrap();
+        assert_eq!((notes.status, notes.insertions, notes.deletions), (FileStatus::Added, 2, 0));
+        let file = range.files.iter().find(|f| f.path == "file.txt").unwrap();
+        assert_eq!((file.status, file.insertions, file.deletions), (FileStatus::Modified, 2, 1));
+        assert_eq!((range.insertions, range.deletions), (4, 1));
+        assert_eq!(range.diff_stat().as_deref(), Some("+4 -1 across 2 file(s)"));
+    }
+
+    #[test]
+    fn test_session_range_unchanged_is_empty() {
+        let tmp = init_test_repo();
+        let start = head_state(tmp.path()).unwrap();
+        let range = session_range(tmp.path(), start.sha.as_deref()).unwrap();
+        assert!(range.files.is_empty() && range.diff_stat().is_none());
+        assert!(commits_since(tmp.path(), start.sha.as_deref()).unwrap().is_empty());
+        assert!(head_state(TempDir::new().unwrap().path()).is_none());
+    }
+
+    #[test]
+    fn test_git_context_detects_linked_worktree() {
+        let tmp = init_test_repo();
+        let main = git_context(tmp.path()).unwrap();
+        assert!(!main.linked_worktree && main.upstream.is_none());
+        let dir_name = tmp.path().file_name().unwrap().to_string_lossy().to_string();
+        assert!(main.worktree.as_deref().is_some_and(|w| w.ends_with(&dir_name)));
+
+        let wt_parent = TempDir::new().unwrap();
+        let wt_path = wt_parent.path().join("feature");
+        Repository::open(tmp.path()).unwrap().worktree("feature", &wt_path, None).unwrap();
+        let linked = git_context(&wt_path).unwrap();
+        assert!(linked.linked_worktree);
+        assert_eq!(linked.branch.as_deref(), Some("feature"));
+        assert_ne!(linked.worktree, main.worktree);
     }
 
     // --- sync tests ---
@@ -242,18 +765,8 @@ mod tests {
     #[test]
     fn test_has_remote_true() {
         let tmp = init_test_repo();
-        // Create a bare remote and add it as origin.
-        let bare = TempDir::new().unwrap();
-        Command::new("git")
-            .args(["init", "--bare"])
-            .current_dir(bare.path())
-            .output()
-            .unwrap();
-        Command::new("git")
-            .args(["remote", "add", "origin", &bare.path().to_string_lossy()])
-            .current_dir(tmp.path())
-            .output()
-            .unwrap();
+        let bare = init_bare();
+        add_remote(tmp.path(), "origin", &bare.path().to_string_lossy()).unwrap();
         assert!(has_remote(tmp.path()));
     }
 
@@ -273,66 +786,91 @@ mod tests {
 
     #[test]
     fn test_sync_push_commits_and_pushes() {
-        // 1. Create a bare remote.
-        let bare = TempDir::new().unwrap();
-        Command::new("git")
-            .args(["init", "--bare"])
-            .current_dir(bare.path())
-            .output()
-            .unwrap();
-
-        // 2. Clone the bare remote into a workdir.
-        let workdir = TempDir::new().unwrap();
-        Command::new("git")
-            .args(["clone", &bare.path().to_string_lossy(), "."])
-            .current_dir(workdir.path())
-            .output()
-            .unwrap();
-        Command::new("git")
-            .args(["config", "user.email", "test@test.com"])
-            .current_dir(workdir.path())
-            .output()
-            .unwrap();
-        Command::new("git")
-            .args(["config", "user.name", "Test"])
-            .current_dir(workdir.path())
-            .output()
-            .unwrap();
-
-        // Create an initial commit so we have a branch to push to.
+        let bare = init_bare();
+        let workdir = clone_of(bare.path());
+
         std::fs::write(workdir.path().join("seed.txt"), "seed").unwrap();
-        Command::new("git")
-            .args(["add", "."])
-            .current_dir(workdir.path())
-            .output()
-            .unwrap();
-        Command::new("git")
-            .args(["commit", "-m", "seed"])
-            .current_dir(workdir.path())
-            .output()
-            .unwrap();
-        Command::new("git")
-            .args(["push", "-u", "origin", "HEAD"])
-            .current_dir(workdir.path())
-            .output()
-            .unwrap();
-
-        // 3. Write a new file and sync_push.
+        assert_eq!(sync_push(workdir.path(), "seed").unwrap(), SyncResult::Synced);
+
         std::fs::write(workdir.path().join("new.txt"), "data").unwrap();
         let result = sync_push(workdir.path(), "sync: add new.txt").unwrap();
         assert_eq!(result, SyncResult::Synced);
+        assert!(!has_uncommitted_changes(workdir.path()));
+        assert_eq!(sync_push(workdir.path(), "again").unwrap(), SyncResult::NothingToCommit);
+
+        let tracking = git_context(workdir.path()).unwrap();
+        assert!(tracking.upstream.as_deref().is_some_and(|u| u.starts_with("origin/")));
+        assert_eq!((tracking.ahead, tracking.behind), (0, 0));
+        std::fs::write(workdir.path().join("local.txt"), "local").unwrap();
+        commit_all(&Repository::open(workdir.path()).unwrap(), "local only").unwrap();
+        assert_eq!(git_context(workdir.path()).unwrap().ahead, 1);
+
+        let log = remote_log(bare.path());
+        assert!(log.iter().any(|s| s == "sync: add new.txt"), "Expected commit message in remote log, got: {:?}", log);
+    }
 
-        // 4. Verify the commit arrived in the bare remote.
-        let log_output = Command::new("git")
-            .args(["log", "--oneline"])
-            .current_dir(bare.path())
-            .output()
-            .unwrap();
-        let log_text = String::from_utf8_lossy(&log_output.stdout);
-        assert!(
-            log_text.contains("sync: add new.txt"),
-            "Expected commit message in remote log, got: {}",
-            log_text
+    #[test]
+    fn test_concurrent_roadmap_edits_are_merged_on_push() {
+        let bare = init_bare();
+        let laptop = clone_of(bare.path());
+        let roadmap = "projects/api/roadmap.md";
+        std::fs::create_dir_all(laptop.path().join("projects/api")).unwrap();
+        std::fs::write(laptop.path().join(roadmap), "## Phase 1\n- [ ] Setup\n- [ ] Parser\n").unwrap();
+        assert_eq!(sync_push(laptop.path(), "seed").unwrap(), SyncResult::Synced);
+
+        let desktop = clone_of(bare.path());
+        std::fs::write(desktop.path().join(roadmap), "## Phase 1\n- [x] Setup\n- [ ] Parser\n").unwrap();
+        assert_eq!(sync_push(desktop.path(), "desktop: setup done").unwrap(), SyncResult::Synced);
+
+        // The laptop has not pulled: its push is rejected, then rebased with a structured merge.
+        std::fs::write(laptop.path().join(roadmap), "## Phase 1\n- [ ] Setup\n- [>] Parser\n- [ ] CLI\n").unwrap();
+        assert_eq!(sync_push(laptop.path(), "laptop: parser").unwrap(), SyncResult::Synced);
+        assert!(crate::sync_queue::load_pending(laptop.path()).is_none());
+
+        assert_eq!(sync_pull(desktop.path()).unwrap(), SyncResult::Synced);
+        assert_eq!(
+            std::fs::read_to_string(desktop.path().join(roadmap)).unwrap(),
+            "## Phase 1\n- [x] Setup\n- [>] Parser\n- [ ] CLI\n"
+        );
+    }
+
+    #[test]
+    fn test_failed_push_is_queued_and_retried() {
+        let bare = init_bare();
+        let work = clone_of(bare.path());
+        std::fs::write(work.path().join("a.txt"), "a").unwrap();
+        assert_eq!(sync_push(work.path(), "seed").unwrap(), SyncResult::Synced);
+
+        let repo = Repository::open(work.path()).unwrap();
+        repo.remote_set_url("origin", "/nonexistent/seslog.git").unwrap();
+        std::fs::write(work.path().join("b.txt"), "b").unwrap();
+        assert!(matches!(sync_push(work.path(), "offline").unwrap(), SyncResult::Offline(_)));
+        let pending = crate::sync_queue::load_pending(work.path()).unwrap();
+        assert_eq!(pending.attempts, 1);
+        assert_eq!(retry_pending_push(work.path()).unwrap(), None, "backoff not elapsed");
+
+        // Back online: the next sync pushes the stranded commit even with nothing new to commit.
+        repo.remote_set_url("origin", &bare.path().to_string_lossy()).unwrap();
+        assert_eq!(sync_push(work.path(), "nothing new").unwrap(), SyncResult::Synced);
+        assert!(crate::sync_queue::load_pending(work.path()).is_none());
+        assert!(remote_log(bare.path()).iter().any(|s| s == "offline"));
+    }
+
+    #[test]
+    fn test_credential_sources_order() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("id_rsa"), "key").unwrap();
+        std::fs::write(tmp.path().join("id_ed25519"), "key").unwrap();
+
+        assert_eq!(credential_sources(CredentialType::SSH_KEY, Some(tmp.path()), None), vec![
+            CredentialSource::SshAgent,
+            CredentialSource::SshKey(tmp.path().join("id_ed25519")),
+            CredentialSource::SshKey(tmp.path().join("id_rsa")),
+        ]);
+        assert_eq!(
+            credential_sources(CredentialType::USER_PASS_PLAINTEXT, None, Some("tok".into())),
+            vec![CredentialSource::Token("tok".into()), CredentialSource::Helper]
         );
+        assert_eq!(credential_sources(CredentialType::USER_PASS_PLAINTEXT, None, None), vec![CredentialSource::Helper]);
     }
 }
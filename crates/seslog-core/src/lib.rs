@@ -14,9 +14,37 @@ pub mod queue;
 pub mod sanitize;
 /// Markdown roadmap parser with dependency tracking.
 pub mod roadmap;
+/// Validated, line-preserving edits to `roadmap.md` (add, move, rename, status, phases, dependencies).
+pub mod roadmap_edit;
 /// CLAUDE.md marker-based block injection.
 pub mod claude_md;
 /// Git operations: sync, diff, commit, remote detection.
 pub mod git_ops;
-/// Claude Code JSONL transcript parser and cost estimator.
+/// Claude Code JSONL transcript parser.
 pub mod transcript;
+/// Per-model, effective-dated token prices (`pricing.toml`) and session repricing.
+pub mod pricing;
+/// In-flight session markers and start/end timing reconciliation.
+pub mod active_session;
+/// Collision-proof project identity (git remote / root commit / path) and slug migration.
+pub mod project_identity;
+/// User-defined `projects.toml` rules mapping directories to projects (monorepos).
+pub mod project_map;
+/// Read-only lookup of projects and sessions straight from the data directory.
+pub mod catalog;
+/// Per-project decision log (`decisions.jsonl`) and transcript decision extraction.
+pub mod decisions;
+/// Session-start context composer: ranked, budgeted sections from sessions, roadmap, git and checkpoints.
+pub mod context;
+/// Configurable trigger phrases and the matching Claude Code slash commands.
+pub mod triggers;
+/// User-editable minijinja templates for the CLAUDE.md block and session-start context.
+pub mod templates;
+/// Privacy-mode enforcement: scrubbing session and checkpoint records, identifier hashing.
+pub mod privacy;
+/// Optional age encryption of synced session/checkpoint records, keyed per machine.
+pub mod encryption;
+/// Structured resolution of sync conflicts (roadmap, meta.toml, append-only logs).
+pub mod merge;
+/// Retry/backoff state for pushes that failed while offline.
+pub mod sync_queue;
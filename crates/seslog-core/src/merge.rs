@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use anyhow::Result;
+use crate::roadmap;
+
+/// How a conflicted file in the data directory is resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Three-way merge of roadmap lines, items keyed by `{id:}` or text.
+    Roadmap,
+    /// Three-way merge of the TOML tables; `paths` is always the union of both sides.
+    ProjectMeta,
+    /// Append-only log: union of lines in order, duplicates dropped.
+    AppendLog,
+    /// Take the side from the most recent commit.
+    Newest,
+}
+
+pub fn strategy_for(path: &Path) -> MergeStrategy {
+    match path.file_name().and_then(|n| n.to_str()) {
+        Some("roadmap.md") => MergeStrategy::Roadmap,
+        Some("meta.toml") => MergeStrategy::ProjectMeta,
+        Some(name) if name.ends_with(".jsonl") => MergeStrategy::AppendLog,
+        _ => MergeStrategy::Newest,
+    }
+}
+
+/// Resolve one conflicted file. `None` on a side means the file is absent there
+/// (added or deleted). Returns `None` when the merged result is a deletion.
+pub fn merge_file(
+    path: &Path,
+    base: Option<&str>,
+    local: Option<&str>,
+    remote: Option<&str>,
+    local_is_newer: bool,
+) -> Result<Option<String>> {
+    let (local, remote) = match (local, remote) {
+        (Some(l), Some(r)) => (l, r),
+        // Deleted on one side: keep the other side's data rather than lose it.
+        (Some(only), None) | (None, Some(only)) => return Ok(Some(only.to_string())),
+        (None, None) => return Ok(None),
+    };
+    let merged = match strategy_for(path) {
+        MergeStrategy::Roadmap => merge_roadmap(base.unwrap_or(""), local, remote),
+        MergeStrategy::ProjectMeta => merge_meta(base, local, remote)?,
+        MergeStrategy::AppendLog => merge_append_log(local, remote),
+        MergeStrategy::Newest => if local_is_newer { local } else { remote }.to_string(),
+    };
+    Ok(Some(merged))
+}
+
+/// Standard three-way pick: a side that changed wins; if both changed, `local` wins.
+fn pick<T: PartialEq + Clone>(base: Option<&T>, local: Option<&T>, remote: Option<&T>) -> Option<T> {
+    if local == remote || base == remote {
+        local.cloned()
+    } else if base == local {
+        remote.cloned()
+    } else {
+        local.or(remote).cloned()
+    }
+}
+
+struct Line {
+    key: String,
+    text: String,
+}
+
+/// Split a roadmap into lines keyed for merging: items by `{id:}` (or their text
+/// without the checkbox, so a status change is an edit, not a new item), other
+/// lines by content, blank lines by the line they follow.
+fn keyed_lines(content: &str) -> Vec<Line> {
+    let items: HashMap<usize, roadmap::RoadmapItem> = roadmap::parse_roadmap(content)
+        .into_iter()
+        .map(|item| (item.line_number, item))
+        .collect();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut prev = String::from("^");
+    let mut lines = Vec::new();
+    for (idx, text) in content.lines().enumerate() {
+        let raw = match items.get(&(idx + 1)) {
+            Some(item) => match &item.id {
+                Some(id) => format!("id:{}", id),
+                None => format!("item:{}", item.text.to_lowercase()),
+            },
+            None if text.trim().is_empty() => format!("blank:{}", prev),
+            None => format!("line:{}", text.trim()),
+        };
+        let n = seen.entry(raw.clone()).or_insert(0);
+        *n += 1;
+        let key = if *n == 1 { raw } else { format!("{}#{}", raw, n) };
+        if !text.trim().is_empty() {
+            prev = key.clone();
+        }
+        lines.push(Line { key, text: text.to_string() });
+    }
+    lines
+}
+
+/// Three-way merge of `roadmap.md`. The remote order is kept; items only the local
+/// side added are inserted after the line they follow locally. An item edited on one
+/// side and deleted on the other is kept.
+pub fn merge_roadmap(base: &str, local: &str, remote: &str) -> String {
+    let to_map = |lines: &[Line]| -> HashMap<String, String> {
+        lines.iter().map(|l| (l.key.clone(), l.text.clone())).collect()
+    };
+    let (base_lines, local_lines, remote_lines) = (keyed_lines(base), keyed_lines(local), keyed_lines(remote));
+    let (base_map, local_map, remote_map) = (to_map(&base_lines), to_map(&local_lines), to_map(&remote_lines));
+
+    let mut merged: Vec<Line> = Vec::new();
+    for line in &remote_lines {
+        let b = base_map.get(&line.key);
+        match local_map.get(&line.key) {
+            // Deleted locally; keep only if remote edited it.
+            None if b.is_some() && b != Some(&line.text) => merged.push(Line { key: line.key.clone(), text: line.text.clone() }),
+            None if b.is_some() => {}
+            None => merged.push(Line { key: line.key.clone(), text: line.text.clone() }),
+            Some(l) => {
+                if let Some(text) = pick(b, Some(l), Some(&line.text)) {
+                    merged.push(Line { key: line.key.clone(), text });
+                }
+            }
+        }
+    }
+
+    let mut anchor: Option<String> = None;
+    for line in &local_lines {
+        if remote_map.contains_key(&line.key) {
+            anchor = Some(line.key.clone());
+            continue;
+        }
+        // Deleted remotely; keep only if edited locally.
+        if base_map.get(&line.key).is_some_and(|b| *b == line.text) {
+            continue;
+        }
+        let at = anchor.as_ref()
+            .and_then(|a| merged.iter().position(|m| &m.key == a))
+            .map_or(0, |i| i + 1);
+        merged.insert(at, Line { key: line.key.clone(), text: line.text.clone() });
+        anchor = Some(line.key.clone());
+    }
+
+    let mut out = merged.into_iter().map(|l| l.text).collect::<Vec<_>>().join("\n");
+    if local.ends_with('\n') || remote.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// Three-way merge of two TOML tables, recursing into sub-tables.
+fn merge_tables(base: Option<&toml::Table>, local: &toml::Table, remote: &toml::Table) -> toml::Table {
+    let keys: Vec<&String> = local.keys().chain(remote.keys().filter(|k| !local.contains_key(*k))).collect();
+    let mut out = toml::Table::new();
+    for key in keys {
+        let b = base.and_then(|t| t.get(key));
+        let merged = match (b, local.get(key), remote.get(key)) {
+            (b, Some(toml::Value::Table(l)), Some(toml::Value::Table(r))) => {
+                Some(toml::Value::Table(merge_tables(b.and_then(|v| v.as_table()), l, r)))
+            }
+            (b, l, r) => pick(b, l, r),
+        };
+        if let Some(value) = merged {
+            out.insert(key.clone(), value);
+        }
+    }
+    out
+}
+
+/// Merge `meta.toml`: fields follow the three-way rules, and the per-machine `paths`
+/// are unioned so no machine's checkout location is lost (local wins on the same machine).
+pub fn merge_meta(base: Option<&str>, local: &str, remote: &str) -> Result<String> {
+    let base: Option<toml::Table> = base.and_then(|b| toml::from_str(b).ok());
+    let local: toml::Table = toml::from_str(local)?;
+    let remote: toml::Table = toml::from_str(remote)?;
+    let mut merged = merge_tables(base.as_ref(), &local, &remote);
+
+    let mut paths = remote.get("paths").and_then(|p| p.as_table()).cloned().unwrap_or_default();
+    if let Some(local_paths) = local.get("paths").and_then(|p| p.as_table()) {
+        paths.extend(local_paths.clone());
+    }
+    merged.insert("paths".into(), toml::Value::Table(paths));
+    Ok(toml::to_string_pretty(&merged)?)
+}
+
+/// Union of two append-only logs: remote lines first, then local lines remote lacks.
+pub fn merge_append_log(local: &str, remote: &str) -> String {
+    let mut seen = HashSet::new();
+    let mut out = String::new();
+    for line in remote.lines().chain(local.lines()) {
+        if line.trim().is_empty() || !seen.insert(line) {
+            continue;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roadmap_three_way_merge() {
+        let base = "## Phase 1\n- [ ] Setup {id: setup}\n- [ ] Write parser\n- [ ] Old idea\n";
+        // Local finishes setup and adds an item; remote starts the parser and drops the old idea.
+        let local = "## Phase 1\n- [x] Setup {id: setup}\n- [ ] Write parser\n- [ ] Add CLI\n- [ ] Old idea\n";
+        let remote = "## Phase 1\n- [ ] Setup {id: setup}\n- [>] Write parser\n\n## Phase 2\n- [ ] Ship\n";
+
+        assert_eq!(
+            merge_roadmap(base, local, remote),
+            "## Phase 1\n- [x] Setup {id: setup}\n- [>] Write parser\n- [ ] Add CLI\n\n## Phase 2\n- [ ] Ship\n"
+        );
+    }
+
+    #[test]
+    fn test_roadmap_edit_beats_delete() {
+        let base = "- [ ] Train {id: train}\n- [ ] Eval\n";
+        let local = "- [x] Train model {id: train}\n";
+        let remote = "- [ ] Eval\n";
+        assert_eq!(merge_roadmap(base, local, remote), "- [x] Train model {id: train}\n");
+    }
+
+    #[test]
+    fn test_meta_unions_paths() {
+        let base = "schema_version = 1\n[project]\nid = \"proj_a\"\nname = \"api\"\nstatus = \"active\"\ndescription = \"\"\n[paths]\nmac = \"/Users/me/api\"\n";
+        let local = base.replace("[paths]\n", "[paths]\nlinux = \"/home/me/api\"\n");
+        let remote = base.replace("description = \"\"", "description = \"REST backend\"")
+            .replace("[paths]\n", "[paths]\ndesktop = \"D:/api\"\n");
+
+        let merged: toml::Table = toml::from_str(&merge_meta(Some(base), &local, &remote).unwrap()).unwrap();
+        let paths = merged["paths"].as_table().unwrap();
+        assert_eq!(paths.len(), 3);
+        assert_eq!(merged["project"]["description"].as_str(), Some("REST backend"));
+    }
+
+    #[test]
+    fn test_merge_file_strategies() {
+        let log = merge_file(Path::new("p/decisions.jsonl"), Some("a\n"), Some("a\nb\n"), Some("a\nc\n"), true).unwrap();
+        assert_eq!(log.as_deref(), Some("a\nc\nb\n"));
+
+        let cfg = Path::new("config.toml");
+        assert_eq!(merge_file(cfg, None, Some("x = 1"), Some("x = 2"), true).unwrap().as_deref(), Some("x = 1"));
+        assert_eq!(merge_file(cfg, None, Some("x = 1"), Some("x = 2"), false).unwrap().as_deref(), Some("x = 2"));
+        assert_eq!(merge_file(cfg, Some("x = 0"), None, Some("x = 2"), true).unwrap().as_deref(), Some("x = 2"));
+    }
+}
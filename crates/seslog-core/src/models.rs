@@ -46,7 +46,12 @@ pub struct Session {
     pub machine: String,
     pub started_at: DateTime<Utc>,
     pub ended_at: Option<DateTime<Utc>>,
+    /// Wall-clock minutes between `started_at` and `ended_at`.
     pub duration_minutes: Option<u32>,
+    /// Minutes of actual activity, with idle gaps between transcript entries removed.
+    /// `None` when the transcript carried no timestamps.
+    #[serde(default)]
+    pub active_minutes: Option<u32>,
     pub end_reason: Option<String>,
     pub summary: String,
     /// How the summary text was produced. See [`SummarySource`] for valid values:
@@ -65,10 +70,25 @@ pub struct Session {
     pub tags: Vec<String>,
     #[serde(default)]
     pub tools_used: Vec<String>,
+    /// Calls and errors per tool in the main conversation, in order of first use.
+    /// Subagents' calls are counted in `subagents`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_stats: Vec<ToolStats>,
+    /// Files read or modified through Claude's file tools, subagents included, relative
+    /// to the project directory when inside it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub touched_files: Vec<TouchedFile>,
     #[serde(default)]
     pub files_changed: u32,
     #[serde(default)]
     pub git_commits: Vec<String>,
+    /// Commit range and diff produced during the session. `None` when the start
+    /// state was not recorded (no SessionStart marker, or not a git repository).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_range: Option<GitRange>,
+    /// Branch, worktree and upstream state at session end.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_context: Option<GitContext>,
     #[serde(default)]
     pub checkpoints_merged: Vec<String>,
     /// Whether this session record was reconstructed from partial data
@@ -79,14 +99,181 @@ pub struct Session {
     /// from the summary and transcript highlights by the sanitizer.
     #[serde(default)]
     pub redaction_count: u32,
+    /// Per-pattern breakdown of `redaction_count`, kept for auditing.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redactions: Vec<crate::sanitize::PatternHit>,
+    /// Total tokens across all categories of `token_usage`.
     #[serde(default)]
     pub token_count: Option<u64>,
+    /// Tokens per billing category. `None` for sessions recorded before it was tracked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_usage: Option<TokenUsage>,
+    /// Usage of every assistant turn, subagents' included, in order. Long sessions keep
+    /// only the most recent turns individually; older ones are folded into per-model
+    /// entries at the front (see [`crate::transcript::cap_usage_turns`]). Empty for
+    /// sessions recorded before it was tracked.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub usage_turns: Vec<TurnUsage>,
+    /// Work delegated to subagents (the Task tool), in the order they started.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subagents: Vec<Subagent>,
     #[serde(default)]
     pub estimated_cost_usd: Option<f64>,
     #[serde(default)]
     pub model: Option<String>,
 }
 
+/// Token totals per billing category, as reported in the transcript's `usage` blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub input: u64,
+    #[serde(default)]
+    pub output: u64,
+    /// Input tokens written to the prompt cache.
+    #[serde(default)]
+    pub cache_creation: u64,
+    /// Input tokens served from the prompt cache.
+    #[serde(default)]
+    pub cache_read: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.input + self.output + self.cache_creation + self.cache_read
+    }
+
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input += other.input;
+        self.output += other.output;
+        self.cache_creation += other.cache_creation;
+        self.cache_read += other.cache_read;
+    }
+
+    pub fn sub(&mut self, other: &TokenUsage) {
+        self.input = self.input.saturating_sub(other.input);
+        self.output = self.output.saturating_sub(other.output);
+        self.cache_creation = self.cache_creation.saturating_sub(other.cache_creation);
+        self.cache_read = self.cache_read.saturating_sub(other.cache_read);
+    }
+}
+
+/// Usage of one assistant turn (one API response), or of several folded together.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnUsage {
+    pub model: String,
+    #[serde(flatten)]
+    pub usage: TokenUsage,
+    /// How many turns this entry stands for: 1 unless older turns were folded into it.
+    #[serde(default = "one_turn", skip_serializing_if = "is_one_turn")]
+    pub turns: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    /// [`Subagent::id`] of the subagent that made the turn; `None` for the main conversation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subagent: Option<String>,
+}
+
+fn one_turn() -> u32 {
+    1
+}
+
+fn is_one_turn(turns: &u32) -> bool {
+    *turns == 1
+}
+
+/// A subagent run, taken from the transcript's sidechain entries. Its turns are part of
+/// the session's token totals and cost; `usage` and `cost_usd` are its share of them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Subagent {
+    /// The entries' `agentId`, or the `uuid` of the sidechain's first entry.
+    pub id: String,
+    /// `subagent_type` of the Task call that started it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_type: Option<String>,
+    /// The Task call's description, or the start of the prompt when no call matched.
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<DateTime<Utc>>,
+    /// First sentences of its last few assistant messages, oldest first.
+    #[serde(default)]
+    pub summaries: Vec<String>,
+    #[serde(default)]
+    pub tool_stats: Vec<ToolStats>,
+    #[serde(default)]
+    pub turns: u32,
+    #[serde(default)]
+    pub usage: TokenUsage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+/// Usage summed over all turns on one model.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelUsage {
+    pub model: String,
+    pub turns: u32,
+    pub usage: TokenUsage,
+    pub cost_usd: Option<f64>,
+}
+
+/// Invocations of one tool. `errors` counts the `tool_result` blocks flagged `is_error`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolStats {
+    pub name: String,
+    pub calls: u32,
+    #[serde(default)]
+    pub errors: u32,
+}
+
+/// A file named in the input of a Read, Edit or Write call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TouchedFile {
+    pub path: String,
+    #[serde(default)]
+    pub reads: u32,
+    /// Edit, MultiEdit and NotebookEdit calls.
+    #[serde(default)]
+    pub edits: u32,
+    #[serde(default)]
+    pub writes: u32,
+}
+
+impl TouchedFile {
+    /// Whether Claude changed the file rather than only reading it.
+    pub fn modified(&self) -> bool {
+        self.edits + self.writes > 0
+    }
+}
+
+impl Session {
+    /// Per-model totals of `usage_turns`, largest token total first.
+    pub fn model_breakdown(&self) -> Vec<ModelUsage> {
+        let mut models: Vec<ModelUsage> = Vec::new();
+        for turn in &self.usage_turns {
+            let entry = match models.iter_mut().position(|m| m.model == turn.model) {
+                Some(i) => &mut models[i],
+                None => {
+                    models.push(ModelUsage { model: turn.model.clone(), turns: 0, usage: TokenUsage::default(), cost_usd: None });
+                    models.last_mut().expect("just pushed")
+                }
+            };
+            entry.turns += turn.turns;
+            entry.usage.add(&turn.usage);
+            if let Some(cost) = turn.cost_usd {
+                entry.cost_usd = Some(entry.cost_usd.unwrap_or(0.0) + cost);
+            }
+        }
+        models.sort_by_key(|m| std::cmp::Reverse(m.usage.total()));
+        models
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoadmapChange {
     pub action: String,
@@ -95,6 +282,92 @@ pub struct RoadmapChange {
     pub phase: Option<u32>,
 }
 
+// --- Git Range ---
+
+/// Git `HEAD` as seen by a hook: the commit and the checked-out branch.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct HeadState {
+    /// Full SHA of `HEAD`; `None` in a repository without commits.
+    pub sha: Option<String>,
+    /// `None` when `HEAD` is detached.
+    pub branch: Option<String>,
+}
+
+/// Branch, worktree and upstream tracking state of the repository a hook ran in.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GitContext {
+    /// `None` when `HEAD` is detached.
+    pub branch: Option<String>,
+    /// Root of the working tree; differs between `git worktree` checkouts of one repository.
+    pub worktree: Option<String>,
+    /// Whether `worktree` is a linked worktree rather than the main checkout.
+    #[serde(default)]
+    pub linked_worktree: bool,
+    /// Remote-tracking branch, e.g. `origin/main`. `None` when the branch tracks nothing.
+    #[serde(default)]
+    pub upstream: Option<String>,
+    /// Commits on the branch that the upstream does not have.
+    #[serde(default)]
+    pub ahead: u32,
+    /// Commits on the upstream that the branch does not have.
+    #[serde(default)]
+    pub behind: u32,
+}
+
+/// What a session changed: the commits between the start and end `HEAD`,
+/// plus the diff from the start commit to the working tree at session end.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GitRange {
+    /// `HEAD` when the session started; `None` if the repository had no commits yet.
+    pub base: Option<String>,
+    /// `HEAD` when the session ended.
+    pub head: Option<String>,
+    #[serde(default)]
+    pub branch: Option<String>,
+    pub insertions: u32,
+    pub deletions: u32,
+    #[serde(default)]
+    pub files: Vec<FileChange>,
+}
+
+impl GitRange {
+    /// Short `+N -M across K file(s)` line, or `None` if nothing changed.
+    pub fn diff_stat(&self) -> Option<String> {
+        if self.files.is_empty() {
+            return None;
+        }
+        Some(format!("+{} -{} across {} file(s)", self.insertions, self.deletions, self.files.len()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    pub status: FileStatus,
+    pub insertions: u32,
+    pub deletions: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+impl FileStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FileStatus::Added => "added",
+            FileStatus::Modified => "modified",
+            FileStatus::Deleted => "deleted",
+            FileStatus::Renamed => "renamed",
+        }
+    }
+}
+
 // --- Checkpoint ---
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -110,9 +383,39 @@ pub struct Checkpoint {
     pub files_changed: Vec<String>,
     #[serde(default)]
     pub recent_commits: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_context: Option<GitContext>,
     pub source: String,
 }
 
+// --- Active Session Marker ---
+
+/// In-flight session marker written by the SessionStart hook.
+///
+/// Lives at `.active/{session_id}.json` until the enrichment pass has
+/// reconciled it into the final [`Session`] record. Machine-local, never synced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveSession {
+    pub schema_version: u32,
+    pub session_id: String,
+    pub project_slug: String,
+    pub cwd: String,
+    pub machine: String,
+    pub started_at: DateTime<Utc>,
+    #[serde(default)]
+    pub transcript_path: String,
+    /// Git `HEAD` of `cwd` at session start, so SessionEnd can diff what the session
+    /// produced. `None` outside a git repository and for markers from older versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_start: Option<HeadState>,
+    /// Manual summary recorded (through the MCP server) before SessionEnd wrote the
+    /// session record; SessionEnd applies it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_next_steps: Option<String>,
+}
+
 // --- Project ---
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -132,6 +435,10 @@ pub struct ProjectInfo {
     pub archived_at: Option<DateTime<Utc>>,
     #[serde(default)]
     pub description: String,
+    /// Stable project key (git remote, root commit, or canonical path). See
+    /// [`crate::project_identity`]. `None` for projects registered before identities existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
 }
 
 // --- Machine ---
@@ -217,6 +524,7 @@ mod tests {
             started_at: chrono::Utc::now(),
             ended_at: None,
             duration_minutes: None,
+            active_minutes: None,
             end_reason: None,
             summary: "test session".into(),
             summary_source: Some(SummarySource::TranscriptGit),
@@ -226,12 +534,20 @@ mod tests {
             next_steps: String::new(),
             tags: vec![],
             tools_used: vec![],
+            tool_stats: vec![],
+            touched_files: vec![],
             files_changed: 0,
             git_commits: vec![],
+            git_range: None,
+            git_context: None,
             checkpoints_merged: vec![],
             recovered: false,
             redaction_count: 0,
+            redactions: vec![],
             token_count: None,
+            token_usage: None,
+            usage_turns: vec![],
+            subagents: vec![],
             estimated_cost_usd: None,
             model: None,
         };
@@ -274,6 +590,28 @@ mod tests {
         assert_eq!(session.files_changed, 0);
     }
 
+    #[test]
+    fn test_model_breakdown_sums_turns_per_model() {
+        let json = r#"{
+            "schema_version": 1, "id": "ses_z", "project_id": "proj_z", "machine": "m",
+            "started_at": "2026-01-01T00:00:00Z", "summary": "s",
+            "usage_turns": [
+                {"model": "claude-haiku-4-5", "input": 10, "output": 5, "turns": 3, "cost_usd": 0.5},
+                {"model": "claude-opus-4-6", "input": 100, "output": 50, "cache_read": 1000, "cost_usd": 2.0},
+                {"model": "claude-haiku-4-5", "input": 20, "output": 5}
+            ]
+        }"#;
+        let session: Session = serde_json::from_str(json).unwrap();
+        let breakdown = session.model_breakdown();
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown[0].model, "claude-opus-4-6");
+        assert_eq!(breakdown[0].usage.total(), 1150);
+        assert_eq!(breakdown[0].turns, 1);
+        assert_eq!(breakdown[1].turns, 4);
+        assert_eq!(breakdown[1].usage, TokenUsage { input: 30, output: 10, cache_creation: 0, cache_read: 0 });
+        assert_eq!(breakdown[1].cost_usd, Some(0.5));
+    }
+
     #[test]
     fn test_checkpoint_serialize_roundtrip() {
         let cp = Checkpoint {
@@ -286,6 +624,7 @@ mod tests {
             git_diff_stat: Some("+10 -5 across 3 files".into()),
             files_changed: vec!["src/main.rs".into()],
             recent_commits: vec![],
+            git_context: None,
             source: "postToolUse_debounced".into(),
         };
         let json = serde_json::to_string(&cp).unwrap();
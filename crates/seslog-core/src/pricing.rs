@@ -0,0 +1,334 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::models::{Session, TokenUsage};
+
+pub const PRICING_FILE: &str = "pricing.toml";
+/// Format version of `pricing.toml` this build understands.
+pub const PRICING_VERSION: u32 = 1;
+
+/// USD per million tokens for models whose name contains `model`, from `effective` on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    /// Substring of the model name (`opus`, `claude-haiku-4-5`); `*` matches any model.
+    pub model: String,
+    /// First day (UTC) these prices apply to, `YYYY-MM-DD`.
+    pub effective: NaiveDate,
+    pub input: f64,
+    pub output: f64,
+    /// Writing to the prompt cache (`cache_creation_input_tokens`).
+    pub cache_write: f64,
+    /// Reading from the prompt cache (`cache_read_input_tokens`).
+    pub cache_read: f64,
+}
+
+/// The contents of `~/.seslog/pricing.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PricingTable {
+    pub version: u32,
+    #[serde(default, rename = "price")]
+    pub prices: Vec<ModelPrice>,
+}
+
+impl Default for PricingTable {
+    fn default() -> Self {
+        // Cache writes cost 1.25x input, cache reads 0.1x input.
+        let price = |model: &str, effective: (i32, u32, u32), input: f64, output: f64| ModelPrice {
+            model: model.into(),
+            effective: NaiveDate::from_ymd_opt(effective.0, effective.1, effective.2).expect("valid date"),
+            input,
+            output,
+            cache_write: input * 1.25,
+            cache_read: input * 0.1,
+        };
+        Self {
+            version: PRICING_VERSION,
+            prices: vec![
+                // Unknown models are priced as Sonnet, the most common.
+                price("*", (2024, 1, 1), 3.0, 15.0),
+                // Family fallbacks, at the prices of the models that predate the entries below.
+                price("sonnet", (2024, 1, 1), 3.0, 15.0),
+                price("opus", (2024, 1, 1), 15.0, 75.0),
+                price("haiku", (2024, 1, 1), 0.25, 1.25),
+                price("claude-haiku-4-5", (2025, 10, 15), 1.0, 5.0),
+                price("claude-opus-4-5", (2025, 11, 24), 5.0, 25.0),
+                price("claude-opus-4-6", (2026, 2, 5), 5.0, 25.0),
+            ],
+        }
+    }
+}
+
+impl PricingTable {
+    /// Prices for `model` on `date`. The most specific (longest) matching pattern wins;
+    /// among its entries, the latest one effective on or before `date`. Dates before a
+    /// pattern's first entry use that first entry.
+    pub fn price_for(&self, model: Option<&str>, date: NaiveDate) -> Option<&ModelPrice> {
+        let model = model.unwrap_or("");
+        let matches = |p: &&ModelPrice| p.model == "*" || (!model.is_empty() && model.contains(p.model.as_str()));
+        let specificity = |p: &ModelPrice| if p.model == "*" { 0 } else { p.model.len() };
+        let best = self.prices.iter().filter(matches).map(specificity).max()?;
+        let candidates: Vec<&ModelPrice> = self.prices.iter()
+            .filter(matches)
+            .filter(|p| specificity(p) == best)
+            .collect();
+        candidates.iter()
+            .filter(|p| p.effective <= date)
+            .max_by_key(|p| p.effective)
+            .or_else(|| candidates.iter().min_by_key(|p| p.effective))
+            .copied()
+    }
+
+    /// Cost in USD of `usage` on `model` for a session started at `at`.
+    pub fn cost(&self, usage: &TokenUsage, model: Option<&str>, at: DateTime<Utc>) -> Option<f64> {
+        let p = self.price_for(model, at.date_naive())?;
+        let total = usage.input as f64 * p.input
+            + usage.output as f64 * p.output
+            + usage.cache_creation as f64 * p.cache_write
+            + usage.cache_read as f64 * p.cache_read;
+        Some(total / 1_000_000.0)
+    }
+}
+
+/// Load `pricing.toml` from the data directory, falling back to the built-in table.
+pub fn load_pricing(data_dir: &Path) -> Result<PricingTable> {
+    let path = data_dir.join(PRICING_FILE);
+    let table: PricingTable = match std::fs::read_to_string(&path) {
+        Ok(content) => toml::from_str(&content)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(PricingTable::default()),
+        Err(e) => return Err(e.into()),
+    };
+    if table.version > PRICING_VERSION {
+        bail!("{} has version {}, this build supports up to {}", path.display(), table.version, PRICING_VERSION);
+    }
+    Ok(table)
+}
+
+/// Write the built-in table to `pricing.toml` unless the file already exists.
+pub fn ensure_pricing_file(data_dir: &Path) -> Result<()> {
+    let path = data_dir.join(PRICING_FILE);
+    if path.exists() {
+        return Ok(());
+    }
+    let content = toml::to_string_pretty(&PricingTable::default())?;
+    crate::storage::atomic_write(&path, content.as_bytes())
+}
+
+/// Recompute `session`'s cost: turn by turn when per-turn usage was recorded, else from
+/// the session totals and model. Returns whether any cost changed. Sessions recorded
+/// before per-category usage was kept have nothing to reprice.
+pub fn reprice_session(session: &mut Session, table: &PricingTable) -> bool {
+    let before = (session.estimated_cost_usd, session.usage_turns.clone());
+    if !session.usage_turns.is_empty() {
+        let mut total = None;
+        for turn in &mut session.usage_turns {
+            turn.cost_usd = table.cost(&turn.usage, Some(&turn.model), session.started_at);
+            if let Some(cost) = turn.cost_usd {
+                total = Some(total.unwrap_or(0.0) + cost);
+            }
+        }
+        session.estimated_cost_usd = total;
+        for subagent in &mut session.subagents {
+            subagent.cost_usd = session.usage_turns.iter()
+                .filter(|t| t.subagent.as_deref() == Some(subagent.id.as_str()))
+                .filter_map(|t| t.cost_usd)
+                .fold(None, |sum, cost| Some(sum.unwrap_or(0.0) + cost));
+        }
+    } else if let Some(usage) = session.token_usage {
+        session.estimated_cost_usd = table.cost(&usage, session.model.as_deref(), session.started_at);
+    } else {
+        return false;
+    }
+    let close = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (Some(a), Some(b)) => (a - b).abs() <= 1e-9,
+        (a, b) => a.is_none() && b.is_none(),
+    };
+    !close(before.0, session.estimated_cost_usd)
+        || before.1.iter().zip(&session.usage_turns).any(|(a, b)| !close(a.cost_usd, b.cost_usd))
+}
+
+/// Outcome of [`reprice_all`].
+#[derive(Debug, Default)]
+pub struct RepriceReport {
+    /// Sessions with token usage that were checked.
+    pub checked: u32,
+    /// `(session id, old cost, new cost)` for every session whose cost changed.
+    pub changed: Vec<(String, Option<f64>, Option<f64>)>,
+}
+
+/// Recompute the cost of every session under `data_dir` with `table`, rewriting the
+/// sessions whose cost changed unless `dry_run`.
+pub fn reprice_all(data_dir: &Path, table: &PricingTable, dry_run: bool) -> Result<RepriceReport> {
+    let mut report = RepriceReport::default();
+    for project in crate::catalog::list_projects(data_dir) {
+        let Ok(entries) = std::fs::read_dir(project.dir.join("sessions")) else {
+            continue;
+        };
+        let mut paths: Vec<_> = entries.flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            // Unreadable records (e.g. encrypted for another machine) are left alone.
+            let Some(mut session) = crate::encryption::read_record(&path).ok()
+                .and_then(|content| serde_json::from_str::<Session>(&content).ok())
+            else {
+                continue;
+            };
+            if session.token_usage.is_none() && session.usage_turns.is_empty() {
+                continue;
+            }
+            report.checked += 1;
+            let old = session.estimated_cost_usd;
+            if reprice_session(&mut session, table) {
+                report.changed.push((session.id.clone(), old, session.estimated_cost_usd));
+                if !dry_run {
+                    crate::storage::write_json(&path, &session)?;
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Subagent, TurnUsage};
+    use tempfile::TempDir;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn usage(input: u64, output: u64, cache_creation: u64, cache_read: u64) -> TokenUsage {
+        TokenUsage { input, output, cache_creation, cache_read }
+    }
+
+    #[test]
+    fn test_default_prices() {
+        let table = PricingTable::default();
+        let at = "2026-01-01T00:00:00Z".parse().unwrap();
+        let million = usage(1_000_000, 1_000_000, 0, 0);
+        assert!((table.cost(&million, Some("claude-opus-4-1"), at).unwrap() - 90.0).abs() < 0.01);
+        assert!((table.cost(&million, None, at).unwrap() - 18.0).abs() < 0.01);
+        assert!((table.cost(&million, Some("claude-3-haiku-20240307"), at).unwrap() - 1.5).abs() < 0.01);
+        // Cache reads are a tenth of input, cache writes a quarter more.
+        let cached = usage(0, 0, 1_000_000, 1_000_000);
+        assert!((table.cost(&cached, Some("claude-sonnet-4-5"), at).unwrap() - 4.05).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_current_models_use_their_own_entry() {
+        let table = PricingTable::default();
+        let at = date("2026-06-01");
+        let opus = table.price_for(Some("claude-opus-4-5-20251101"), at).unwrap();
+        assert_eq!((opus.model.as_str(), opus.input, opus.output), ("claude-opus-4-5", 5.0, 25.0));
+        let haiku = table.price_for(Some("claude-haiku-4-5-20251001"), at).unwrap();
+        assert_eq!((haiku.model.as_str(), haiku.input, haiku.output), ("claude-haiku-4-5", 1.0, 5.0));
+        // Older models of the same family keep the family price.
+        assert_eq!(table.price_for(Some("claude-opus-4-1"), at).unwrap().model, "opus");
+        assert_eq!(table.price_for(Some("claude-3-haiku-20240307"), at).unwrap().model, "haiku");
+    }
+
+    #[test]
+    fn test_price_for_prefers_specific_and_effective_entries() {
+        let mut table = PricingTable::default();
+        let base = table.price_for(Some("claude-opus-4-1"), date("2026-01-01")).unwrap().clone();
+        table.prices.push(ModelPrice {
+            model: "opus-4-1".into(),
+            effective: date("2026-01-01"),
+            input: 7.0,
+            output: 35.0,
+            cache_write: 8.75,
+            cache_read: 0.7,
+        });
+        assert_eq!(table.price_for(Some("claude-opus-4-1"), date("2026-02-01")).unwrap().input, 7.0);
+        // Before its first entry, the specific pattern still applies.
+        assert_eq!(table.price_for(Some("claude-opus-4-1"), date("2025-01-01")).unwrap().input, 7.0);
+        assert_eq!(table.price_for(Some("claude-opus-4"), date("2026-02-01")).unwrap(), &base);
+
+        let latest = table.prices.last().unwrap().clone();
+        table.prices.push(ModelPrice { effective: date("2026-03-01"), input: 4.0, ..latest });
+        assert_eq!(table.price_for(Some("claude-opus-4-1"), date("2026-02-28")).unwrap().input, 7.0);
+        assert_eq!(table.price_for(Some("claude-opus-4-1"), date("2026-03-01")).unwrap().input, 4.0);
+
+        let empty = PricingTable { version: PRICING_VERSION, prices: Vec::new() };
+        assert!(empty.price_for(Some("claude-opus-4-5"), date("2026-01-01")).is_none());
+    }
+
+    #[test]
+    fn test_reprice_session_per_turn() {
+        let mut session: Session = serde_json::from_value(serde_json::json!({
+            "schema_version": 1, "id": "ses_a", "project_id": "p", "machine": "mac",
+            "started_at": "2026-01-01T00:00:00Z", "summary": "", "model": "claude-opus-4-6",
+        })).unwrap();
+        assert!(!reprice_session(&mut session, &PricingTable::default()));
+
+        let turn = |model: &str, input: u64| TurnUsage { model: model.into(), usage: usage(input, 0, 0, 0), turns: 1, cost_usd: None, subagent: None };
+        session.usage_turns = vec![turn("claude-opus-4-6", 1_000_000), turn("claude-haiku-4-5", 1_000_000)];
+        assert!(reprice_session(&mut session, &PricingTable::default()));
+        assert_eq!(session.usage_turns[0].cost_usd, Some(5.0));
+        assert_eq!(session.usage_turns[1].cost_usd, Some(1.0));
+        assert_eq!(session.estimated_cost_usd, Some(6.0));
+        assert!(!reprice_session(&mut session, &PricingTable::default()));
+
+        // A subagent's cost is its share of the priced turns.
+        session.usage_turns[1].subagent = Some("agent_1".into());
+        session.subagents = vec![Subagent { id: "agent_1".into(), ..Subagent::default() }];
+        reprice_session(&mut session, &PricingTable::default());
+        assert_eq!(session.subagents[0].cost_usd, Some(1.0));
+        assert_eq!(session.estimated_cost_usd, Some(6.0));
+    }
+
+    #[test]
+    fn test_load_pricing_file() {
+        let tmp = TempDir::new().unwrap();
+        assert_eq!(load_pricing(tmp.path()).unwrap(), PricingTable::default());
+
+        ensure_pricing_file(tmp.path()).unwrap();
+        assert_eq!(load_pricing(tmp.path()).unwrap(), PricingTable::default());
+
+        std::fs::write(
+            tmp.path().join(PRICING_FILE),
+            "version = 1\n\n[[price]]\nmodel = \"*\"\neffective = \"2024-01-01\"\ninput = 1.0\noutput = 2.0\ncache_write = 0.0\ncache_read = 0.0\n",
+        ).unwrap();
+        let table = load_pricing(tmp.path()).unwrap();
+        assert_eq!(table.prices.len(), 1);
+        // An existing file is never overwritten.
+        ensure_pricing_file(tmp.path()).unwrap();
+        assert_eq!(load_pricing(tmp.path()).unwrap(), table);
+
+        std::fs::write(tmp.path().join(PRICING_FILE), "version = 99\n").unwrap();
+        assert!(load_pricing(tmp.path()).is_err());
+    }
+
+    #[test]
+    fn test_reprice_all_rewrites_changed_sessions() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join("projects/api");
+        std::fs::create_dir_all(project.join("sessions")).unwrap();
+        std::fs::write(project.join("meta.toml"), "schema_version = 1\n\n[project]\nid = \"proj_api\"\nname = \"api\"\nstatus = \"active\"\ncreated_at = \"2026-01-01T00:00:00Z\"\n\n[paths]\n").unwrap();
+        let session = |id: &str, usage: Option<TokenUsage>| serde_json::json!({
+            "schema_version": 1, "id": id, "project_id": "proj_api", "machine": "mac",
+            "started_at": "2026-01-01T00:00:00Z", "summary": "",
+            "model": "claude-sonnet-4-5", "estimated_cost_usd": 1.0, "token_usage": usage,
+        });
+        let priced = project.join("sessions/a.json");
+        std::fs::write(&priced, session("ses_a", Some(usage(1_000_000, 0, 0, 0))).to_string()).unwrap();
+        std::fs::write(project.join("sessions/b.json"), session("ses_b", None).to_string()).unwrap();
+
+        let table = PricingTable::default();
+        let report = reprice_all(tmp.path(), &table, true).unwrap();
+        assert_eq!(report.checked, 1);
+        assert_eq!(report.changed, vec![("ses_a".to_string(), Some(1.0), Some(3.0))]);
+        let untouched: Session = serde_json::from_str(&std::fs::read_to_string(&priced).unwrap()).unwrap();
+        assert_eq!(untouched.estimated_cost_usd, Some(1.0));
+
+        reprice_all(tmp.path(), &table, false).unwrap();
+        let repriced: Session = serde_json::from_str(&std::fs::read_to_string(&priced).unwrap()).unwrap();
+        assert_eq!(repriced.estimated_cost_usd, Some(3.0));
+        assert!(reprice_all(tmp.path(), &table, false).unwrap().changed.is_empty());
+    }
+}
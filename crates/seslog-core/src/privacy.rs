@@ -0,0 +1,249 @@
+use std::path::Path;
+use anyhow::Result;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use crate::config::{self, PrivacyMode, LOCAL_CONFIG_FILE};
+use crate::models::{Checkpoint, GitContext, Session, SummarySource};
+
+/// Marks a value as already hashed, so a second scrub pass leaves it alone.
+pub const HASH_PREFIX: &str = "h:";
+
+/// Secret that keys identifier hashes. Without it, a hash can't be matched against a
+/// list of guessed hostnames, paths or branch names.
+///
+/// Hashes are stable for one key: machines that should map the same repository to the
+/// same hashed project must share the `[privacy] hash_key` from `local.toml`.
+#[derive(Clone, Default)]
+pub struct HashKey(Vec<u8>);
+
+impl HashKey {
+    pub fn new(secret: &[u8]) -> Self {
+        Self(secret.to_vec())
+    }
+
+    /// The key from `<data_dir>/local.toml`, generating and saving one on first use.
+    /// `local.toml` is git-ignored, so the key is never synced.
+    pub fn load_or_create(data_dir: &Path) -> Result<Self> {
+        let path = data_dir.join(LOCAL_CONFIG_FILE);
+        let mut local = config::load_local_config(&path)?;
+        if local.privacy.hash_key.is_empty() {
+            local.privacy.hash_key = format!(
+                "{}{}",
+                uuid::Uuid::new_v4().simple(),
+                uuid::Uuid::new_v4().simple()
+            );
+            crate::storage::ensure_gitignored(data_dir, &[LOCAL_CONFIG_FILE])?;
+            config::write_local_config(&path, &local)?;
+        }
+        Ok(Self(local.privacy.hash_key.into_bytes()))
+    }
+
+    /// The key `mode` needs: loaded (or created) when it hashes identifiers, otherwise
+    /// an unused empty key so other modes never touch `local.toml`.
+    pub fn for_mode(data_dir: &Path, mode: PrivacyMode) -> Result<Self> {
+        if mode.hashes_identifiers() { Self::load_or_create(data_dir) } else { Ok(Self::default()) }
+    }
+
+    /// 12 hex chars of the HMAC-SHA256 of `value`, without [`HASH_PREFIX`].
+    pub fn digest(&self, value: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.0).expect("HMAC accepts any key length");
+        mac.update(value.as_bytes());
+        mac.finalize().into_bytes()[..6].iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Stand-in for a name or path: [`HASH_PREFIX`] followed by its [`digest`](Self::digest).
+    pub fn hash(&self, value: &str) -> String {
+        format!("{}{}", HASH_PREFIX, self.digest(value))
+    }
+
+    /// [`hash`](Self::hash) unless `value` carries the prefix already. Sessions are
+    /// scrubbed at SessionEnd and again by the enrichment pass; the second pass must
+    /// not re-hash.
+    fn hash_once(&self, value: &str) -> String {
+        if value.starts_with(HASH_PREFIX) { value.to_string() } else { self.hash(value) }
+    }
+}
+
+/// Drop whatever `mode` does not allow from a session record before it is written.
+pub fn scrub_session(session: &mut Session, mode: PrivacyMode, key: &HashKey) {
+    if !mode.keeps_transcript_text() {
+        session.transcript_highlights.clear();
+        session.decisions.clear();
+        session.next_steps.clear();
+        for subagent in &mut session.subagents {
+            subagent.description.clear();
+            subagent.summaries.clear();
+        }
+        if session.summary_source == Some(SummarySource::TranscriptGit) {
+            session.summary.clear();
+            session.summary_source = Some(SummarySource::Minimal);
+        }
+    }
+    if !mode.keeps_summaries() {
+        session.summary.clear();
+        session.summary_source = Some(SummarySource::Minimal);
+        session.next_steps.clear();
+        session.git_commits.clear();
+    }
+    if mode.hashes_identifiers() {
+        // The hostname also names the synced session file, so hash it before that is built
+        session.machine = key.hash_once(&session.machine);
+        if let Some(range) = &mut session.git_range {
+            range.branch = range.branch.as_deref().map(|b| key.hash_once(b));
+            for file in &mut range.files {
+                file.path = key.hash_once(&file.path);
+            }
+        }
+        if let Some(context) = &mut session.git_context {
+            hash_git_context(context, key);
+        }
+        for file in &mut session.touched_files {
+            file.path = key.hash_once(&file.path);
+        }
+    }
+}
+
+/// Branch, worktree and upstream names can carry client or feature names.
+fn hash_git_context(context: &mut GitContext, key: &HashKey) {
+    context.branch = context.branch.as_deref().map(|b| key.hash_once(b));
+    context.worktree = context.worktree.as_deref().map(|w| key.hash_once(w));
+    context.upstream = context.upstream.as_deref().map(|u| key.hash_once(u));
+}
+
+/// Drop whatever `mode` does not allow from a checkpoint before it is written.
+pub fn scrub_checkpoint(checkpoint: &mut Checkpoint, mode: PrivacyMode, key: &HashKey) {
+    if mode.hashes_identifiers() {
+        checkpoint.machine = key.hash_once(&checkpoint.machine);
+        checkpoint.git_diff_stat = None;
+        checkpoint.recent_commits.clear();
+        checkpoint.files_changed = checkpoint.files_changed.iter().map(|f| key.hash_once(f)).collect();
+        if let Some(context) = &mut checkpoint.git_context {
+            hash_git_context(context, key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> HashKey {
+        HashKey::new(b"test-secret")
+    }
+
+    fn session() -> Session {
+        serde_json::from_value(serde_json::json!({
+            "schema_version": 1, "id": "ses_1", "project_id": "p", "machine": "mac",
+            "started_at": "2026-01-01T10:00:00Z",
+            "summary": "Refactored the OAuth client",
+            "summary_source": "transcript+git",
+            "transcript_highlights": ["fix the login bug for acme corp"],
+            "decisions": ["We decided to drop the legacy API"],
+            "next_steps": "Add retry tests",
+            "git_commits": ["abc123 Fix token refresh"],
+            "git_range": {
+                "base": "1111111", "head": "2222222", "branch": "acme-billing",
+                "insertions": 3, "deletions": 1,
+                "files": [{"path": "src/acme/billing.rs", "status": "modified", "insertions": 3, "deletions": 1}],
+            },
+            "touched_files": [{"path": "src/acme/billing.rs", "edits": 2}],
+            "subagents": [{"id": "agent_1", "description": "Audit acme billing", "summaries": ["Billing looks fine"], "turns": 2}],
+            "token_count": 1200,
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_metadata_only_drops_transcript_text() {
+        let mut s = session();
+        scrub_session(&mut s, PrivacyMode::MetadataOnly, &key());
+        assert!(s.transcript_highlights.is_empty() && s.decisions.is_empty());
+        assert!(s.subagents[0].description.is_empty() && s.subagents[0].summaries.is_empty());
+        assert_eq!(s.subagents[0].turns, 2);
+        assert!(s.summary.is_empty() && s.next_steps.is_empty());
+        assert_eq!(s.git_commits.len(), 1);
+        assert_eq!(s.token_count, Some(1200));
+
+        let mut manual = session();
+        manual.summary_source = Some(SummarySource::Manual);
+        scrub_session(&mut manual, PrivacyMode::MetadataOnly, &key());
+        assert_eq!(manual.summary, "Refactored the OAuth client");
+    }
+
+    #[test]
+    fn test_off_drops_summaries_and_commits() {
+        let mut s = session();
+        s.summary_source = Some(SummarySource::Manual);
+        scrub_session(&mut s, PrivacyMode::Off, &key());
+        assert!(s.summary.is_empty() && s.git_commits.is_empty());
+        assert_eq!(s.summary_source, Some(SummarySource::Minimal));
+        let range = s.git_range.unwrap();
+        assert_eq!(range.files[0].path, key().hash("src/acme/billing.rs"));
+        assert_eq!(range.branch, Some(key().hash("acme-billing")));
+        assert_eq!(range.insertions, 3);
+        assert_eq!(s.machine, key().hash("mac"));
+
+        // The enrichment pass scrubs the already-scrubbed record again.
+        let mut twice = session();
+        scrub_session(&mut twice, PrivacyMode::Off, &key());
+        scrub_session(&mut twice, PrivacyMode::Off, &key());
+        assert_eq!(twice.git_range.unwrap().files[0].path, key().hash("src/acme/billing.rs"));
+        assert_eq!(twice.touched_files[0].path, key().hash("src/acme/billing.rs"));
+        assert_eq!(twice.machine, key().hash("mac"));
+
+        let mut full = session();
+        scrub_session(&mut full, PrivacyMode::Full, &key());
+        assert_eq!(full.transcript_highlights.len(), 1);
+        assert_eq!(full.git_range.unwrap().files[0].path, "src/acme/billing.rs");
+        assert_eq!(full.machine, "mac");
+    }
+
+    #[test]
+    fn test_scrub_checkpoint_hashes_paths_when_off() {
+        let mut c: Checkpoint = serde_json::from_value(serde_json::json!({
+            "schema_version": 1, "id": "chk_1", "session_id": "ses_1", "project_id": "p",
+            "machine": "mac", "timestamp": "2026-01-01T10:00:00Z",
+            "git_diff_stat": "2 files changed", "files_changed": ["src/acme/billing.rs"],
+            "recent_commits": ["abc Fix"], "source": "test",
+            "git_context": {"branch": "acme-billing", "worktree": "/work/acme", "upstream": "origin/acme-billing", "ahead": 2},
+        })).unwrap();
+        scrub_checkpoint(&mut c, PrivacyMode::Off, &key());
+        assert_eq!(c.machine, key().hash("mac"));
+        assert_eq!(c.files_changed, vec![key().hash("src/acme/billing.rs")]);
+        assert_eq!(c.files_changed[0].len(), HASH_PREFIX.len() + 12);
+        assert!(c.git_diff_stat.is_none() && c.recent_commits.is_empty());
+        let context = c.git_context.unwrap();
+        assert_eq!(context.branch, Some(key().hash("acme-billing")));
+        assert_eq!(context.worktree, Some(key().hash("/work/acme")));
+        assert_eq!(context.ahead, 2);
+    }
+
+    #[test]
+    fn test_hash_is_keyed_and_marked() {
+        let other = HashKey::new(b"another-secret");
+        assert_ne!(key().hash("laptop"), other.hash("laptop"));
+        assert_eq!(key().hash("laptop"), key().hash("laptop"));
+        assert!(key().hash("laptop").starts_with(HASH_PREFIX));
+
+        // A branch that merely looks like a hash is still hashed
+        let mut s = session();
+        s.git_range.as_mut().unwrap().branch = Some("deadbeefcafe".into());
+        scrub_session(&mut s, PrivacyMode::Off, &key());
+        assert_eq!(s.git_range.unwrap().branch, Some(key().hash("deadbeefcafe")));
+    }
+
+    #[test]
+    fn test_hash_key_is_generated_once_and_gitignored() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let first = HashKey::load_or_create(tmp.path()).unwrap();
+        let second = HashKey::load_or_create(tmp.path()).unwrap();
+        assert_eq!(first.hash("mac"), second.hash("mac"));
+        assert_ne!(first.hash("mac"), key().hash("mac"));
+        let gitignore = std::fs::read_to_string(tmp.path().join(".gitignore")).unwrap();
+        assert!(gitignore.lines().any(|l| l == LOCAL_CONFIG_FILE));
+
+        let unused = tmp.path().join("full");
+        std::fs::create_dir_all(&unused).unwrap();
+        HashKey::for_mode(&unused, PrivacyMode::Full).unwrap();
+        assert!(!unused.join(LOCAL_CONFIG_FILE).exists());
+    }
+}
@@ -0,0 +1,712 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use crate::models::{ProjectInfo, ProjectMeta, SCHEMA_VERSION};
+use crate::privacy::HashKey;
+
+/// Stable identity of a working directory, independent of its basename.
+///
+/// Keys take one of three forms, in order of preference:
+/// - `remote:<host>/<path>` — normalized URL of `origin` (or the first remote)
+/// - `root:<sha>` — the repository's root commit
+/// - `path:<canonical path>` — fallback for non-git directories
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectIdentity {
+    pub key: String,
+    /// Repository workdir (or canonical cwd outside git). Recorded in `ProjectMeta.paths`.
+    pub root: PathBuf,
+    /// Human-readable name, used for new project directories and display.
+    pub name: String,
+}
+
+/// Outcome of a `migrate_projects` run.
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    /// (slug, key) for legacy projects that were assigned an identity.
+    pub identified: Vec<(String, String)>,
+    /// (session file name, from slug, to slug) for sessions moved to their real project.
+    pub split: Vec<(String, String, String)>,
+    /// (from slug, into slug) for directories that turned out to be the same project.
+    pub merged: Vec<(String, String)>,
+    pub warnings: Vec<String>,
+}
+
+pub fn identify(cwd: &Path) -> ProjectIdentity {
+    let canonical = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    if let Ok(repo) = git2::Repository::discover(&canonical) {
+        if let Some(workdir) = repo.workdir() {
+            let root = workdir.canonicalize().unwrap_or_else(|_| workdir.to_path_buf());
+            let name = dir_name(&root);
+            if let Some(url) = primary_remote_url(&repo) {
+                return ProjectIdentity { key: format!("remote:{}", normalize_remote_url(&url)), root, name };
+            }
+            if let Some(oid) = root_commit(&repo) {
+                return ProjectIdentity { key: format!("root:{}", oid), root, name };
+            }
+            return ProjectIdentity { key: format!("path:{}", root.display()), root, name };
+        }
+    }
+    let name = dir_name(&canonical);
+    ProjectIdentity { key: format!("path:{}", canonical.display()), root: canonical, name }
+}
+
+/// The identity with its key, root path and name replaced by keyed hashes, for
+/// `PrivacyMode::Off`. Machines sharing the hash key map the same repository to the
+/// same project.
+pub fn anonymize(identity: &ProjectIdentity, hash_key: &HashKey) -> ProjectIdentity {
+    let key_hash = hash_key.digest(&identity.key);
+    ProjectIdentity {
+        key: format!("anon:{}", key_hash),
+        root: PathBuf::from(format!("anon:{}", hash_key.digest(&identity.root.to_string_lossy()))),
+        name: format!("project-{}", &key_hash[..8]),
+    }
+}
+
+/// Reduce the many spellings of a remote to one: `git@github.com:Me/Repo.git`,
+/// `https://user@github.com/Me/Repo` and `ssh://git@github.com/Me/Repo.git/` all
+/// become `github.com/Me/Repo`.
+pub fn normalize_remote_url(url: &str) -> String {
+    let url = url.trim();
+    let (rest, had_scheme) = match url.find("://") {
+        Some(i) => (&url[i + 3..], true),
+        None => (url, false),
+    };
+    let rest = match rest.find('@') {
+        Some(i) if rest[..i].find('/').is_none() => &rest[i + 1..],
+        _ => rest,
+    };
+    // scp-like syntax: host:path
+    let rest = if had_scheme { rest.to_string() } else { rest.replacen(':', "/", 1) };
+    let rest = rest.trim_end_matches('/');
+    let rest = rest.strip_suffix(".git").unwrap_or(rest);
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    // Drop an explicit port; the same repo is reachable over ssh and https.
+    let host = host.split(':').next().unwrap_or(host).to_lowercase();
+    if path.is_empty() { host } else { format!("{}/{}", host, path) }
+}
+
+fn primary_remote_url(repo: &git2::Repository) -> Option<String> {
+    if let Ok(origin) = repo.find_remote("origin") {
+        if let Some(url) = origin.url() {
+            return Some(url.to_string());
+        }
+    }
+    let remotes = repo.remotes().ok()?;
+    let mut names: Vec<&str> = remotes.iter().flatten().collect();
+    names.sort();
+    names.into_iter()
+        .filter_map(|n| repo.find_remote(n).ok())
+        .find_map(|r| r.url().map(|u| u.to_string()))
+}
+
+/// Root commit reached by following first parents from HEAD.
+fn root_commit(repo: &git2::Repository) -> Option<git2::Oid> {
+    let mut commit = repo.head().ok()?.peel_to_commit().ok()?;
+    while let Ok(parent) = commit.parent(0) {
+        commit = parent;
+    }
+    Some(commit.id())
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown-project".into())
+}
+
+fn read_meta(project_dir: &Path) -> Option<ProjectMeta> {
+    let content = fs::read_to_string(project_dir.join("meta.toml")).ok()?;
+    toml::from_str(&content).ok()
+}
+
+fn write_meta(project_dir: &Path, meta: &ProjectMeta) -> Result<()> {
+    let toml_str = toml::to_string_pretty(meta)?;
+    crate::storage::atomic_write(&project_dir.join("meta.toml"), toml_str.as_bytes())
+}
+
+/// Project directories (slug -> meta), sorted by slug.
+fn list_projects(projects_dir: &Path) -> BTreeMap<String, ProjectMeta> {
+    let mut projects = BTreeMap::new();
+    let entries = match fs::read_dir(projects_dir) {
+        Ok(e) => e,
+        Err(_) => return projects,
+    };
+    for entry in entries.flatten() {
+        if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) { continue; }
+        if let Some(meta) = read_meta(&entry.path()) {
+            projects.insert(entry.file_name().to_string_lossy().to_string(), meta);
+        }
+    }
+    projects
+}
+
+fn same_path(a: &str, b: &Path) -> bool {
+    Path::new(a).canonicalize().map(|p| p == b).unwrap_or(false)
+}
+
+/// Slug for a new project directory: the plain name when free, otherwise the name
+/// suffixed with a short hash of the identity key.
+fn new_slug(projects_dir: &Path, identity: &ProjectIdentity) -> String {
+    if !projects_dir.join(&identity.name).exists() {
+        return identity.name.clone();
+    }
+    let hash = git2::Oid::hash_object(git2::ObjectType::Blob, identity.key.as_bytes())
+        .map(|oid| oid.to_string()[..8].to_string())
+        .unwrap_or_else(|_| uuid::Uuid::new_v4().to_string()[..8].to_string());
+    format!("{}-{}", identity.name, hash)
+}
+
+/// Find the project directory slug for `cwd`, creating or adopting one as needed.
+///
+/// Lookup order: a project whose `meta.toml` carries the same identity key; then a
+/// legacy project (no identity yet) whose path on this machine is the same directory,
+/// which gets the key written back. Otherwise a new project is registered. In every
+/// case this machine's entry in `ProjectMeta.paths` is kept up to date.
+pub fn resolve_project_dir(projects_dir: &Path, cwd: &Path, hostname: &str) -> Result<String> {
+    resolve_identity_dir(projects_dir, &identify(cwd), hostname)
+}
+
+/// Like [`resolve_project_dir`], but registers the project under an [`anonymize`]d identity.
+pub fn resolve_anonymous_project_dir(projects_dir: &Path, cwd: &Path, hostname: &str, hash_key: &HashKey) -> Result<String> {
+    resolve_identity_dir(projects_dir, &anonymize(&identify(cwd), hash_key), hostname)
+}
+
+fn resolve_identity_dir(projects_dir: &Path, identity: &ProjectIdentity, hostname: &str) -> Result<String> {
+    let projects = list_projects(projects_dir);
+    let root_str = identity.root.to_string_lossy().to_string();
+
+    let found = projects.iter()
+        .find(|(_, m)| m.project.identity.as_deref() == Some(identity.key.as_str()))
+        .or_else(|| projects.iter().find(|(_, m)| {
+            m.project.identity.is_none()
+                && m.paths.get(hostname).is_some_and(|p| same_path(p, &identity.root))
+        }));
+
+    if let Some((slug, meta)) = found {
+        let needs_key = meta.project.identity.is_none();
+        let needs_path = meta.paths.get(hostname) != Some(&root_str);
+        if needs_key || needs_path {
+            let mut meta = meta.clone();
+            meta.project.identity = Some(identity.key.clone());
+            meta.paths.insert(hostname.to_string(), root_str);
+            write_meta(&projects_dir.join(slug), &meta)?;
+        }
+        return Ok(slug.clone());
+    }
+
+    let slug = new_slug(projects_dir, identity);
+    let meta = ProjectMeta {
+        schema_version: SCHEMA_VERSION,
+        project: ProjectInfo {
+            id: format!("proj_{}", &uuid::Uuid::new_v4().to_string()[..8]),
+            name: identity.name.clone(),
+            status: "active".into(),
+            created_at: chrono::Utc::now(),
+            archived_at: None,
+            description: String::new(),
+            identity: Some(identity.key.clone()),
+        },
+        paths: HashMap::from([(hostname.to_string(), root_str)]),
+    };
+    write_meta(&projects_dir.join(&slug), &meta)?;
+    Ok(slug)
+}
+
+/// Machine-local cache of resolved project slugs, relative to the data directory.
+pub const SLUG_CACHE_FILE: &str = ".cache/slugs.json";
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct CachedSlug {
+    slug: String,
+    fingerprint: String,
+}
+
+fn load_slug_cache(data_dir: &Path) -> BTreeMap<String, CachedSlug> {
+    fs::read_to_string(data_dir.join(SLUG_CACHE_FILE))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// Slug previously resolved for `cwd_key`.
+///
+/// Every hook and MCP call resolves its project; the cache spares them git discovery and
+/// the meta.toml scan. An entry is only used while `fingerprint` (the settings the
+/// resolution depends on) is unchanged and the project still exists.
+pub fn cached_slug(data_dir: &Path, cwd_key: &str, fingerprint: &str) -> Option<String> {
+    let entry = load_slug_cache(data_dir).remove(cwd_key)?;
+    (entry.fingerprint == fingerprint && data_dir.join("projects").join(&entry.slug).join("meta.toml").exists())
+        .then_some(entry.slug)
+}
+
+pub fn cache_slug(data_dir: &Path, cwd_key: &str, fingerprint: &str, slug: &str) -> Result<()> {
+    let mut cache = load_slug_cache(data_dir);
+    cache.insert(cwd_key.to_string(), CachedSlug { slug: slug.to_string(), fingerprint: fingerprint.to_string() });
+    // Keyed by local paths; never synced
+    crate::storage::ensure_gitignored(data_dir, &[".cache/"])?;
+    crate::storage::write_json(&data_dir.join(SLUG_CACHE_FILE), &cache)
+}
+
+pub fn clear_slug_cache(data_dir: &Path) -> Result<()> {
+    match fs::remove_file(data_dir.join(SLUG_CACHE_FILE)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Working directory a Claude Code session ran in, read from its transcript.
+///
+/// Transcripts live at `<transcripts_root>/<encoded cwd>/<session uuid>.jsonl` and
+/// every entry carries a `cwd` field.
+pub fn session_cwd(transcripts_root: &Path, session_uuid: &str) -> Option<PathBuf> {
+    let file_name = format!("{}.jsonl", session_uuid);
+    let path = fs::read_dir(transcripts_root).ok()?
+        .flatten()
+        .map(|e| e.path().join(&file_name))
+        .find(|p| p.is_file())?;
+    let reader = BufReader::new(fs::File::open(path).ok()?);
+    reader.lines()
+        .map_while(|l| l.ok())
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(&l).ok())
+        .find_map(|v| v.get("cwd").and_then(|c| c.as_str()).map(PathBuf::from))
+}
+
+fn json_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect())
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// Move a session/checkpoint JSON into another project, rewriting its `project_id`.
+/// Unknown fields are preserved. An existing file at the destination is left alone.
+fn move_record(src: &Path, dest_dir: &Path, project_id: &str) -> Result<bool> {
+    let dest = dest_dir.join(src.file_name().unwrap_or_default());
+    if dest.exists() {
+        return Ok(false);
+    }
+    let mut value: serde_json::Value = serde_json::from_str(&crate::encryption::read_record(src)?)?;
+    value["project_id"] = serde_json::Value::String(project_id.to_string());
+    crate::storage::write_json(&dest, &value)?;
+    fs::remove_file(src)?;
+    Ok(true)
+}
+
+fn record_session_id(path: &Path) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(&crate::encryption::read_record(path).ok()?).ok()?;
+    value.get("session_id").or_else(|| value.get("id"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+/// Rewrite legacy basename-slug project directories to identity-keyed ones.
+///
+/// 1. **Identify:** legacy projects with a path on this machine get an identity key.
+/// 2. **Split:** sessions whose transcript shows they ran in a different project are
+///    moved (with their checkpoints) to that project, registering it if needed.
+/// 3. **Merge:** directories that share an identity are folded into the oldest one;
+///    the emptied directory is kept under `<data_dir>/.migrated/` (gitignored, never
+///    synced) rather than deleted.
+///
+/// With `dry_run`, nothing is written; the report describes what would change.
+pub fn migrate_projects(
+    data_dir: &Path,
+    hostname: &str,
+    transcripts_root: &Path,
+    dry_run: bool,
+) -> Result<MigrationReport> {
+    let projects_dir = data_dir.join("projects");
+    let mut report = MigrationReport::default();
+
+    // 1. Identify
+    let mut keys: HashMap<String, String> = HashMap::new();
+    for (slug, mut meta) in list_projects(&projects_dir) {
+        if let Some(key) = &meta.project.identity {
+            keys.insert(slug, key.clone());
+            continue;
+        }
+        let local = match meta.paths.get(hostname) {
+            Some(p) if Path::new(p).exists() => p.clone(),
+            _ => {
+                report.warnings.push(format!("{}: no local path on {}, left unchanged", slug, hostname));
+                continue;
+            }
+        };
+        let identity = identify(Path::new(&local));
+        if !dry_run {
+            meta.project.identity = Some(identity.key.clone());
+            meta.paths.insert(hostname.to_string(), identity.root.to_string_lossy().to_string());
+            write_meta(&projects_dir.join(&slug), &meta)?;
+        }
+        report.identified.push((slug.clone(), identity.key.clone()));
+        keys.insert(slug, identity.key);
+    }
+
+    // 2. Split
+    for (slug, key) in keys.clone() {
+        let project_dir = projects_dir.join(&slug);
+        for session_file in json_files(&project_dir.join("sessions")) {
+            let session_id = match record_session_id(&session_file) {
+                Some(id) => id,
+                None => continue,
+            };
+            let uuid = session_id.strip_prefix("ses_").unwrap_or(&session_id);
+            let cwd = match session_cwd(transcripts_root, uuid) {
+                Some(c) if c.exists() => c,
+                _ => continue,
+            };
+            let identity = identify(&cwd);
+            if identity.key == key { continue; }
+
+            let file_name = session_file.file_name().unwrap_or_default().to_string_lossy().to_string();
+            if dry_run {
+                let target = keys.iter().find(|(_, k)| **k == identity.key)
+                    .map(|(s, _)| s.clone())
+                    .unwrap_or_else(|| format!("(new) {}", identity.name));
+                report.split.push((file_name, slug.clone(), target));
+                continue;
+            }
+            let target = resolve_identity_dir(&projects_dir, &identity, hostname)?;
+            keys.insert(target.clone(), identity.key.clone());
+            let target_dir = projects_dir.join(&target);
+            let target_id = read_meta(&target_dir).map(|m| m.project.id).unwrap_or_default();
+            if !move_record(&session_file, &target_dir.join("sessions"), &target_id)? {
+                report.warnings.push(format!("{}: already present in {}, left in {}", file_name, target, slug));
+                continue;
+            }
+            for checkpoint in json_files(&project_dir.join("checkpoints")) {
+                if record_session_id(&checkpoint).as_deref() == Some(session_id.as_str()) {
+                    move_record(&checkpoint, &target_dir.join("checkpoints"), &target_id)?;
+                }
+            }
+            report.split.push((file_name, slug.clone(), target));
+        }
+    }
+
+    // 3. Merge
+    let projects = list_projects(&projects_dir);
+    let mut by_key: BTreeMap<&str, Vec<(&String, &ProjectMeta)>> = BTreeMap::new();
+    for (slug, meta) in &projects {
+        if let Some(key) = keys.get(slug) {
+            by_key.entry(key.as_str()).or_default().push((slug, meta));
+        }
+    }
+    for group in by_key.values_mut().filter(|g| g.len() > 1) {
+        group.sort_by_key(|(slug, meta)| (meta.project.created_at, (*slug).clone()));
+        let (target_slug, target_meta) = group[0];
+        let target_dir = projects_dir.join(target_slug);
+        let mut merged_meta = target_meta.clone();
+
+        for (slug, meta) in group.iter().skip(1) {
+            report.merged.push(((*slug).clone(), target_slug.clone()));
+            if dry_run { continue; }
+            let source_dir = projects_dir.join(slug);
+            for sub in ["sessions", "checkpoints"] {
+                for file in json_files(&source_dir.join(sub)) {
+                    if !move_record(&file, &target_dir.join(sub), &target_meta.project.id)? {
+                        report.warnings.push(format!(
+                            "{}/{}/{}: duplicate kept in backup",
+                            slug, sub, file.file_name().unwrap_or_default().to_string_lossy()
+                        ));
+                    }
+                }
+            }
+            for (host, path) in &meta.paths {
+                merged_meta.paths.entry(host.clone()).or_insert_with(|| path.clone());
+            }
+            let source_roadmap = source_dir.join("roadmap.md");
+            let target_roadmap = target_dir.join("roadmap.md");
+            if source_roadmap.exists() {
+                if !target_roadmap.exists() {
+                    fs::rename(&source_roadmap, &target_roadmap)?;
+                } else {
+                    report.warnings.push(format!("{}: roadmap.md differs from {}, kept in backup", slug, target_slug));
+                }
+            }
+            // Backups are local-only; syncing them would duplicate every merged project
+            crate::storage::ensure_gitignored(data_dir, &[".migrated/"])?;
+            let backup_dir = data_dir.join(".migrated");
+            fs::create_dir_all(&backup_dir)?;
+            let backup = backup_dir.join(format!("{}_{}", slug, chrono::Utc::now().format("%Y%m%d_%H%M%S")));
+            fs::rename(&source_dir, &backup)?;
+        }
+        if !dry_run {
+            write_meta(&target_dir, &merged_meta)?;
+        }
+    }
+
+    // Directories may now resolve to a different project
+    if !dry_run {
+        clear_slug_cache(data_dir)?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn init_repo(path: &Path, remote: Option<&str>) -> git2::Repository {
+        fs::create_dir_all(path).unwrap();
+        let repo = git2::Repository::init(path).unwrap();
+        if let Some(url) = remote {
+            repo.remote("origin", url).unwrap();
+        }
+        {
+            let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+            let tree_id = repo.index().unwrap().write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[]).unwrap();
+        }
+        repo
+    }
+
+    fn write_legacy_meta(projects_dir: &Path, slug: &str, id: &str, host: &str, path: &Path) {
+        let meta = ProjectMeta {
+            schema_version: SCHEMA_VERSION,
+            project: ProjectInfo {
+                id: id.into(),
+                name: slug.into(),
+                status: "active".into(),
+                created_at: chrono::Utc::now(),
+                archived_at: None,
+                description: String::new(),
+                identity: None,
+            },
+            paths: HashMap::from([(host.to_string(), path.to_string_lossy().to_string())]),
+        };
+        write_meta(&projects_dir.join(slug), &meta).unwrap();
+    }
+
+    fn write_session(projects_dir: &Path, slug: &str, uuid: &str, project_id: &str) {
+        let session = serde_json::json!({
+            "schema_version": 1,
+            "id": format!("ses_{}", uuid),
+            "project_id": project_id,
+            "machine": "mac",
+            "started_at": "2026-01-01T10:00:00Z",
+            "summary": "work",
+        });
+        crate::storage::write_json(
+            &projects_dir.join(slug).join("sessions").join(format!("20260101_mac_{}.json", uuid)),
+            &session,
+        ).unwrap();
+    }
+
+    fn write_transcript(root: &Path, uuid: &str, cwd: &Path) {
+        let dir = root.join(cwd.to_string_lossy().replace('/', "-"));
+        fs::create_dir_all(&dir).unwrap();
+        let line = serde_json::json!({"type": "user", "cwd": cwd, "message": {"role": "user", "content": "hi"}});
+        fs::write(dir.join(format!("{}.jsonl", uuid)), line.to_string()).unwrap();
+    }
+
+    #[test]
+    fn test_normalize_remote_url() {
+        assert_eq!(normalize_remote_url("git@github.com:Me/Repo.git"), "github.com/Me/Repo");
+        assert_eq!(normalize_remote_url("https://github.com/Me/Repo"), "github.com/Me/Repo");
+        assert_eq!(normalize_remote_url("https://user@GitHub.com/Me/Repo.git/"), "github.com/Me/Repo");
+        assert_eq!(normalize_remote_url("ssh://git@github.com:22/Me/Repo.git"), "github.com/Me/Repo");
+    }
+
+    #[test]
+    fn test_identify_prefers_remote_then_root_then_path() {
+        let tmp = TempDir::new().unwrap();
+        let with_remote = tmp.path().join("a");
+        init_repo(&with_remote, Some("git@github.com:me/a.git"));
+        fs::create_dir_all(with_remote.join("src")).unwrap();
+        let id = identify(&with_remote.join("src"));
+        assert_eq!(id.key, "remote:github.com/me/a");
+        assert_eq!(id.root, with_remote.canonicalize().unwrap());
+        assert_eq!(id.name, "a");
+
+        let local_only = tmp.path().join("b");
+        init_repo(&local_only, None);
+        assert!(identify(&local_only).key.starts_with("root:"));
+
+        let plain = tmp.path().join("c");
+        fs::create_dir_all(&plain).unwrap();
+        assert_eq!(identify(&plain).key, format!("path:{}", plain.canonicalize().unwrap().display()));
+    }
+
+    #[test]
+    fn test_root_commit_follows_first_parents() {
+        let tmp = TempDir::new().unwrap();
+        let repo = init_repo(&tmp.path().join("r"), None);
+        let first = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = first.tree().unwrap();
+        // An unrelated history merged in as the second parent
+        let other = repo.commit(None, &sig, &sig, "other root", &tree, &[]).unwrap();
+        let other = repo.find_commit(other).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "merge", &tree, &[&first, &other]).unwrap();
+        assert_eq!(root_commit(&repo), Some(first.id()));
+    }
+
+    #[test]
+    fn test_slug_cache_checks_fingerprint_and_project() {
+        let tmp = TempDir::new().unwrap();
+        assert_eq!(cached_slug(tmp.path(), "/work/api", "fp"), None);
+        cache_slug(tmp.path(), "/work/api", "fp", "api").unwrap();
+        // The project directory is gone (merged, deleted): not trusted
+        assert_eq!(cached_slug(tmp.path(), "/work/api", "fp"), None);
+
+        fs::create_dir_all(tmp.path().join("projects/api")).unwrap();
+        fs::write(tmp.path().join("projects/api/meta.toml"), "").unwrap();
+        assert_eq!(cached_slug(tmp.path(), "/work/api", "fp").as_deref(), Some("api"));
+        assert_eq!(cached_slug(tmp.path(), "/work/api", "other"), None);
+        assert!(fs::read_to_string(tmp.path().join(".gitignore")).unwrap().lines().any(|l| l == ".cache/"));
+
+        clear_slug_cache(tmp.path()).unwrap();
+        assert_eq!(cached_slug(tmp.path(), "/work/api", "fp"), None);
+    }
+
+    #[test]
+    fn test_same_basename_gets_distinct_projects() {
+        let tmp = TempDir::new().unwrap();
+        let projects_dir = tmp.path().join("projects");
+        let work = tmp.path().join("work").join("api");
+        let oss = tmp.path().join("oss").join("api");
+        init_repo(&work, Some("git@github.com:corp/api.git"));
+        init_repo(&oss, Some("https://github.com/me/api"));
+
+        let first = resolve_project_dir(&projects_dir, &work, "mac").unwrap();
+        let second = resolve_project_dir(&projects_dir, &oss, "mac").unwrap();
+        assert_eq!(first, "api");
+        assert!(second.starts_with("api-"));
+        // Stable on repeat lookups, including from a subdirectory
+        fs::create_dir_all(oss.join("src")).unwrap();
+        assert_eq!(resolve_project_dir(&projects_dir, &oss.join("src"), "mac").unwrap(), second);
+    }
+
+    #[test]
+    fn test_same_remote_on_another_machine_maps_to_same_project() {
+        let tmp = TempDir::new().unwrap();
+        let projects_dir = tmp.path().join("projects");
+        let laptop = tmp.path().join("laptop").join("app");
+        let desktop = tmp.path().join("desktop").join("my-app");
+        init_repo(&laptop, Some("git@github.com:me/app.git"));
+        init_repo(&desktop, Some("https://github.com/me/app.git"));
+
+        let a = resolve_project_dir(&projects_dir, &laptop, "laptop").unwrap();
+        let b = resolve_project_dir(&projects_dir, &desktop, "desktop").unwrap();
+        assert_eq!(a, b);
+        let meta = read_meta(&projects_dir.join(&a)).unwrap();
+        assert_eq!(meta.paths.len(), 2);
+        assert_eq!(meta.paths["desktop"], desktop.canonicalize().unwrap().to_string_lossy());
+    }
+
+    #[test]
+    fn test_anonymous_project_has_no_name_or_path() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let repo = root.join("acme-billing");
+        init_repo(&repo, Some("git@github.com:acme/billing.git"));
+        fs::create_dir_all(repo.join("src")).unwrap();
+        let projects_dir = root.join("projects");
+
+        let key = HashKey::new(b"secret");
+        let slug = resolve_anonymous_project_dir(&projects_dir, &repo, "mac", &key).unwrap();
+        assert!(slug.starts_with("project-"));
+        let meta_toml = fs::read_to_string(projects_dir.join(&slug).join("meta.toml")).unwrap();
+        assert!(!meta_toml.contains("acme"), "{}", meta_toml);
+        assert!(!meta_toml.contains(&*repo.to_string_lossy()));
+        assert_eq!(resolve_anonymous_project_dir(&projects_dir, &repo.join("src"), "laptop", &key).unwrap(), slug);
+        let other_key = HashKey::new(b"other");
+        assert_ne!(resolve_anonymous_project_dir(&projects_dir, &repo, "mac", &other_key).unwrap(), slug);
+    }
+
+    #[test]
+    fn test_resolve_adopts_legacy_project() {
+        let tmp = TempDir::new().unwrap();
+        let projects_dir = tmp.path().join("projects");
+        let repo = tmp.path().join("api");
+        init_repo(&repo, Some("git@github.com:corp/api.git"));
+        write_legacy_meta(&projects_dir, "api", "proj_legacy", "mac", &repo);
+
+        assert_eq!(resolve_project_dir(&projects_dir, &repo, "mac").unwrap(), "api");
+        let meta = read_meta(&projects_dir.join("api")).unwrap();
+        assert_eq!(meta.project.id, "proj_legacy");
+        assert_eq!(meta.project.identity.as_deref(), Some("remote:github.com/corp/api"));
+    }
+
+    #[test]
+    fn test_migrate_splits_collided_sessions() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("data");
+        let projects_dir = data_dir.join("projects");
+        let transcripts = tmp.path().join("transcripts");
+        let work = tmp.path().join("work").join("app");
+        let oss = tmp.path().join("oss").join("app");
+        init_repo(&work, Some("git@github.com:corp/app.git"));
+        init_repo(&oss, Some("git@github.com:me/app.git"));
+
+        // Both repos were recorded under the same basename slug
+        write_legacy_meta(&projects_dir, "app", "proj_app", "mac", &work);
+        write_session(&projects_dir, "app", "aaa", "proj_app");
+        write_session(&projects_dir, "app", "bbb", "proj_app");
+        write_transcript(&transcripts, "aaa", &work);
+        write_transcript(&transcripts, "bbb", &oss);
+
+        let preview = migrate_projects(&data_dir, "mac", &transcripts, true).unwrap();
+        assert_eq!(preview.split.len(), 1);
+        assert_eq!(json_files(&projects_dir.join("app").join("sessions")).len(), 2);
+
+        let report = migrate_projects(&data_dir, "mac", &transcripts, false).unwrap();
+        assert_eq!(report.identified, vec![("app".to_string(), "remote:github.com/corp/app".to_string())]);
+        assert_eq!(report.split.len(), 1);
+        let target = &report.split[0].2;
+        assert_ne!(target, "app");
+
+        let moved = json_files(&projects_dir.join(target).join("sessions"));
+        assert_eq!(moved.len(), 1);
+        let moved: serde_json::Value = serde_json::from_str(&fs::read_to_string(&moved[0]).unwrap()).unwrap();
+        assert_eq!(moved["id"], "ses_bbb");
+        assert_eq!(moved["project_id"], read_meta(&projects_dir.join(target)).unwrap().project.id);
+        assert_eq!(json_files(&projects_dir.join("app").join("sessions")).len(), 1);
+    }
+
+    #[test]
+    fn test_migrate_merges_same_project() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("data");
+        let projects_dir = data_dir.join("projects");
+        let repo = tmp.path().join("app");
+        init_repo(&repo, Some("git@github.com:me/app.git"));
+
+        // Older directory from another machine (identity already assigned there)
+        write_legacy_meta(&projects_dir, "my-app", "proj_old", "desktop", Path::new("/home/me/my-app"));
+        let mut meta = read_meta(&projects_dir.join("my-app")).unwrap();
+        meta.project.identity = Some("remote:github.com/me/app".into());
+        meta.project.created_at = "2025-01-01T00:00:00Z".parse().unwrap();
+        write_meta(&projects_dir.join("my-app"), &meta).unwrap();
+        write_session(&projects_dir, "my-app", "old", "proj_old");
+
+        // Newer directory from this machine
+        write_legacy_meta(&projects_dir, "app", "proj_new", "mac", &repo);
+        write_session(&projects_dir, "app", "new", "proj_new");
+        fs::write(projects_dir.join("app").join("roadmap.md"), "## P\n- [ ] x\n").unwrap();
+
+        let report = migrate_projects(&data_dir, "mac", tmp.path(), false).unwrap();
+        assert_eq!(report.merged, vec![("app".to_string(), "my-app".to_string())]);
+        assert!(!projects_dir.join("app").exists());
+        assert_eq!(fs::read_dir(data_dir.join(".migrated")).unwrap().count(), 1);
+        let gitignore = fs::read_to_string(data_dir.join(".gitignore")).unwrap();
+        assert!(gitignore.lines().any(|l| l == ".migrated/"));
+
+        let merged = read_meta(&projects_dir.join("my-app")).unwrap();
+        assert_eq!(merged.paths.len(), 2);
+        assert!(projects_dir.join("my-app").join("roadmap.md").exists());
+        let sessions = json_files(&projects_dir.join("my-app").join("sessions"));
+        assert_eq!(sessions.len(), 2);
+        for s in sessions {
+            let v: serde_json::Value = serde_json::from_str(&fs::read_to_string(s).unwrap()).unwrap();
+            assert_eq!(v["project_id"], "proj_old");
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use crate::models::{ProjectInfo, ProjectMeta, SCHEMA_VERSION};
+
+/// User-defined cwd → project rules, stored in `~/.seslog/projects.toml`:
+///
+/// ```toml
+/// [[rule]]
+/// match = "~/work/mono/services/*"
+/// project = "mono-{name}"
+///
+/// [[rule]]
+/// match = "~/work/mono"
+/// project = "mono"
+/// ```
+///
+/// `match` is a glob (`*`, `?`, `[...]`, `**`) or a plain path prefix. Rules are tried in
+/// file order against the cwd and each of its parent directories; the first hit wins.
+/// `{name}` in `project` expands to the folder name of the matched directory. The
+/// resolved value becomes a directory under `projects/`, so it may only contain letters,
+/// digits, `-`, `_` and `.` (and never `..`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectMap {
+    #[serde(default, rename = "rule")]
+    pub rules: Vec<MappingRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MappingRule {
+    #[serde(rename = "match")]
+    pub pattern: String,
+    pub project: String,
+}
+
+/// A rule hit: the target project slug and the directory the rule matched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleMatch {
+    pub slug: String,
+    pub dir: PathBuf,
+}
+
+/// Whether `slug` is safe to use as a directory name under `projects/`.
+pub fn is_valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && slug != "."
+        && !slug.contains("..")
+        && slug.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+pub fn load_map(path: &Path) -> Result<ProjectMap> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => {
+            let map: ProjectMap = toml::from_str(&content)?;
+            for rule in &map.rules {
+                rule.validate().map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+            }
+            Ok(map)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ProjectMap::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn write_map(path: &Path, map: &ProjectMap) -> Result<()> {
+    let content = toml::to_string_pretty(map)?;
+    crate::storage::atomic_write(path, content.as_bytes())
+}
+
+fn expand_home(pattern: &str) -> PathBuf {
+    match (pattern.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(pattern),
+    }
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+impl MappingRule {
+    /// Reject `project` values that could not name a directory under `projects/`.
+    ///
+    /// `{name}` is checked with a placeholder; folder names it expands to are checked
+    /// again at lookup time.
+    pub fn validate(&self) -> Result<()> {
+        if !is_valid_slug(&self.project.replace("{name}", "name")) {
+            anyhow::bail!(
+                "rule '{}': invalid project '{}' (use letters, digits, '-', '_' or '.', optionally with {{name}})",
+                self.pattern, self.project
+            );
+        }
+        Ok(())
+    }
+
+    /// The directory this rule matches for `cwd` (the cwd itself or one of its parents).
+    pub fn matched_dir(&self, cwd: &Path) -> Option<PathBuf> {
+        let expanded = expand_home(&self.pattern);
+        if is_glob(&self.pattern) {
+            let pattern = glob::Pattern::new(&expanded.to_string_lossy()).ok()?;
+            let options = glob::MatchOptions { require_literal_separator: true, ..Default::default() };
+            cwd.ancestors()
+                .find(|dir| pattern.matches_path_with(dir, options))
+                .map(Path::to_path_buf)
+        } else {
+            let prefix = expanded.canonicalize().unwrap_or(expanded);
+            cwd.starts_with(&prefix).then_some(prefix)
+        }
+    }
+}
+
+impl ProjectMap {
+    pub fn lookup(&self, cwd: &Path) -> Option<RuleMatch> {
+        let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+        self.rules.iter().find_map(|rule| {
+            let dir = rule.matched_dir(&cwd)?;
+            let name = dir.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+            // A folder name that doesn't make a safe slug falls through to the next rule
+            let slug = rule.project.replace("{name}", &name);
+            is_valid_slug(&slug).then_some(RuleMatch { slug, dir })
+        })
+    }
+
+    /// Add a rule, replacing any existing rule with the same pattern in place.
+    pub fn add(&mut self, rule: MappingRule) {
+        match self.rules.iter_mut().find(|r| r.pattern == rule.pattern) {
+            Some(existing) => *existing = rule,
+            None => self.rules.push(rule),
+        }
+    }
+
+    /// Remove the rule with this pattern. Returns whether one was removed.
+    pub fn remove(&mut self, pattern: &str) -> bool {
+        let before = self.rules.len();
+        self.rules.retain(|r| r.pattern != pattern);
+        self.rules.len() != before
+    }
+}
+
+/// Resolve `cwd` through the mapping rules, registering the target project if it has
+/// no `meta.toml` yet. Returns `None` when no rule matches.
+///
+/// The project is identified as `rule:<slug>`; a `projects/<slug>/` that belongs to
+/// another project is an error rather than silently shared. With a `hash_key`
+/// (`privacy_mode = "off"`) the identity is [`anonymize`]d like on the identity path,
+/// so the slug, name and matched directory are keyed hashes.
+///
+/// [`anonymize`]: crate::project_identity::anonymize
+pub fn resolve_mapped(
+    projects_dir: &Path,
+    map: &ProjectMap,
+    cwd: &Path,
+    hostname: &str,
+    hash_key: Option<&crate::privacy::HashKey>,
+) -> Result<Option<String>> {
+    let hit = match map.lookup(cwd) {
+        Some(h) => h,
+        None => return Ok(None),
+    };
+    let mut identity = crate::project_identity::ProjectIdentity {
+        key: format!("rule:{}", hit.slug),
+        root: hit.dir.clone(),
+        name: hit.slug.clone(),
+    };
+    if let Some(key) = hash_key {
+        identity = crate::project_identity::anonymize(&identity, key);
+    }
+    let slug = identity.name.clone();
+    let dir_str = identity.root.to_string_lossy().to_string();
+
+    let meta_path = projects_dir.join(&slug).join("meta.toml");
+    let existing: Option<ProjectMeta> = match std::fs::read_to_string(&meta_path) {
+        Ok(content) => Some(toml::from_str(&content).with_context(|| format!("failed to parse {}", meta_path.display()))?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    let meta = match existing {
+        Some(meta) if meta.project.identity.as_deref() != Some(identity.key.as_str()) => bail!(
+            "projects.toml maps {} to '{}', but projects/{}/ belongs to another project ({})",
+            hit.dir.display(),
+            slug,
+            slug,
+            meta.project.identity.as_deref().unwrap_or("no identity"),
+        ),
+        Some(meta) if meta.paths.contains_key(hostname) => return Ok(Some(slug)),
+        Some(mut meta) => {
+            meta.paths.insert(hostname.to_string(), dir_str);
+            meta
+        }
+        None => ProjectMeta {
+            schema_version: SCHEMA_VERSION,
+            project: ProjectInfo {
+                id: format!("proj_{}", &uuid::Uuid::new_v4().to_string()[..8]),
+                name: identity.name.clone(),
+                status: "active".into(),
+                created_at: chrono::Utc::now(),
+                archived_at: None,
+                description: String::new(),
+                identity: Some(identity.key.clone()),
+            },
+            paths: HashMap::from([(hostname.to_string(), dir_str)]),
+        },
+    };
+    let toml_str = toml::to_string_pretty(&meta)?;
+    crate::storage::atomic_write(&meta_path, toml_str.as_bytes())?;
+    Ok(Some(slug))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn rule(pattern: &Path, project: &str) -> MappingRule {
+        MappingRule { pattern: pattern.to_string_lossy().to_string(), project: project.into() }
+    }
+
+    #[test]
+    fn test_glob_rule_matches_subdirectories_with_name() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let service = root.join("mono").join("services").join("billing");
+        std::fs::create_dir_all(service.join("src").join("api")).unwrap();
+
+        let map = ProjectMap { rules: vec![rule(&root.join("mono/services/*"), "mono-{name}")] };
+        let hit = map.lookup(&service.join("src").join("api")).unwrap();
+        assert_eq!(hit.slug, "mono-billing");
+        assert_eq!(hit.dir, service);
+        assert!(map.lookup(&root.join("mono")).is_none());
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let web = root.join("mono").join("web");
+        std::fs::create_dir_all(&web).unwrap();
+
+        let map = ProjectMap { rules: vec![
+            rule(&root.join("mono/web"), "frontend"),
+            rule(&root.join("mono"), "mono"),
+        ] };
+        assert_eq!(map.lookup(&web).unwrap().slug, "frontend");
+        assert_eq!(map.lookup(&root.join("mono")).unwrap().slug, "mono");
+        // Prefix rules compare whole path components
+        std::fs::create_dir_all(root.join("monolith")).unwrap();
+        assert!(map.lookup(&root.join("monolith")).is_none());
+    }
+
+    #[test]
+    fn test_rules_with_unsafe_project_are_rejected() {
+        for project in ["../escape", "a/b", "a\\b", "..", "", "my app", "{name}/x"] {
+            let rule = MappingRule { pattern: "/a/*".into(), project: project.into() };
+            assert!(rule.validate().is_err(), "{project:?} should be rejected");
+        }
+        for project in ["mono-{name}", "{name}", "api_v2.0"] {
+            assert!(MappingRule { pattern: "/a/*".into(), project: project.into() }.validate().is_ok());
+        }
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("projects.toml");
+        std::fs::write(&path, "[[rule]]\nmatch = \"/a\"\nproject = \"../../etc\"\n").unwrap();
+        let err = load_map(&path).unwrap_err().to_string();
+        assert!(err.contains("invalid project '../../etc'"), "{err}");
+    }
+
+    #[test]
+    fn test_unsafe_folder_name_falls_through() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let odd = root.join("mono").join("my service");
+        std::fs::create_dir_all(&odd).unwrap();
+
+        let map = ProjectMap { rules: vec![
+            rule(&root.join("mono/*"), "{name}"),
+            rule(&root.join("mono"), "mono"),
+        ] };
+        assert_eq!(map.lookup(&odd).unwrap().slug, "mono");
+    }
+
+    #[test]
+    fn test_add_replaces_and_remove() {
+        let mut map = ProjectMap::default();
+        map.add(MappingRule { pattern: "/a/*".into(), project: "x".into() });
+        map.add(MappingRule { pattern: "/b".into(), project: "y".into() });
+        map.add(MappingRule { pattern: "/a/*".into(), project: "z".into() });
+        assert_eq!(map.rules.len(), 2);
+        assert_eq!(map.rules[0].project, "z");
+        assert!(map.remove("/b"));
+        assert!(!map.remove("/b"));
+    }
+
+    #[test]
+    fn test_map_round_trip_and_missing_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("projects.toml");
+        assert!(load_map(&path).unwrap().rules.is_empty());
+
+        let map = ProjectMap { rules: vec![MappingRule { pattern: "~/mono/*".into(), project: "{name}".into() }] };
+        write_map(&path, &map).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("[[rule]]"));
+        assert_eq!(load_map(&path).unwrap().rules, map.rules);
+    }
+
+    #[test]
+    fn test_resolve_mapped_registers_project() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let projects_dir = root.join("projects");
+        let service = root.join("mono").join("services").join("billing");
+        std::fs::create_dir_all(&service).unwrap();
+        let map = ProjectMap { rules: vec![rule(&root.join("mono/services/*"), "{name}")] };
+
+        let slug = resolve_mapped(&projects_dir, &map, &service, "mac", None).unwrap().unwrap();
+        assert_eq!(slug, "billing");
+        let meta: ProjectMeta = toml::from_str(
+            &std::fs::read_to_string(projects_dir.join("billing").join("meta.toml")).unwrap(),
+        ).unwrap();
+        assert_eq!(meta.project.identity.as_deref(), Some("rule:billing"));
+        assert_eq!(meta.paths["mac"], service.to_string_lossy());
+
+        assert!(resolve_mapped(&projects_dir, &map, &root, "mac", None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_resolve_mapped_refuses_another_projects_directory() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let projects_dir = root.join("projects");
+        let service = root.join("mono").join("api");
+        std::fs::create_dir_all(&service).unwrap();
+        // `api` was registered by its git remote before the rule existed
+        std::fs::create_dir_all(projects_dir.join("api")).unwrap();
+        let meta = "schema_version = 1\n\n[project]\nid = \"proj_1\"\nname = \"api\"\nstatus = \"active\"\ncreated_at = \"2026-01-01T00:00:00Z\"\nidentity = \"remote:github.com/me/api\"\n\n[paths]\n";
+        std::fs::write(projects_dir.join("api").join("meta.toml"), meta).unwrap();
+
+        let map = ProjectMap { rules: vec![rule(&root.join("mono/*"), "{name}")] };
+        let err = resolve_mapped(&projects_dir, &map, &service, "mac", None).unwrap_err().to_string();
+        assert!(err.contains("remote:github.com/me/api"), "{err}");
+        assert_eq!(std::fs::read_to_string(projects_dir.join("api").join("meta.toml")).unwrap(), meta);
+    }
+
+    #[test]
+    fn test_resolve_mapped_hashes_slug_and_name_when_anonymous() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let projects_dir = root.join("projects");
+        let service = root.join("mono").join("billing");
+        std::fs::create_dir_all(&service).unwrap();
+        let map = ProjectMap { rules: vec![rule(&root.join("mono/*"), "{name}")] };
+        let key = crate::privacy::HashKey::new(b"secret");
+
+        let slug = resolve_mapped(&projects_dir, &map, &service, "h:mac", Some(&key)).unwrap().unwrap();
+        assert!(slug.starts_with("project-") && !slug.contains("billing"), "{slug}");
+        let content = std::fs::read_to_string(projects_dir.join(&slug).join("meta.toml")).unwrap();
+        assert!(!content.contains("billing"), "{content}");
+        let meta: ProjectMeta = toml::from_str(&content).unwrap();
+        assert_eq!(meta.project.name, slug);
+        assert!(meta.project.identity.unwrap().starts_with("anon:"));
+        assert!(meta.paths["h:mac"].starts_with("anon:"));
+        // Resolving again finds the same project
+        assert_eq!(resolve_mapped(&projects_dir, &map, &service, "h:mac", Some(&key)).unwrap().unwrap(), slug);
+    }
+}
@@ -1,8 +1,8 @@
 use regex::Regex;
 use once_cell::sync::Lazy;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct RoadmapItem {
     pub status: ItemStatus,
     pub text: String,
@@ -17,6 +17,29 @@ pub struct RoadmapData {
     pub items: Vec<RoadmapItem>,
     pub progress_percent: f32,
     pub warnings: Vec<String>,
+    pub dependencies: DependencyAnalysis,
+}
+
+/// What the `{depends:}` graph says about a roadmap. Indices refer to the analyzed items;
+/// dependencies on ids that don't exist are ignored here (they show up as warnings).
+/// An id used by several items refers to the first of them.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct DependencyAnalysis {
+    /// Ids used by more than one item, in file order.
+    pub duplicate_ids: Vec<String>,
+    /// Ids along a dependency cycle. When set, `order`, `depth` and `critical_path` are left empty.
+    pub cycle: Option<Vec<String>>,
+    /// Every item after the items it depends on, file order breaking ties.
+    pub order: Vec<usize>,
+    /// Pending items whose dependencies are all done.
+    pub ready: Vec<usize>,
+    /// Per item, the ids of its dependencies that are not done yet.
+    pub waiting_on: Vec<Vec<String>>,
+    /// Per item, the number of items on the longest dependency chain leading to it.
+    pub depth: Vec<usize>,
+    /// The longest chain of unfinished items, in dependency order. Its length is the
+    /// least number of steps left before the roadmap can be finished.
+    pub critical_path: Vec<usize>,
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -29,6 +52,45 @@ pub enum ItemStatus {
     Blocked,    // [!]
 }
 
+impl ItemStatus {
+    /// The character between the brackets, e.g. `x` for `- [x]`.
+    pub fn marker(&self) -> char {
+        match self {
+            ItemStatus::Done => 'x',
+            ItemStatus::Active => '>',
+            ItemStatus::Pending => ' ',
+            ItemStatus::Suspended => '~',
+            ItemStatus::Blocked => '!',
+        }
+    }
+
+    /// Lowercase name, as used in JSON and on the command line.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemStatus::Done => "done",
+            ItemStatus::Active => "active",
+            ItemStatus::Pending => "pending",
+            ItemStatus::Suspended => "suspended",
+            ItemStatus::Blocked => "blocked",
+        }
+    }
+}
+
+impl std::str::FromStr for ItemStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "done" => Ok(ItemStatus::Done),
+            "active" => Ok(ItemStatus::Active),
+            "pending" => Ok(ItemStatus::Pending),
+            "suspended" => Ok(ItemStatus::Suspended),
+            "blocked" => Ok(ItemStatus::Blocked),
+            _ => Err(format!("unknown status '{}' (expected done, active, pending, suspended or blocked)", s)),
+        }
+    }
+}
+
 // Step 1: Capture checkbox + full remaining text
 static ITEM_RE: Lazy<Regex> = Lazy::new(||
     Regex::new(r"^-\s+\[([ x>~!])\]\s+(.+?)\s*$").unwrap()
@@ -136,14 +198,16 @@ pub fn parse_roadmap(content: &str) -> Vec<RoadmapItem> {
     items
 }
 
-/// Validate that all dependency references point to existing item ids.
-/// Returns a list of warning strings for any broken references.
+/// Validate that ids are unique, that all dependency references point to existing
+/// item ids and that the dependencies don't form a cycle. Returns a warning string per problem.
 pub fn validate_dependencies(items: &[RoadmapItem]) -> Vec<String> {
     let known_ids: HashSet<&str> = items.iter()
         .filter_map(|i| i.id.as_deref())
         .collect();
 
-    let mut warnings = Vec::new();
+    let mut warnings: Vec<String> = duplicate_ids(items).iter()
+        .map(|id| format!("Id '{}' is used by more than one item", id))
+        .collect();
     for item in items {
         for dep in &item.depends_on {
             if !known_ids.contains(dep.as_str()) {
@@ -155,6 +219,9 @@ pub fn validate_dependencies(items: &[RoadmapItem]) -> Vec<String> {
             }
         }
     }
+    if let Some(cycle) = find_cycle(items) {
+        warnings.push(format!("Dependency cycle: {}", cycle.join(" -> ")));
+    }
     warnings
 }
 
@@ -170,7 +237,8 @@ pub fn parse_roadmap_data(content: &str) -> RoadmapData {
         (done / total * 100.0).round()
     };
     let warnings = validate_dependencies(&items);
-    RoadmapData { items, progress_percent, warnings }
+    let dependencies = analyze_dependencies(&items);
+    RoadmapData { items, progress_percent, warnings, dependencies }
 }
 
 pub fn active_item(content: &str) -> Option<RoadmapItem> {
@@ -217,6 +285,153 @@ pub fn mark_complete(content: &str, item_text: &str) -> Option<String> {
     Some(new_lines.join("\n"))
 }
 
+/// Index of the item carrying each id. A duplicated id refers to its first item.
+fn index_by_id(items: &[RoadmapItem]) -> HashMap<&str, usize> {
+    let mut by_id = HashMap::new();
+    for (idx, item) in items.iter().enumerate() {
+        if let Some(id) = item.id.as_deref() {
+            by_id.entry(id).or_insert(idx);
+        }
+    }
+    by_id
+}
+
+/// Per item, the indices of the items it depends on. Unknown ids are skipped.
+fn dependency_indices(items: &[RoadmapItem], by_id: &HashMap<&str, usize>) -> Vec<Vec<usize>> {
+    items.iter()
+        .map(|i| i.depends_on.iter().filter_map(|d| by_id.get(d.as_str()).copied()).collect())
+        .collect()
+}
+
+fn duplicate_ids(items: &[RoadmapItem]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut duplicates: Vec<String> = Vec::new();
+    for id in items.iter().filter_map(|i| i.id.as_deref()) {
+        if !seen.insert(id) && !duplicates.iter().any(|d| d == id) {
+            duplicates.push(id.to_string());
+        }
+    }
+    duplicates
+}
+
+/// Find a dependency cycle among items with ids. Returns the ids along the cycle,
+/// starting and ending with the same id, or `None` if the graph is acyclic.
+pub fn find_cycle(items: &[RoadmapItem]) -> Option<Vec<String>> {
+    cycle_in(items, &dependency_indices(items, &index_by_id(items)))
+}
+
+/// Depth-first search with an explicit stack, so long chains can't overflow it.
+fn cycle_in(items: &[RoadmapItem], deps: &[Vec<usize>]) -> Option<Vec<String>> {
+    // 0 = unvisited, 1 = on the current path, 2 = finished
+    let mut state = vec![0u8; items.len()];
+    let id_of = |idx: usize| items[idx].id.clone().unwrap_or_default();
+
+    for root in 0..items.len() {
+        if state[root] != 0 {
+            continue;
+        }
+        state[root] = 1;
+        // (item, position of the next dependency to visit)
+        let mut path: Vec<(usize, usize)> = vec![(root, 0)];
+        while let Some(top) = path.last_mut() {
+            let (idx, next) = *top;
+            match deps[idx].get(next) {
+                Some(&dep) => {
+                    top.1 += 1;
+                    match state[dep] {
+                        0 => {
+                            state[dep] = 1;
+                            path.push((dep, 0));
+                        }
+                        1 => {
+                            let start = path.iter().position(|&(i, _)| i == dep).unwrap_or(0);
+                            let mut cycle: Vec<String> = path[start..].iter().map(|&(i, _)| id_of(i)).collect();
+                            cycle.push(id_of(dep));
+                            return Some(cycle);
+                        }
+                        _ => {}
+                    }
+                }
+                None => {
+                    state[idx] = 2;
+                    path.pop();
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Topological order, readiness, depths and the critical path of the `{depends:}` graph.
+pub fn analyze_dependencies(items: &[RoadmapItem]) -> DependencyAnalysis {
+    let deps = dependency_indices(items, &index_by_id(items));
+    let done = |idx: usize| items[idx].status == ItemStatus::Done;
+
+    let waiting_on: Vec<Vec<String>> = deps.iter()
+        .map(|ds| ds.iter().filter(|&&d| !done(d)).filter_map(|&d| items[d].id.clone()).collect())
+        .collect();
+    let ready = (0..items.len())
+        .filter(|&idx| items[idx].status == ItemStatus::Pending && waiting_on[idx].is_empty())
+        .collect();
+
+    let mut analysis = DependencyAnalysis {
+        duplicate_ids: duplicate_ids(items),
+        waiting_on,
+        ready,
+        ..DependencyAnalysis::default()
+    };
+    if let Some(cycle) = cycle_in(items, &deps) {
+        analysis.cycle = Some(cycle);
+        return analysis;
+    }
+
+    // Kahn's algorithm; the smallest available index goes first to keep file order.
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); items.len()];
+    let mut pending: Vec<usize> = vec![0; items.len()];
+    for (idx, ds) in deps.iter().enumerate() {
+        for &d in ds {
+            dependents[d].push(idx);
+            pending[idx] += 1;
+        }
+    }
+    let mut available: std::collections::BTreeSet<usize> = (0..items.len()).filter(|&i| pending[i] == 0).collect();
+    while let Some(idx) = available.pop_first() {
+        analysis.order.push(idx);
+        for &next in &dependents[idx] {
+            pending[next] -= 1;
+            if pending[next] == 0 {
+                available.insert(next);
+            }
+        }
+    }
+
+    // Longest chains: `depth` counts every item, the critical path only unfinished ones.
+    analysis.depth = vec![0; items.len()];
+    let mut remaining = vec![0usize; items.len()];
+    let mut via: Vec<Option<usize>> = vec![None; items.len()];
+    for &idx in &analysis.order {
+        let mut best: Option<usize> = None;
+        for &d in &deps[idx] {
+            analysis.depth[idx] = analysis.depth[idx].max(analysis.depth[d] + 1);
+            if best.is_none_or(|b| remaining[d] > remaining[b]) {
+                best = Some(d);
+            }
+        }
+        via[idx] = best;
+        remaining[idx] = best.map_or(0, |b| remaining[b]) + usize::from(!done(idx));
+    }
+    let end = (0..items.len()).rev().max_by_key(|&i| remaining[i]).filter(|&i| remaining[i] > 0);
+    let mut cursor = end;
+    while let Some(idx) = cursor {
+        if !done(idx) {
+            analysis.critical_path.push(idx);
+        }
+        cursor = via[idx];
+    }
+    analysis.critical_path.reverse();
+    analysis
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -410,4 +625,69 @@ mod tests {
         assert!(active.is_some());
         assert_eq!(active.unwrap().text, "Train baseline model");
     }
+
+    #[test]
+    fn test_analyze_dependencies() {
+        let md = "\
+- [x] Data {id: data}
+- [ ] Eval {id: eval, depends: train}
+- [>] Train {id: train, depends: data}
+- [ ] Docs
+- [ ] Ship {id: ship, depends: eval, docs_missing}
+";
+        let data = parse_roadmap_data(md);
+        let deps = &data.dependencies;
+        assert_eq!(deps.cycle, None);
+        assert_eq!(deps.order, vec![0, 2, 1, 3, 4]);
+        assert_eq!(deps.ready, vec![3]);
+        assert_eq!(deps.waiting_on[1], vec!["train"]);
+        assert!(deps.waiting_on[2].is_empty());
+        assert_eq!(deps.depth, vec![0, 2, 1, 0, 3]);
+        // Train -> Eval -> Ship; the finished Data step doesn't count.
+        assert_eq!(deps.critical_path, vec![2, 1, 4]);
+        assert_eq!(data.warnings, vec!["Item 'ship' depends on 'docs_missing' which does not exist"]);
+    }
+
+    #[test]
+    fn test_analyze_dependencies_with_cycle() {
+        let data = parse_roadmap_data("- [ ] A {id: a, depends: b}\n- [ ] B {id: b, depends: a}\n- [ ] C");
+        assert_eq!(data.dependencies.cycle, Some(vec!["a".into(), "b".into(), "a".into()]));
+        assert!(data.dependencies.order.is_empty());
+        assert!(data.dependencies.critical_path.is_empty());
+        assert_eq!(data.dependencies.ready, vec![2]);
+        assert_eq!(data.warnings, vec!["Dependency cycle: a -> b -> a"]);
+        assert_eq!(analyze_dependencies(&[]), DependencyAnalysis::default());
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let acyclic = parse_roadmap("- [ ] A {id: a}\n- [ ] B {id: b, depends: a}\n- [ ] C {depends: b, missing}");
+        assert!(find_cycle(&acyclic).is_none());
+        let cyclic = parse_roadmap("- [ ] A {id: a, depends: c}\n- [ ] B {id: b, depends: a}\n- [ ] C {id: c, depends: b}");
+        assert_eq!(find_cycle(&cyclic).unwrap(), vec!["a", "c", "b", "a"]);
+        assert_eq!(find_cycle(&parse_roadmap("- [ ] A {id: a, depends: a}")).unwrap(), vec!["a", "a"]);
+    }
+
+    #[test]
+    fn test_duplicate_ids_resolve_to_first_item() {
+        // `b` depends on the first `a`; the second `a` (which depends on `b`) is not a
+        // graph node, so both the cycle check and the analysis see no cycle.
+        let md = "- [x] A {id: a}\n- [ ] B {id: b, depends: a}\n- [ ] A again {id: a, depends: b}";
+        let data = parse_roadmap_data(md);
+        assert_eq!(find_cycle(&data.items), None);
+        assert_eq!(data.dependencies.cycle, None);
+        assert_eq!(data.dependencies.duplicate_ids, vec!["a"]);
+        assert_eq!(data.dependencies.ready, vec![1]);
+        assert_eq!(data.warnings, vec!["Id 'a' is used by more than one item"]);
+    }
+
+    #[test]
+    fn test_find_cycle_on_long_chain() {
+        let md: String = (0..20_000)
+            .map(|i| format!("- [ ] Step {{id: s{}, depends: s{}}}\n", i, (i + 1) % 20_000))
+            .collect();
+        let cycle = find_cycle(&parse_roadmap(&md)).unwrap();
+        assert_eq!(cycle.len(), 20_001);
+        assert_eq!(cycle.first(), cycle.last());
+    }
 }
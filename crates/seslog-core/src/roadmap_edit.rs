@@ -0,0 +1,381 @@
+//! Structured, validated edits to `roadmap.md`.
+//!
+//! Edits work line by line: only the lines an edit touches change, so headings, prose,
+//! blank lines and indentation elsewhere in the file survive byte for byte. Every edit
+//! is checked before it is returned; an edit that introduces a broken dependency, a
+//! duplicate id or a dependency cycle is rejected.
+
+use std::path::Path;
+use anyhow::{bail, Context, Result};
+use crate::roadmap::{self, ItemStatus, RoadmapItem};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RoadmapEdit {
+    /// Add an item after `after` (id or text), or at the end of `phase`, or at the end of the roadmap.
+    AddItem {
+        text: String,
+        status: ItemStatus,
+        phase: Option<String>,
+        id: Option<String>,
+        depends_on: Vec<String>,
+        after: Option<String>,
+    },
+    SetStatus { item: String, status: ItemStatus },
+    Rename { item: String, text: String },
+    /// Move an item after `after`, or to the end of `phase`.
+    Move { item: String, phase: Option<String>, after: Option<String> },
+    Remove { item: String },
+    /// Append a `## name` heading.
+    AddPhase { name: String },
+    SetDependencies { item: String, depends_on: Vec<String> },
+    /// Set or clear (`None`) an item's id.
+    SetId { item: String, id: Option<String> },
+}
+
+/// Apply `edit` to roadmap `content`, returning the new content.
+pub fn apply_edit(content: &str, edit: &RoadmapEdit) -> Result<String> {
+    let mut doc = Doc::parse(content);
+    let items = roadmap::parse_roadmap(content);
+    match edit {
+        RoadmapEdit::AddItem { text, status, phase, id, depends_on, after } => {
+            check_text(text)?;
+            if let Some(id) = id {
+                check_id(id)?;
+            }
+            depends_on.iter().try_for_each(|d| check_id(d))?;
+            let at = insert_position(&doc, &items, phase.as_deref(), after.as_deref())?;
+            let indent = at.indent.clone();
+            doc.lines.insert(at.index, render_item(&indent, status, text, id.as_deref(), depends_on));
+        }
+        RoadmapEdit::SetStatus { item, status } => {
+            let target = find_item(&items, item)?;
+            let line = &mut doc.lines[target.line_number - 1];
+            let open = line.find('[').context("malformed roadmap item")?;
+            line.replace_range(open + 1..open + 2, &status.marker().to_string());
+        }
+        RoadmapEdit::Rename { item, text } => {
+            check_text(text)?;
+            let target = find_item(&items, item)?;
+            doc.rewrite(target, |t| t.text = text.clone());
+        }
+        RoadmapEdit::Move { item, phase, after } => {
+            if phase.is_none() && after.is_none() {
+                bail!("move needs a phase or an item to follow");
+            }
+            let target = find_item(&items, item)?;
+            if let Some(after) = after {
+                if std::ptr::eq(find_item(&items, after)?, target) {
+                    bail!("cannot move '{}' after itself", item);
+                }
+            }
+            let line = doc.lines.remove(target.line_number - 1);
+            let rest = roadmap::parse_roadmap(&doc.join());
+            let at = insert_position(&doc, &rest, phase.as_deref(), after.as_deref())?;
+            let body = line.trim_start();
+            doc.lines.insert(at.index, format!("{}{}", at.indent, body));
+        }
+        RoadmapEdit::Remove { item } => {
+            let target = find_item(&items, item)?;
+            doc.lines.remove(target.line_number - 1);
+        }
+        RoadmapEdit::AddPhase { name } => {
+            check_text(name)?;
+            if phase_line(&doc, name).is_some() {
+                bail!("phase '{}' already exists", name);
+            }
+            let mut at = doc.lines.len();
+            while at > 0 && doc.lines[at - 1].trim().is_empty() {
+                at -= 1;
+            }
+            let mut block = Vec::new();
+            if at > 0 {
+                block.push(String::new());
+            }
+            block.push(format!("## {}", name));
+            doc.lines.splice(at..at, block);
+        }
+        RoadmapEdit::SetDependencies { item, depends_on } => {
+            depends_on.iter().try_for_each(|d| check_id(d))?;
+            let target = find_item(&items, item)?;
+            doc.rewrite(target, |t| t.depends_on = depends_on.clone());
+        }
+        RoadmapEdit::SetId { item, id } => {
+            if let Some(id) = id {
+                check_id(id)?;
+            }
+            let target = find_item(&items, item)?;
+            doc.rewrite(target, |t| t.id = id.clone());
+        }
+    }
+
+    let updated = doc.join();
+    let before = roadmap::validate_dependencies(&items);
+    let introduced: Vec<String> = roadmap::validate_dependencies(&roadmap::parse_roadmap(&updated))
+        .into_iter()
+        .filter(|p| !before.contains(p))
+        .collect();
+    if !introduced.is_empty() {
+        bail!("roadmap edit rejected: {}", introduced.join("; "));
+    }
+    Ok(updated)
+}
+
+/// Read `path`, apply `edit` and write the result atomically. A missing file counts as
+/// an empty roadmap, so the first `AddItem` or `AddPhase` creates it.
+pub fn edit_file(path: &Path, edit: &RoadmapEdit) -> Result<String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    let updated = apply_edit(&content, edit)?;
+    crate::storage::atomic_write(path, updated.as_bytes())?;
+    Ok(updated)
+}
+
+/// Item whose id is `query`, else the single item whose text is exactly `query`.
+fn find_item<'a>(items: &'a [RoadmapItem], query: &str) -> Result<&'a RoadmapItem> {
+    if let Some(item) = items.iter().find(|i| i.id.as_deref() == Some(query)) {
+        return Ok(item);
+    }
+    let mut matches = items.iter().filter(|i| i.text == query);
+    match (matches.next(), matches.next()) {
+        (Some(item), None) => Ok(item),
+        (Some(_), Some(_)) => bail!("more than one roadmap item reads '{}'; refer to it by id", query),
+        _ => bail!("no roadmap item with id or text '{}'", query),
+    }
+}
+
+fn check_text(text: &str) -> Result<()> {
+    if text.trim().is_empty() {
+        bail!("text must not be empty");
+    }
+    if text.contains('\n') || text.contains('\r') {
+        bail!("text must be a single line");
+    }
+    // Text ending in `{id: ...}` would be read back as attributes.
+    let parsed = roadmap::parse_roadmap(&format!("- [ ] {}", text));
+    if parsed.first().map(|i| i.text.as_str()) != Some(text.trim()) {
+        bail!("text must not end with an {{id: ...}} or {{depends: ...}} block");
+    }
+    Ok(())
+}
+
+fn check_id(id: &str) -> Result<()> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        bail!("invalid id '{}': use letters, digits, '-' or '_'", id);
+    }
+    Ok(())
+}
+
+/// `- [x] text {id: a, depends: b, c}` with the attribute block only when needed.
+fn render_item(indent: &str, status: &ItemStatus, text: &str, id: Option<&str>, depends_on: &[String]) -> String {
+    let mut attrs = Vec::new();
+    if let Some(id) = id {
+        attrs.push(format!("id: {}", id));
+    }
+    if !depends_on.is_empty() {
+        attrs.push(format!("depends: {}", depends_on.join(", ")));
+    }
+    let attrs = if attrs.is_empty() { String::new() } else { format!(" {{{}}}", attrs.join(", ")) };
+    format!("{}- [{}] {}{}", indent, status.marker(), text.trim(), attrs)
+}
+
+fn indent_of(line: &str) -> String {
+    line.chars().take_while(|c| c.is_whitespace()).collect()
+}
+
+fn phase_line(doc: &Doc, name: &str) -> Option<usize> {
+    doc.lines.iter().position(|l| {
+        l.trim().strip_prefix("##").is_some_and(|rest| {
+            !rest.starts_with('#') && rest.starts_with(char::is_whitespace) && rest.trim() == name
+        })
+    })
+}
+
+struct Position {
+    index: usize,
+    indent: String,
+}
+
+/// Where a new or moved item goes: after `after`, else after the last item of `phase`
+/// (or its heading), else after the last item in the file.
+fn insert_position(doc: &Doc, items: &[RoadmapItem], phase: Option<&str>, after: Option<&str>) -> Result<Position> {
+    if let Some(after) = after {
+        let anchor = find_item(items, after)?;
+        if let Some(phase) = phase {
+            if anchor.phase.as_deref() != Some(phase) {
+                bail!("'{}' is not in phase '{}'", after, phase);
+            }
+        }
+        return Ok(Position { index: anchor.line_number, indent: indent_of(&doc.lines[anchor.line_number - 1]) });
+    }
+    if let Some(phase) = phase {
+        let heading = phase_line(doc, phase)
+            .with_context(|| format!("no phase '{}' in the roadmap (add it first)", phase))?;
+        return Ok(match items.iter().rfind(|i| i.phase.as_deref() == Some(phase)) {
+            Some(last) => Position { index: last.line_number, indent: indent_of(&doc.lines[last.line_number - 1]) },
+            None => Position { index: heading + 1, indent: String::new() },
+        });
+    }
+    Ok(match items.last() {
+        Some(last) => Position { index: last.line_number, indent: indent_of(&doc.lines[last.line_number - 1]) },
+        None => {
+            let mut end = doc.lines.len();
+            while end > 0 && doc.lines[end - 1].trim().is_empty() {
+                end -= 1;
+            }
+            Position { index: end, indent: String::new() }
+        }
+    })
+}
+
+/// The roadmap as lines, plus what is needed to join them back exactly.
+struct Doc {
+    lines: Vec<String>,
+    eol: &'static str,
+    trailing_newline: bool,
+}
+
+impl Doc {
+    fn parse(content: &str) -> Self {
+        Doc {
+            lines: content.lines().map(String::from).collect(),
+            eol: if content.contains("\r\n") { "\r\n" } else { "\n" },
+            // New files get a trailing newline like the ones Claude writes.
+            trailing_newline: content.is_empty() || content.ends_with('\n'),
+        }
+    }
+
+    fn join(&self) -> String {
+        let mut out = self.lines.join(self.eol);
+        if self.trailing_newline && !self.lines.is_empty() {
+            out.push_str(self.eol);
+        }
+        out
+    }
+
+    /// Re-render `item`'s line after `change`, keeping its indentation and status.
+    fn rewrite(&mut self, item: &RoadmapItem, change: impl FnOnce(&mut RoadmapItem)) {
+        let mut updated = item.clone();
+        change(&mut updated);
+        let idx = item.line_number - 1;
+        let indent = indent_of(&self.lines[idx]);
+        self.lines[idx] = render_item(&indent, &updated.status, &updated.text, updated.id.as_deref(), &updated.depends_on);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+# Roadmap
+
+Some notes that must survive.
+
+## Phase 1
+- [x] Collect data {id: data}
+- [>] Train model {id: train, depends: data}
+
+## Phase 2
+  - [ ] Evaluate
+";
+
+    fn add(text: &str) -> RoadmapEdit {
+        RoadmapEdit::AddItem { text: text.into(), status: ItemStatus::Pending, phase: None, id: None, depends_on: vec![], after: None }
+    }
+
+    #[test]
+    fn test_set_status_touches_one_line() {
+        let edit = RoadmapEdit::SetStatus { item: "train".into(), status: ItemStatus::Done };
+        let updated = apply_edit(SAMPLE, &edit).unwrap();
+        assert_eq!(updated, SAMPLE.replace("- [>] Train model", "- [x] Train model"));
+        let edit = RoadmapEdit::SetStatus { item: "Evaluate".into(), status: ItemStatus::Blocked };
+        assert!(apply_edit(&updated, &edit).unwrap().contains("\n  - [!] Evaluate\n"));
+        let missing = RoadmapEdit::SetStatus { item: "Deploy".into(), status: ItemStatus::Done };
+        assert!(apply_edit(SAMPLE, &missing).unwrap_err().to_string().contains("no roadmap item"));
+    }
+
+    #[test]
+    fn test_add_item_positions() {
+        let mut edit = add("Tune");
+        if let RoadmapEdit::AddItem { phase, id, depends_on, .. } = &mut edit {
+            *phase = Some("Phase 1".into());
+            *id = Some("tune".into());
+            *depends_on = vec!["train".into()];
+        }
+        let updated = apply_edit(SAMPLE, &edit).unwrap();
+        assert!(updated.contains("{id: train, depends: data}\n- [ ] Tune {id: tune, depends: train}\n\n## Phase 2"));
+
+        // Without a phase the item follows the last one, with its indentation.
+        assert!(apply_edit(SAMPLE, &add("Ship")).unwrap().ends_with("  - [ ] Evaluate\n  - [ ] Ship\n"));
+
+        let mut after = add("Clean data");
+        if let RoadmapEdit::AddItem { after: a, .. } = &mut after {
+            *a = Some("data".into());
+        }
+        assert!(apply_edit(SAMPLE, &after).unwrap().contains("{id: data}\n- [ ] Clean data\n- [>] Train"));
+
+        assert_eq!(apply_edit("", &add("First")).unwrap(), "- [ ] First\n");
+    }
+
+    #[test]
+    fn test_rename_move_remove_and_phase() {
+        let renamed = apply_edit(SAMPLE, &RoadmapEdit::Rename { item: "train".into(), text: "Train baseline".into() }).unwrap();
+        assert!(renamed.contains("- [>] Train baseline {id: train, depends: data}"));
+
+        let moved = apply_edit(SAMPLE, &RoadmapEdit::Move { item: "Evaluate".into(), phase: None, after: Some("data".into()) }).unwrap();
+        assert!(moved.contains("{id: data}\n- [ ] Evaluate\n- [>] Train"));
+        assert!(moved.ends_with("## Phase 2\n"));
+
+        let with_phase = apply_edit(SAMPLE, &RoadmapEdit::AddPhase { name: "Phase 3".into() }).unwrap();
+        assert!(with_phase.ends_with("  - [ ] Evaluate\n\n## Phase 3\n"));
+        assert!(apply_edit(&with_phase, &RoadmapEdit::AddPhase { name: "Phase 3".into() }).is_err());
+        let mut into_phase = add("Deploy");
+        if let RoadmapEdit::AddItem { phase, .. } = &mut into_phase {
+            *phase = Some("Phase 3".into());
+        }
+        assert!(apply_edit(&with_phase, &into_phase).unwrap().ends_with("## Phase 3\n- [ ] Deploy\n"));
+
+        let removed = apply_edit(SAMPLE, &RoadmapEdit::Remove { item: "Evaluate".into() }).unwrap();
+        assert!(removed.ends_with("## Phase 2\n"));
+    }
+
+    #[test]
+    fn test_validation_rejects_broken_graph() {
+        // Removing an item others depend on leaves a dangling reference.
+        let err = apply_edit(SAMPLE, &RoadmapEdit::Remove { item: "data".into() }).unwrap_err();
+        assert!(err.to_string().contains("depends on 'data' which does not exist"));
+
+        let cycle = RoadmapEdit::SetDependencies { item: "data".into(), depends_on: vec!["train".into()] };
+        assert!(apply_edit(SAMPLE, &cycle).unwrap_err().to_string().contains("Dependency cycle"));
+
+        let dup = RoadmapEdit::SetId { item: "Evaluate".into(), id: Some("data".into()) };
+        assert!(apply_edit(SAMPLE, &dup).unwrap_err().to_string().contains("more than one item"));
+        assert!(apply_edit(SAMPLE, &RoadmapEdit::SetId { item: "Evaluate".into(), id: Some("a b".into()) }).is_err());
+        assert!(apply_edit(SAMPLE, &add("two\nlines")).is_err());
+        assert!(apply_edit(SAMPLE, &add("Sneaky {id: data}")).is_err());
+
+        // Problems already in the file don't block unrelated edits.
+        let broken = "- [ ] A {depends: missing}\n- [ ] B\n";
+        let edit = RoadmapEdit::SetStatus { item: "B".into(), status: ItemStatus::Done };
+        assert_eq!(apply_edit(broken, &edit).unwrap(), "- [ ] A {depends: missing}\n- [x] B\n");
+    }
+
+    #[test]
+    fn test_crlf_and_missing_newline_round_trip() {
+        let content = "## P\r\n- [ ] A {id: a}\r\n- [ ] B";
+        let edit = RoadmapEdit::SetDependencies { item: "B".into(), depends_on: vec!["a".into()] };
+        assert_eq!(apply_edit(content, &edit).unwrap(), "## P\r\n- [ ] A {id: a}\r\n- [ ] B {depends: a}");
+    }
+
+    #[test]
+    fn test_edit_file_creates_roadmap() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("roadmap.md");
+        edit_file(&path, &RoadmapEdit::AddPhase { name: "Phase 1".into() }).unwrap();
+        edit_file(&path, &add("Start")).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "## Phase 1\n- [ ] Start\n");
+    }
+}
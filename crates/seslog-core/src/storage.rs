@@ -24,15 +24,19 @@ pub fn safe_read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Opt
     safe_read_json_with_quarantine(path, &quarantine_dir)
 }
 
+/// Like [`safe_read_json`], moving unparseable files to `quarantine_dir`.
 pub fn safe_read_json_with_quarantine<T: serde::de::DeserializeOwned>(
     path: &Path,
     quarantine_dir: &Path,
 ) -> Result<Option<T>> {
-    let content = match fs::read_to_string(path) {
-        Ok(c) => c,
+    let bytes = match fs::read(path) {
+        Ok(b) => b,
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
         Err(e) => return Err(e.into()),
     };
+    // Encrypted records are decrypted transparently. A missing key is an error, never
+    // a reason to quarantine: the file is fine, this machine just can't read it.
+    let content = crate::encryption::decode_record(path, bytes)?;
     match serde_json::from_str::<T>(&content) {
         Ok(v) => Ok(Some(v)),
         Err(e) => {
@@ -87,6 +91,26 @@ fn has_active_queue(data_dir: &Path) -> bool {
     })
 }
 
+/// Append any of `entries` missing from `<data_dir>/.gitignore`, one per line.
+pub fn ensure_gitignored(data_dir: &Path, entries: &[&str]) -> Result<()> {
+    let path = data_dir.join(".gitignore");
+    let mut content = fs::read_to_string(&path).unwrap_or_default();
+    let missing: Vec<&str> = entries.iter().copied()
+        .filter(|entry| !content.lines().any(|l| l.trim() == *entry))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    for entry in missing {
+        content.push_str(entry);
+        content.push('\n');
+    }
+    atomic_write(&path, content.as_bytes())
+}
+
 pub fn init_data_dir() -> Result<PathBuf> {
     let base = seslog_dir()?;
     init_data_dir_at(&base)?;
@@ -164,6 +188,19 @@ mod tests {
         assert_eq!(entries.len(), 1);
     }
 
+    #[test]
+    fn test_safe_read_json_encrypted_without_key_is_not_quarantined() {
+        let tmp = TempDir::new().unwrap();
+        let quarantine = tmp.path().join("quarantine");
+        let path = tmp.path().join("projects/api/sessions/ses_1.json");
+        let keyring = crate::encryption::Keyring::new(vec![age::x25519::Identity::generate()], vec![]);
+        atomic_write(&path, &keyring.encrypt(br#"{"id":"ses_1"}"#).unwrap()).unwrap();
+
+        assert!(safe_read_json_with_quarantine::<serde_json::Value>(&path, &quarantine).is_err());
+        assert!(path.exists());
+        assert!(!quarantine.exists());
+    }
+
     #[test]
     fn test_init_data_dir_creates_subdirs() {
         let tmp = TempDir::new().unwrap();
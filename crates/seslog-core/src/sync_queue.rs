@@ -0,0 +1,92 @@
+use std::path::Path;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use crate::storage;
+
+/// Machine-local record of a push that failed, relative to the data directory.
+pub const PENDING_PUSH_FILE: &str = ".sync/pending_push.json";
+
+const BASE_DELAY_SECS: i64 = 30;
+const MAX_DELAY_SECS: i64 = 60 * 60;
+
+/// A commit that is waiting to be pushed, with exponential backoff between attempts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingPush {
+    pub attempts: u32,
+    pub first_failed_at: DateTime<Utc>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: String,
+}
+
+impl PendingPush {
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        now >= self.next_attempt_at
+    }
+}
+
+/// Delay after the `attempts`-th consecutive failure: 30s, 1m, 2m, … capped at one hour.
+pub fn backoff(attempts: u32) -> Duration {
+    let exp = attempts.saturating_sub(1).min(16);
+    Duration::seconds((BASE_DELAY_SECS << exp).min(MAX_DELAY_SECS))
+}
+
+pub fn load_pending(data_dir: &Path) -> Option<PendingPush> {
+    let content = std::fs::read_to_string(data_dir.join(PENDING_PUSH_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Record a failed push and schedule the next attempt.
+pub fn record_failure(data_dir: &Path, error: &str, now: DateTime<Utc>) -> Result<PendingPush> {
+    let previous = load_pending(data_dir);
+    let attempts = previous.as_ref().map_or(0, |p| p.attempts) + 1;
+    let pending = PendingPush {
+        attempts,
+        first_failed_at: previous.map_or(now, |p| p.first_failed_at),
+        next_attempt_at: now + backoff(attempts),
+        last_error: error.trim().chars().take(500).collect(),
+    };
+    storage::ensure_gitignored(data_dir, &[".sync/"])?;
+    storage::write_json(&data_dir.join(PENDING_PUSH_FILE), &pending)?;
+    Ok(pending)
+}
+
+pub fn clear(data_dir: &Path) -> Result<()> {
+    match std::fs::remove_file(data_dir.join(PENDING_PUSH_FILE)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        assert_eq!(backoff(1), Duration::seconds(30));
+        assert_eq!(backoff(2), Duration::seconds(60));
+        assert_eq!(backoff(4), Duration::seconds(240));
+        assert_eq!(backoff(40), Duration::seconds(3600));
+    }
+
+    #[test]
+    fn test_failures_accumulate_until_cleared() {
+        let tmp = TempDir::new().unwrap();
+        let t0: DateTime<Utc> = "2026-03-01T10:00:00Z".parse().unwrap();
+        assert!(load_pending(tmp.path()).is_none());
+
+        record_failure(tmp.path(), "Could not resolve host", t0).unwrap();
+        let second = record_failure(tmp.path(), "Could not resolve host", t0 + Duration::seconds(40)).unwrap();
+        assert_eq!(second.attempts, 2);
+        assert_eq!(second.first_failed_at, t0);
+        assert!(!second.is_due(t0 + Duration::seconds(60)));
+        assert!(second.is_due(t0 + Duration::seconds(100)));
+        assert!(std::fs::read_to_string(tmp.path().join(".gitignore")).unwrap().contains(".sync/"));
+
+        clear(tmp.path()).unwrap();
+        clear(tmp.path()).unwrap();
+        assert!(load_pending(tmp.path()).is_none());
+    }
+}
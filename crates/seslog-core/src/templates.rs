@@ -0,0 +1,275 @@
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use crate::context::{ContextComposer, ContextInput, ContextSection};
+use crate::models::Session;
+use crate::roadmap::{self, ItemStatus};
+
+/// Template for the `CLAUDE.md` status block.
+pub const CLAUDE_MD_BLOCK: &str = "claude_md.md";
+/// Template for the `additionalContext` returned at SessionStart.
+pub const ADDITIONAL_CONTEXT: &str = "additional_context.md";
+pub const TEMPLATE_NAMES: [&str; 2] = [CLAUDE_MD_BLOCK, ADDITIONAL_CONTEXT];
+
+const DEFAULT_CLAUDE_MD_BLOCK: &str = "\
+## Project Status (auto-updated by Seslog)
+
+{% if last_session_at %}
+**Last Session:** {{ last_session_at }}
+{% endif %}
+{% if summary %}
+**Summary:** {{ summary }}
+{% endif %}
+{% if active_step %}
+**Active Step:** {{ active_step }}
+{% endif %}
+{% if upcoming %}
+
+### Upcoming
+{% for item in upcoming %}
+- {{ item.marker }} {{ item.text }}
+{% endfor %}
+{% endif %}
+";
+
+const DEFAULT_ADDITIONAL_CONTEXT: &str = "{{ context }}\n";
+
+/// The built-in source of a template, used when no file overrides it.
+pub fn builtin(name: &str) -> Option<&'static str> {
+    match name {
+        CLAUDE_MD_BLOCK => Some(DEFAULT_CLAUDE_MD_BLOCK),
+        ADDITIONAL_CONTEXT => Some(DEFAULT_ADDITIONAL_CONTEXT),
+        _ => None,
+    }
+}
+
+/// Where a template was loaded from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateOrigin {
+    Project(PathBuf),
+    Global(PathBuf),
+    Builtin,
+}
+
+/// Resolve `name`: `projects/<slug>/templates/`, then `templates/` in the data dir,
+/// then the built-in default.
+pub fn load_template(data_dir: &Path, slug: Option<&str>, name: &str) -> Result<(String, TemplateOrigin)> {
+    let builtin = builtin(name).with_context(|| format!("unknown template '{}'", name))?;
+    if let Some(slug) = slug {
+        let path = data_dir.join("projects").join(slug).join("templates").join(name);
+        if path.is_file() {
+            return Ok((std::fs::read_to_string(&path)?, TemplateOrigin::Project(path)));
+        }
+    }
+    let path = data_dir.join("templates").join(name);
+    if path.is_file() {
+        return Ok((std::fs::read_to_string(&path)?, TemplateOrigin::Global(path)));
+    }
+    Ok((builtin.to_string(), TemplateOrigin::Builtin))
+}
+
+/// Render a template source. Block tags swallow their own line, so templates can put
+/// `{% if %}` on lines of its own without leaving blank lines behind.
+pub fn render(source: &str, data: impl Serialize) -> Result<String> {
+    let mut env = minijinja::Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    let rendered = env.render_str(source, data)?;
+    Ok(rendered.trim_end().to_string())
+}
+
+/// Render the resolved template for `name`, falling back to the built-in when a
+/// user template fails to render so a typo never blanks the output.
+pub fn render_named(data_dir: &Path, slug: Option<&str>, name: &str, data: impl Serialize) -> Result<String> {
+    let (source, origin) = load_template(data_dir, slug, name)?;
+    match render(&source, &data) {
+        Ok(out) => Ok(out),
+        Err(e) if origin != TemplateOrigin::Builtin => {
+            eprintln!("[seslog] WARN: template {:?} failed, using built-in: {:#}", origin, e);
+            render(builtin(name).unwrap_or_default(), &data)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Write the built-in templates into `dir` for editing, skipping files that exist
+/// unless `force`. Returns the paths written.
+pub fn write_defaults(dir: &Path, force: bool) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)?;
+    let mut written = Vec::new();
+    for name in TEMPLATE_NAMES {
+        let path = dir.join(name);
+        if path.exists() && !force {
+            continue;
+        }
+        crate::storage::atomic_write(&path, builtin(name).unwrap_or_default().as_bytes())?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Variables available to the `CLAUDE.md` block template.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StatusData {
+    pub slug: String,
+    /// `%Y-%m-%d %H:%M` (UTC) of when the last session ended.
+    pub last_session_at: Option<String>,
+    pub summary: Option<String>,
+    pub next_steps: Option<String>,
+    pub active_step: Option<String>,
+    /// Roadmap completion, 0-100; absent without a roadmap.
+    pub progress: Option<u32>,
+    /// Up to five active and pending roadmap items.
+    pub upcoming: Vec<UpcomingItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpcomingItem {
+    /// `[>]` or `[ ]`.
+    pub marker: String,
+    pub text: String,
+}
+
+impl StatusData {
+    pub fn new(slug: &str, last_session: Option<&Session>, roadmap_content: &str) -> Self {
+        let non_empty = |s: &str| (!s.trim().is_empty()).then(|| s.chars().take(300).collect::<String>());
+        let upcoming = roadmap::parse_roadmap(roadmap_content).into_iter()
+            .filter(|i| matches!(i.status, ItemStatus::Active | ItemStatus::Pending))
+            .take(5)
+            .map(|i| UpcomingItem {
+                marker: if i.status == ItemStatus::Active { "[>]" } else { "[ ]" }.into(),
+                text: i.text,
+            })
+            .collect();
+        Self {
+            slug: slug.to_string(),
+            last_session_at: last_session
+                .map(|s| s.ended_at.unwrap_or(s.started_at).format("%Y-%m-%d %H:%M").to_string()),
+            summary: last_session.and_then(|s| non_empty(&s.summary)),
+            next_steps: last_session.and_then(|s| non_empty(&s.next_steps)),
+            active_step: roadmap::active_item(roadmap_content).map(|i| i.text),
+            progress: (!roadmap_content.trim().is_empty())
+                .then(|| roadmap::progress_percent(roadmap_content) as u32),
+            upcoming,
+        }
+    }
+}
+
+/// Variables available to the additional-context template.
+#[derive(Debug, Clone, Serialize)]
+pub struct AdditionalContextData {
+    pub slug: String,
+    /// The composed context, already ranked and cut to `additional_context_max_chars`.
+    pub context: String,
+    /// All sections, highest priority first, for templates that lay them out themselves.
+    pub sections: Vec<ContextSection>,
+}
+
+impl AdditionalContextData {
+    pub fn new(composer: &ContextComposer, input: &ContextInput) -> Self {
+        Self {
+            slug: input.slug.clone(),
+            context: composer.compose(input),
+            sections: composer.sections(input),
+        }
+    }
+}
+
+/// Render the additional context, cutting custom layouts back to the composer's budget.
+pub fn render_additional_context(data_dir: &Path, composer: &ContextComposer, input: &ContextInput) -> Result<String> {
+    let data = AdditionalContextData::new(composer, input);
+    let out = render_named(data_dir, Some(&input.slug), ADDITIONAL_CONTEXT, &data)?;
+    let max = composer.max_chars();
+    if out.chars().count() <= max {
+        return Ok(out);
+    }
+    Ok(out.chars().take(max.saturating_sub(3)).collect::<String>() + "...")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn session() -> Session {
+        serde_json::from_value(serde_json::json!({
+            "schema_version": 1, "id": "ses_1", "project_id": "p", "machine": "mac",
+            "started_at": "2026-01-01T10:00:00Z", "ended_at": "2026-01-01T11:30:00Z",
+            "summary": "Fixed auth bug", "next_steps": "Add retry tests",
+        })).unwrap()
+    }
+
+    const ROADMAP: &str = "## Phase 1\n- [x] Setup\n- [>] Feature engineering\n- [ ] Deploy\n";
+
+    #[test]
+    fn test_default_claude_md_block() {
+        let data = StatusData::new("api", Some(&session()), ROADMAP);
+        let out = render(builtin(CLAUDE_MD_BLOCK).unwrap(), &data).unwrap();
+        assert_eq!(out, "## Project Status (auto-updated by Seslog)\n\n\
+            **Last Session:** 2026-01-01 11:30\n\
+            **Summary:** Fixed auth bug\n\
+            **Active Step:** Feature engineering\n\n\
+            ### Upcoming\n\
+            - [>] Feature engineering\n\
+            - [ ] Deploy");
+
+        let empty = render(builtin(CLAUDE_MD_BLOCK).unwrap(), StatusData::new("api", None, "")).unwrap();
+        assert_eq!(empty, "## Project Status (auto-updated by Seslog)");
+    }
+
+    #[test]
+    fn test_project_template_overrides_global() {
+        let tmp = TempDir::new().unwrap();
+        let (_, origin) = load_template(tmp.path(), Some("api"), CLAUDE_MD_BLOCK).unwrap();
+        assert_eq!(origin, TemplateOrigin::Builtin);
+
+        std::fs::create_dir_all(tmp.path().join("templates")).unwrap();
+        std::fs::write(tmp.path().join("templates").join(CLAUDE_MD_BLOCK), "global {{ slug }}").unwrap();
+        let data = StatusData::new("api", None, "");
+        assert_eq!(render_named(tmp.path(), Some("api"), CLAUDE_MD_BLOCK, &data).unwrap(), "global api");
+
+        let project_templates = tmp.path().join("projects/api/templates");
+        std::fs::create_dir_all(&project_templates).unwrap();
+        std::fs::write(project_templates.join(CLAUDE_MD_BLOCK), "project {{ progress or 'n/a' }}").unwrap();
+        assert_eq!(render_named(tmp.path(), Some("api"), CLAUDE_MD_BLOCK, &data).unwrap(), "project n/a");
+        assert_eq!(render_named(tmp.path(), Some("web"), CLAUDE_MD_BLOCK, &data).unwrap(), "global api");
+    }
+
+    #[test]
+    fn test_broken_template_falls_back_to_builtin() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("templates")).unwrap();
+        std::fs::write(tmp.path().join("templates").join(CLAUDE_MD_BLOCK), "{% if summary %}unclosed").unwrap();
+        let out = render_named(tmp.path(), None, CLAUDE_MD_BLOCK, StatusData::new("api", Some(&session()), "")).unwrap();
+        assert!(out.contains("**Summary:** Fixed auth bug"));
+        assert!(load_template(tmp.path(), None, "nope.md").is_err());
+    }
+
+    #[test]
+    fn test_additional_context_template_is_budgeted() {
+        let tmp = TempDir::new().unwrap();
+        let input = ContextInput { slug: "api".into(), sessions: vec![session()], ..ContextInput::default() };
+        let composer = ContextComposer::with_defaults(400, &Default::default());
+        let out = render_additional_context(tmp.path(), &composer, &input).unwrap();
+        assert_eq!(out, composer.compose(&input));
+
+        std::fs::create_dir_all(tmp.path().join("templates")).unwrap();
+        std::fs::write(
+            tmp.path().join("templates").join(ADDITIONAL_CONTEXT),
+            "{% for s in sections %}{{ s.title }}|{% endfor %}{% for _ in range(500) %}x{% endfor %}",
+        ).unwrap();
+        let out = render_additional_context(tmp.path(), &composer, &input).unwrap();
+        assert!(out.starts_with("Last session||Pending next steps|xxx"));
+        assert_eq!(out.chars().count(), 400);
+    }
+
+    #[test]
+    fn test_write_defaults_keeps_existing() {
+        let tmp = TempDir::new().unwrap();
+        assert_eq!(write_defaults(tmp.path(), false).unwrap().len(), 2);
+        std::fs::write(tmp.path().join(CLAUDE_MD_BLOCK), "mine").unwrap();
+        assert!(write_defaults(tmp.path(), false).unwrap().is_empty());
+        assert_eq!(std::fs::read_to_string(tmp.path().join(CLAUDE_MD_BLOCK)).unwrap(), "mine");
+        assert_eq!(write_defaults(tmp.path(), true).unwrap().len(), 2);
+    }
+}
@@ -1,14 +1,46 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use crate::models::{Subagent, TokenUsage, ToolStats, TouchedFile, TurnUsage};
 
+/// Everything gathered from a transcript. Covers the whole file; the storage caps are
+/// applied separately by [`cap_for_storage`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TranscriptHighlights {
     pub user_messages: Vec<String>,
     pub assistant_summaries: Vec<String>,
     pub tools_used: Vec<String>,
-    pub total_input_tokens: u64,
-    pub total_output_tokens: u64,
+    /// Calls and errors per tool in the main conversation, in order of first use.
+    pub tool_stats: Vec<ToolStats>,
+    /// Files named by Read, Edit and Write calls, subagents' included, with paths as
+    /// given in the call.
+    pub touched_files: Vec<TouchedFile>,
+    pub usage: TokenUsage,
+    /// Usage of each assistant turn, subagents' included; `usage` is their sum.
+    pub turns: Vec<TurnUsage>,
+    /// The first model seen.
     pub model: Option<String>,
+    /// Sidechains, kept apart from the main conversation's messages and tool calls.
+    pub subagents: Vec<Subagent>,
+}
+
+/// How many assistant summaries are kept per subagent; its last messages report back.
+const SUBAGENT_SUMMARIES: usize = 3;
+
+/// How many usage turns a synced session record keeps individually; see [`cap_usage_turns`].
+pub const STORED_USAGE_TURNS: usize = 200;
+
+/// A Task call from the main conversation, matched to the sidechain it starts by prompt.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskCall {
+    pub prompt: String,
+    pub description: String,
+    pub agent_type: Option<String>,
 }
 
 /// Structured summary built from transcript highlights.
@@ -19,6 +51,8 @@ pub struct TranscriptSummary {
     pub next_steps: String,
     /// The first real user request in the session.
     pub first_request: String,
+    /// "We decided to…" statements found in the conversation.
+    pub decisions: Vec<String>,
 }
 
 /// Build a structured summary from transcript highlights.
@@ -26,6 +60,7 @@ pub struct TranscriptSummary {
 /// - `what_was_done`: synthesized from the last few assistant summaries
 /// - `next_steps`: extracted from messages containing keywords like "next", "TODO", "remaining"
 /// - `first_request`: the first user message
+/// - `decisions`: "we decided to…" statements from user messages and assistant summaries
 pub fn build_summary(highlights: &TranscriptHighlights) -> TranscriptSummary {
     // --- first_request ---
     let first_request = highlights
@@ -59,10 +94,21 @@ pub fn build_summary(highlights: &TranscriptHighlights) -> TranscriptSummary {
     // Scan all messages (user + assistant) for lines containing actionable keywords.
     let next_steps = extract_next_steps(highlights);
 
+    // --- decisions ---
+    let mut decisions: Vec<String> = Vec::new();
+    for msg in highlights.user_messages.iter().chain(&highlights.assistant_summaries) {
+        for decision in crate::decisions::extract_decisions(msg) {
+            if !decisions.iter().any(|d| d.to_lowercase() == decision.to_lowercase()) {
+                decisions.push(decision);
+            }
+        }
+    }
+
     TranscriptSummary {
         what_was_done,
         next_steps,
         first_request,
+        decisions,
     }
 }
 
@@ -107,7 +153,7 @@ fn extract_next_steps(highlights: &TranscriptHighlights) -> String {
 }
 
 pub trait TranscriptSource {
-    fn extract_highlights(&self, max_messages: usize, max_bytes: usize) -> Result<TranscriptHighlights>;
+    fn extract_highlights(&self) -> Result<TranscriptHighlights>;
 }
 
 pub struct JsonlTranscriptSource<'a> {
@@ -118,35 +164,43 @@ pub struct GitDiffFallback<'a> {
     pub cwd: &'a Path,
 }
 
-/// Smart selector: try JSONL first, fallback to git diff
-pub fn extract_highlights(transcript_path: &Path, cwd: &Path, max_messages: usize, max_bytes: usize) -> TranscriptHighlights {
-    let jsonl = JsonlTranscriptSource { path: transcript_path };
-    match jsonl.extract_highlights(max_messages, max_bytes) {
-        Ok(h) if !h.user_messages.is_empty() || !h.tools_used.is_empty() || !h.assistant_summaries.is_empty() => return h,
-        Ok(_) | Err(_) => {
-            eprintln!("[seslog] WARN: transcript parse failed, falling back to git diff");
-        }
+impl TranscriptHighlights {
+    fn is_empty(&self) -> bool {
+        self.user_messages.is_empty()
+            && self.tools_used.is_empty()
+            && self.assistant_summaries.is_empty()
+            && self.subagents.is_empty()
+    }
+}
+
+/// Smart selector: use the parsed JSONL highlights, fallback to git diff when they are empty
+pub fn highlights_or_fallback(highlights: TranscriptHighlights, cwd: &Path) -> TranscriptHighlights {
+    if !highlights.is_empty() {
+        return highlights;
     }
+    eprintln!("[seslog] WARN: transcript parse failed, falling back to git diff");
     let fallback = GitDiffFallback { cwd };
-    fallback.extract_highlights(max_messages, max_bytes)
+    fallback.extract_highlights()
         .unwrap_or_else(|_| TranscriptHighlights {
-            user_messages: vec![],
             assistant_summaries: vec!["(transcript unavailable)".into()],
-            tools_used: vec![],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
-            model: None,
+            ..TranscriptHighlights::default()
         })
 }
 
+/// Smart selector: try JSONL first, fallback to git diff
+pub fn extract_highlights(transcript_path: &Path, cwd: &Path) -> TranscriptHighlights {
+    let jsonl = JsonlTranscriptSource { path: transcript_path };
+    highlights_or_fallback(jsonl.extract_highlights().unwrap_or_default(), cwd)
+}
+
 impl<'a> TranscriptSource for JsonlTranscriptSource<'a> {
-    fn extract_highlights(&self, max_messages: usize, max_bytes: usize) -> Result<TranscriptHighlights> {
-        parse_jsonl(self.path, max_messages, max_bytes)
+    fn extract_highlights(&self) -> Result<TranscriptHighlights> {
+        parse_jsonl(self.path)
     }
 }
 
 impl<'a> TranscriptSource for GitDiffFallback<'a> {
-    fn extract_highlights(&self, _max_messages: usize, _max_bytes: usize) -> Result<TranscriptHighlights> {
+    fn extract_highlights(&self) -> Result<TranscriptHighlights> {
         let commits = crate::git_ops::recent_commits(self.cwd, 5).unwrap_or_default();
         let diff = crate::git_ops::diff_stat(self.cwd).unwrap_or(None);
         let mut summaries = Vec::new();
@@ -160,153 +214,556 @@ impl<'a> TranscriptSource for GitDiffFallback<'a> {
             summaries.push("(no git activity found)".into());
         }
         Ok(TranscriptHighlights {
-            user_messages: vec![],
             assistant_summaries: summaries,
-            tools_used: vec![],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
-            model: None,
+            ..TranscriptHighlights::default()
         })
     }
 }
 
-fn parse_jsonl(path: &Path, max_messages: usize, max_bytes: usize) -> Result<TranscriptHighlights> {
-    let file = std::fs::File::open(path)?;
-    let file_size = file.metadata()?.len();
-    let reader = if file_size > max_bytes as u64 {
-        let mut f = file;
-        f.seek(SeekFrom::End(-(max_bytes as i64)))?;
-        let mut reader = BufReader::new(f);
-        let mut _discard = String::new();
-        reader.read_line(&mut _discard)?;
-        reader
-    } else {
-        BufReader::new(file)
-    };
-
-    let mut highlights = TranscriptHighlights {
-        user_messages: Vec::new(),
-        assistant_summaries: Vec::new(),
-        tools_used: Vec::new(),
-        total_input_tokens: 0,
-        total_output_tokens: 0,
-        model: None,
-    };
-    let mut message_count = 0;
-    for line in reader.lines() {
-        let line = line?;
-        if line.trim().is_empty() { continue; }
-        let entry = match serde_json::from_str::<serde_json::Value>(&line) {
-            Ok(e) => e,
-            Err(_) => continue,
-        };
+/// What has been parsed from one session's transcript so far. `offset` is the byte
+/// position parsing stopped at (always the start of a line), so the next pass only
+/// reads what Claude Code appended since.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscriptState {
+    pub offset: u64,
+    pub highlights: TranscriptHighlights,
+    /// `timestamp` of every user/assistant entry, in file order.
+    pub timestamps: Vec<DateTime<Utc>>,
+    /// `message.id` of the last assistant turn. A response with several content blocks is
+    /// written as one entry per block, each repeating the response's usage.
+    pub last_message_id: Option<String>,
+    /// Tool name of each `tool_use` id whose `tool_result` has not been seen yet.
+    pub pending_tools: BTreeMap<String, String>,
+    /// Task calls whose sidechain has not started yet.
+    pub pending_tasks: Vec<TaskCall>,
+    /// Subagent id of each sidechain entry's `uuid`, for transcripts without `agentId`.
+    pub sidechain_entries: BTreeMap<String, String>,
+    /// `last_message_id` of each subagent.
+    pub subagent_message_ids: BTreeMap<String, String>,
+}
 
-        let top_type = entry.get("type").and_then(|t| t.as_str()).unwrap_or("");
+/// Parse the whole transcript from the start.
+fn parse_jsonl(path: &Path) -> Result<TranscriptHighlights> {
+    let mut state = TranscriptState::default();
+    parse_transcript(path, &mut state)?;
+    Ok(state.highlights)
+}
 
-        // Skip non-conversation entries (progress, system, file snapshots, queue ops)
-        match top_type {
-            "progress" | "system" | "file-history-snapshot" | "queue-operation" => continue,
-            _ => {}
+/// Advance `state` over everything appended to the transcript since `state.offset`.
+///
+/// A transcript shorter than the offset was replaced, so it is parsed again from the
+/// start. A final line without a newline that doesn't parse is treated as still being
+/// written and left for the next pass.
+pub fn parse_transcript(path: &Path, state: &mut TranscriptState) -> Result<()> {
+    let mut file = std::fs::File::open(path)?;
+    if file.metadata()?.len() < state.offset {
+        *state = TranscriptState::default();
+    }
+    file.seek(SeekFrom::Start(state.offset))?;
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 {
+            break;
         }
-
-        // Skip entries with isMeta: true
-        if entry.get("isMeta").and_then(|v| v.as_bool()).unwrap_or(false) {
-            continue;
+        let entry = serde_json::from_str::<serde_json::Value>(line.trim()).ok();
+        if !line.ends_with('\n') && entry.is_none() {
+            break;
+        }
+        state.offset += read as u64;
+        if let Some(entry) = entry {
+            apply_entry(state, &entry);
         }
+    }
+    Ok(())
+}
 
-        // Try real Claude Code format first: top-level "type" is "user"/"assistant",
-        // actual content is inside "message" object with "role" and "content" fields.
-        if let Some(message_obj) = entry.get("message").and_then(|m| m.as_object()) {
-            let role = message_obj.get("role").and_then(|r| r.as_str()).unwrap_or("");
-            let content = message_obj.get("content");
-
-            match role {
-                "user" => {
-                    // String content = real user message; array = tool_results (skip)
-                    if let Some(content_val) = content {
-                        if let Some(text) = content_val.as_str() {
-                            if !is_command_content(text) {
-                                highlights.user_messages.push(text.chars().take(200).collect());
-                            }
+/// Fold one JSONL entry into the running state.
+fn apply_entry(state: &mut TranscriptState, entry: &serde_json::Value) {
+    let top_type = entry.get("type").and_then(|t| t.as_str()).unwrap_or("");
+
+    let timestamp = entry.get("timestamp")
+        .and_then(|t| t.as_str())
+        .and_then(|t| t.parse::<DateTime<Utc>>().ok())
+        .filter(|_| matches!(top_type, "user" | "assistant"));
+    if let Some(ts) = timestamp {
+        state.timestamps.push(ts);
+    }
+
+    // Skip non-conversation entries (progress, system, file snapshots, queue ops)
+    match top_type {
+        "progress" | "system" | "file-history-snapshot" | "queue-operation" => return,
+        _ => {}
+    }
+
+    // Skip entries with isMeta: true
+    if entry.get("isMeta").and_then(|v| v.as_bool()).unwrap_or(false) {
+        return;
+    }
+
+    // Subagent (Task tool) activity is written inline, flagged as a sidechain.
+    if entry.get("isSidechain").and_then(|v| v.as_bool()).unwrap_or(false) {
+        apply_sidechain_entry(state, entry, timestamp);
+        return;
+    }
+
+    // Try real Claude Code format first: top-level "type" is "user"/"assistant",
+    // actual content is inside "message" object with "role" and "content" fields.
+    if let Some(message_obj) = entry.get("message").and_then(|m| m.as_object()) {
+        let role = message_obj.get("role").and_then(|r| r.as_str()).unwrap_or("");
+        let content = message_obj.get("content");
+
+        match role {
+            "user" => {
+                let highlights = &mut state.highlights;
+                // String content = real user message; array = tool_results
+                if let Some(text) = content.and_then(|c| c.as_str()) {
+                    if !is_command_content(text) {
+                        highlights.user_messages.push(text.chars().take(200).collect());
+                    }
+                } else if let Some(arr) = content.and_then(|c| c.as_array()) {
+                    for item in arr {
+                        if item.get("type").and_then(|t| t.as_str()) == Some("tool_result") {
+                            record_tool_result(&mut highlights.tool_stats, &mut state.pending_tools, item);
                         }
-                        // Array content with tool_result entries: skip (not real user messages)
                     }
                 }
-                "assistant" => {
-                    // Extract usage tokens from message.usage
-                    if let Some(usage) = message_obj.get("usage") {
-                        let input = usage.get("input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
-                        let output = usage.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
-                        highlights.total_input_tokens += input;
-                        highlights.total_output_tokens += output;
-                    }
-                    // Extract model name (take the first non-null model)
-                    if highlights.model.is_none() {
-                        if let Some(model) = message_obj.get("model").and_then(|m| m.as_str()) {
-                            highlights.model = Some(model.to_string());
-                        }
+            }
+            "assistant" => {
+                record_turn(&mut state.highlights, &mut state.last_message_id, message_obj, None);
+                // Extract model name (take the first non-null model)
+                if state.highlights.model.is_none() {
+                    if let Some(model) = message_obj.get("model").and_then(|m| m.as_str()) {
+                        state.highlights.model = Some(model.to_string());
                     }
+                }
 
-                    if let Some(arr) = content.and_then(|c| c.as_array()) {
-                        for item in arr {
-                            let item_type = item.get("type").and_then(|t| t.as_str()).unwrap_or("");
-                            match item_type {
-                                "text" => {
-                                    if let Some(text) = item.get("text").and_then(|t| t.as_str()) {
-                                        let first_sentence = text.split('.').next().unwrap_or(text);
-                                        highlights.assistant_summaries.push(
-                                            first_sentence.chars().take(200).collect()
-                                        );
-                                    }
-                                }
-                                "tool_use" => {
-                                    if let Some(name) = item.get("name").and_then(|n| n.as_str()) {
-                                        if !highlights.tools_used.contains(&name.to_string()) {
-                                            highlights.tools_used.push(name.to_string());
-                                        }
-                                    }
+                if let Some(arr) = content.and_then(|c| c.as_array()) {
+                    for item in arr {
+                        let item_type = item.get("type").and_then(|t| t.as_str()).unwrap_or("");
+                        match item_type {
+                            "text" => {
+                                if let Some(text) = item.get("text").and_then(|t| t.as_str()) {
+                                    state.highlights.assistant_summaries.push(first_sentence(text));
                                 }
-                                // Skip "thinking" blocks and other types
-                                _ => {}
                             }
+                            "tool_use" => record_main_tool_use(state, item),
+                            // Skip "thinking" blocks and other types
+                            _ => {}
                         }
                     }
                 }
-                _ => {}
             }
-        } else {
-            // Backward compat: flat format (role/type/message at top level)
-            let role = entry.get("role").and_then(|r| r.as_str()).unwrap_or("");
-            let msg_type = entry.get("type").and_then(|t| t.as_str()).unwrap_or("");
-            match (role, msg_type) {
-                ("user", _) => {
-                    if let Some(text) = extract_text_flat(&entry) {
-                        if !is_command_content(&text) {
-                            highlights.user_messages.push(text.chars().take(200).collect());
+            _ => {}
+        }
+    } else {
+        // Backward compat: flat format (role/type/message at top level)
+        let role = entry.get("role").and_then(|r| r.as_str()).unwrap_or("");
+        let highlights = &mut state.highlights;
+        match (role, top_type) {
+            ("user", _) => {
+                if let Some(text) = extract_text_flat(entry) {
+                    if !is_command_content(&text) {
+                        highlights.user_messages.push(text.chars().take(200).collect());
+                    }
+                }
+            }
+            ("assistant", "text") => {
+                if let Some(text) = extract_text_flat(entry) {
+                    highlights.assistant_summaries.push(first_sentence(&text));
+                }
+            }
+            ("assistant", "tool_use") => record_main_tool_use(state, entry),
+            _ => {}
+        }
+    }
+}
+
+/// First sentence of an assistant message, at most 200 characters.
+fn first_sentence(text: &str) -> String {
+    text.split('.').next().unwrap_or(text).chars().take(200).collect()
+}
+
+/// Fold a sidechain entry into the subagent it belongs to. Its tokens still count towards
+/// the session's totals, as turns tagged with the subagent.
+fn apply_sidechain_entry(state: &mut TranscriptState, entry: &serde_json::Value, timestamp: Option<DateTime<Utc>>) {
+    let Some(message_obj) = entry.get("message").and_then(|m| m.as_object()) else {
+        return;
+    };
+    let index = subagent_index(state, entry);
+    let role = message_obj.get("role").and_then(|r| r.as_str()).unwrap_or("");
+    let content = message_obj.get("content");
+
+    let recorded = if role == "assistant" {
+        let id = state.highlights.subagents[index].id.clone();
+        let mut last_id = state.subagent_message_ids.remove(&id);
+        let recorded = record_turn(&mut state.highlights, &mut last_id, message_obj, Some(&id));
+        if let Some(last_id) = last_id {
+            state.subagent_message_ids.insert(id, last_id);
+        }
+        recorded
+    } else {
+        None
+    };
+
+    let highlights = &mut state.highlights;
+    let subagent = &mut highlights.subagents[index];
+    if let Some(ts) = timestamp {
+        subagent.started_at = Some(subagent.started_at.map_or(ts, |s| s.min(ts)));
+        subagent.ended_at = Some(subagent.ended_at.map_or(ts, |e| e.max(ts)));
+    }
+    match role {
+        "user" => {
+            if let Some(prompt) = content.and_then(|c| c.as_str()) {
+                if subagent.description.is_empty() {
+                    match state.pending_tasks.iter().position(|t| t.prompt == prompt) {
+                        Some(i) => {
+                            let task = state.pending_tasks.remove(i);
+                            subagent.description = task.description;
+                            subagent.agent_type = task.agent_type;
                         }
+                        None => subagent.description = prompt.chars().take(200).collect(),
                     }
                 }
-                ("assistant", "text") => {
-                    if let Some(text) = extract_text_flat(&entry) {
-                        let first = text.split('.').next().unwrap_or(&text);
-                        highlights.assistant_summaries.push(first.chars().take(200).collect());
+            } else if let Some(arr) = content.and_then(|c| c.as_array()) {
+                for item in arr {
+                    if item.get("type").and_then(|t| t.as_str()) == Some("tool_result") {
+                        record_tool_result(&mut subagent.tool_stats, &mut state.pending_tools, item);
                     }
                 }
-                ("assistant", "tool_use") => {
-                    if let Some(name) = entry.get("name").and_then(|n| n.as_str()) {
-                        if !highlights.tools_used.contains(&name.to_string()) {
-                            highlights.tools_used.push(name.to_string());
+            }
+        }
+        "assistant" => {
+            if let Some((replaced, usage)) = recorded {
+                match replaced {
+                    Some(old) => subagent.usage.sub(&old),
+                    None => subagent.turns += 1,
+                }
+                subagent.usage.add(&usage);
+            }
+            if subagent.model.is_none() {
+                subagent.model = message_obj.get("model").and_then(|m| m.as_str()).map(str::to_string);
+            }
+            for item in content.and_then(|c| c.as_array()).into_iter().flatten() {
+                match item.get("type").and_then(|t| t.as_str()).unwrap_or("") {
+                    "text" => {
+                        if let Some(text) = item.get("text").and_then(|t| t.as_str()) {
+                            subagent.summaries.push(first_sentence(text));
+                            if subagent.summaries.len() > SUBAGENT_SUMMARIES {
+                                subagent.summaries.remove(0);
+                            }
                         }
                     }
+                    "tool_use" => {
+                        record_tool_use(&mut subagent.tool_stats, &mut highlights.touched_files, &mut state.pending_tools, item);
+                    }
+                    _ => {}
                 }
-                _ => {}
             }
         }
+        _ => {}
+    }
+}
+
+/// Index of the subagent a sidechain entry belongs to, starting a new one for the first
+/// entry of a sidechain. Entries carry an `agentId` in newer transcripts; older ones are
+/// followed through `parentUuid`, a sidechain's first entry having no sidechain parent.
+fn subagent_index(state: &mut TranscriptState, entry: &serde_json::Value) -> usize {
+    let subagents = &mut state.highlights.subagents;
+    let id = match entry.get("agentId").and_then(|a| a.as_str()) {
+        Some(agent) => agent.to_string(),
+        None => {
+            let uuid = entry.get("uuid").and_then(|u| u.as_str());
+            let parent = entry.get("parentUuid")
+                .and_then(|p| p.as_str())
+                .and_then(|p| state.sidechain_entries.get(p))
+                .cloned();
+            let id = parent
+                .or_else(|| uuid.map(str::to_string))
+                .or_else(|| subagents.last().map(|s| s.id.clone()))
+                .unwrap_or_else(|| "sidechain".to_string());
+            if let Some(uuid) = uuid {
+                state.sidechain_entries.insert(uuid.to_string(), id.clone());
+            }
+            id
+        }
+    };
+    match subagents.iter().position(|s| s.id == id) {
+        Some(i) => i,
+        None => {
+            subagents.push(Subagent { id, ..Subagent::default() });
+            subagents.len() - 1
+        }
+    }
+}
+
+/// Add the usage of an assistant message as a turn of `subagent` (`None` for the main
+/// conversation). `last_id` is the chain's last `message.id`: later blocks of the same
+/// response repeat its usage, so they replace the turn rather than add one.
+/// Returns the usage replaced, if any, and the usage recorded.
+fn record_turn(
+    highlights: &mut TranscriptHighlights,
+    last_id: &mut Option<String>,
+    message_obj: &serde_json::Map<String, serde_json::Value>,
+    subagent: Option<&str>,
+) -> Option<(Option<TokenUsage>, TokenUsage)> {
+    let usage = message_obj.get("usage")?;
+    let count = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    let usage = TokenUsage {
+        input: count("input_tokens"),
+        output: count("output_tokens"),
+        cache_creation: count("cache_creation_input_tokens"),
+        cache_read: count("cache_read_input_tokens"),
+    };
+    let model = message_obj.get("model").and_then(|m| m.as_str()).unwrap_or("unknown");
+    let id = message_obj.get("id").and_then(|i| i.as_str());
+    let repeated = id.is_some() && id == last_id.as_deref();
+    let previous = highlights.turns.iter_mut().rev().find(|t| t.subagent.as_deref() == subagent);
+    match previous {
+        Some(turn) if repeated => {
+            let replaced = turn.usage;
+            highlights.usage.sub(&replaced);
+            highlights.usage.add(&usage);
+            turn.usage = usage;
+            Some((Some(replaced), usage))
+        }
+        _ if usage.total() > 0 => {
+            highlights.usage.add(&usage);
+            highlights.turns.push(TurnUsage {
+                model: model.to_string(),
+                usage,
+                turns: 1,
+                cost_usd: None,
+                subagent: subagent.map(str::to_string),
+            });
+            *last_id = id.map(str::to_string);
+            Some((None, usage))
+        }
+        _ => {
+            *last_id = None;
+            None
+        }
+    }
+}
+
+/// Count a tool call of the main conversation, remembering Task calls so the sidechain
+/// they start can be described.
+fn record_main_tool_use(state: &mut TranscriptState, block: &serde_json::Value) {
+    let highlights = &mut state.highlights;
+    let Some(name) = record_tool_use(&mut highlights.tool_stats, &mut highlights.touched_files, &mut state.pending_tools, block) else {
+        return;
+    };
+    if !highlights.tools_used.contains(&name) {
+        highlights.tools_used.push(name.clone());
+    }
+    if matches!(name.as_str(), "Task" | "Agent") {
+        let input = block.get("input");
+        let field = |key: &str| input.and_then(|i| i.get(key)).and_then(|v| v.as_str()).map(str::to_string);
+        state.pending_tasks.push(TaskCall {
+            prompt: field("prompt").unwrap_or_default(),
+            description: field("description").unwrap_or_default(),
+            agent_type: field("subagent_type"),
+        });
+    }
+}
+
+/// Count a `tool_use` block in `tool_stats`, note the file it names, and remember its id
+/// for the result. Returns the tool's name.
+fn record_tool_use(
+    tool_stats: &mut Vec<ToolStats>,
+    touched_files: &mut Vec<TouchedFile>,
+    pending: &mut BTreeMap<String, String>,
+    block: &serde_json::Value,
+) -> Option<String> {
+    let name = block.get("name").and_then(|n| n.as_str())?;
+    match tool_stats.iter_mut().find(|t| t.name == name) {
+        Some(stats) => stats.calls += 1,
+        None => tool_stats.push(ToolStats { name: name.to_string(), calls: 1, errors: 0 }),
+    }
+    if let Some(id) = block.get("id").and_then(|i| i.as_str()) {
+        pending.insert(id.to_string(), name.to_string());
+    }
+
+    let path = block.get("input")
+        .and_then(|i| i.get("file_path").or_else(|| i.get("notebook_path")))
+        .and_then(|p| p.as_str())
+        .filter(|p| !p.is_empty());
+    if let (Some(path), true) = (path, matches!(name, "Read" | "Write" | "Edit" | "MultiEdit" | "NotebookEdit")) {
+        let index = match touched_files.iter().position(|f| f.path == path) {
+            Some(i) => i,
+            None => {
+                touched_files.push(TouchedFile { path: path.to_string(), reads: 0, edits: 0, writes: 0 });
+                touched_files.len() - 1
+            }
+        };
+        let file = &mut touched_files[index];
+        match name {
+            "Read" => file.reads += 1,
+            "Write" => file.writes += 1,
+            _ => file.edits += 1,
+        }
+    }
+    Some(name.to_string())
+}
+
+/// Match a `tool_result` block to its call, counting it against the tool if it failed.
+fn record_tool_result(
+    tool_stats: &mut [ToolStats],
+    pending: &mut BTreeMap<String, String>,
+    block: &serde_json::Value,
+) {
+    let Some(name) = block.get("tool_use_id")
+        .and_then(|i| i.as_str())
+        .and_then(|id| pending.remove(id))
+    else {
+        return;
+    };
+    if block.get("is_error").and_then(|e| e.as_bool()).unwrap_or(false) {
+        if let Some(stats) = tool_stats.iter_mut().find(|t| t.name == name) {
+            stats.errors += 1;
+        }
+    }
+}
+
+/// Make paths under `root` relative to it, merging entries that then name the same file.
+/// Paths outside `root` are kept as they are.
+pub fn relativize_touched_files(files: Vec<TouchedFile>, root: &Path) -> Vec<TouchedFile> {
+    let mut merged: Vec<TouchedFile> = Vec::new();
+    for mut file in files {
+        if let Ok(relative) = Path::new(&file.path).strip_prefix(root) {
+            if !relative.as_os_str().is_empty() {
+                file.path = relative.to_string_lossy().into_owned();
+            }
+        }
+        match merged.iter_mut().find(|f| f.path == file.path) {
+            Some(existing) => {
+                existing.reads += file.reads;
+                existing.edits += file.edits;
+                existing.writes += file.writes;
+            }
+            None => merged.push(file),
+        }
+    }
+    merged
+}
+
+/// The messages to store with a session: the first one (the original request) plus the
+/// most recent ones, at most `max_messages` in total and `max_bytes` of text.
+pub fn cap_for_storage(messages: &[String], max_messages: usize, max_bytes: usize) -> Vec<String> {
+    let Some((first, rest)) = messages.split_first() else {
+        return Vec::new();
+    };
+    if max_messages == 0 {
+        return Vec::new();
+    }
+    let mut budget = max_bytes.saturating_sub(first.len());
+    let mut recent: Vec<String> = Vec::new();
+    for msg in rest.iter().rev().take(max_messages - 1) {
+        if msg.len() > budget {
+            break;
+        }
+        budget -= msg.len();
+        recent.push(msg.clone());
+    }
+    recent.reverse();
+    std::iter::once(first.clone()).chain(recent).collect()
+}
+
+/// The usage turns to store with a session: the last `max_turns` as recorded, preceded
+/// by everything older folded into one entry per model and subagent, in order of first
+/// use. Token totals, per-model costs and subagent shares are unchanged by the fold.
+pub fn cap_usage_turns(turns: &[TurnUsage], max_turns: usize) -> Vec<TurnUsage> {
+    let split = turns.len().saturating_sub(max_turns);
+    let (older, recent) = turns.split_at(split);
+    let mut folded: Vec<TurnUsage> = Vec::new();
+    for turn in older {
+        match folded.iter_mut().find(|f| f.model == turn.model && f.subagent == turn.subagent) {
+            Some(entry) => {
+                entry.usage.add(&turn.usage);
+                entry.turns += turn.turns;
+                entry.cost_usd = match (entry.cost_usd, turn.cost_usd) {
+                    (Some(a), Some(b)) => Some(a + b),
+                    (a, b) => a.or(b),
+                };
+            }
+            None => folded.push(turn.clone()),
+        }
+    }
+    folded.extend_from_slice(recent);
+    folded
+}
+
+fn state_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(".transcripts")
+}
+
+/// Where the parse state of `session_id`'s transcript is kept (local, never synced).
+pub fn state_path(data_dir: &Path, session_id: &str) -> PathBuf {
+    state_dir(data_dir).join(format!("{}.json", session_id))
+}
+
+/// Load the saved parse state for `session_id`, advance it over the transcript and save it.
+/// A missing or unreadable state starts from the beginning of the file.
+pub fn update_state(data_dir: &Path, session_id: &str, transcript_path: &Path) -> Result<TranscriptState> {
+    let path = state_path(data_dir, session_id);
+    let mut state: TranscriptState = crate::storage::safe_read_json_with_quarantine(&path, &data_dir.join("quarantine"))
+        .unwrap_or(None)
+        .unwrap_or_default();
+    parse_transcript(transcript_path, &mut state)?;
+    crate::storage::ensure_gitignored(data_dir, &[".transcripts/"])?;
+    crate::storage::write_json(&path, &state)?;
+    Ok(state)
+}
 
-        message_count += 1;
-        if message_count >= max_messages { break; }
+/// Drop the saved parse state once the session has been enriched.
+pub fn remove_state(data_dir: &Path, session_id: &str) -> Result<()> {
+    match std::fs::remove_file(state_path(data_dir, session_id)) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
     }
-    Ok(highlights)
+}
+
+/// Collect the `timestamp` of every conversation entry in the transcript, in file order.
+///
+/// Entries without a parseable timestamp are skipped.
+pub fn entry_timestamps(path: &Path) -> Result<Vec<DateTime<Utc>>> {
+    let mut state = TranscriptState::default();
+    parse_transcript(path, &mut state)?;
+    Ok(state.timestamps)
+}
+
+/// First and last entry timestamps of a transcript, or `None` if it has none.
+pub fn time_bounds(path: &Path) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    timestamp_bounds(&entry_timestamps(path).ok()?)
+}
+
+/// Earliest and latest of the given timestamps, or `None` if the slice is empty.
+pub fn timestamp_bounds(timestamps: &[DateTime<Utc>]) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let first = *timestamps.iter().min()?;
+    let last = *timestamps.iter().max()?;
+    Some((first, last))
+}
+
+/// Minutes of active work: the sum of gaps between consecutive entries, skipping any
+/// gap longer than `idle_threshold_minutes` (the user walked away).
+///
+/// Returns `None` when there are no timestamps to measure.
+pub fn active_minutes(timestamps: &[DateTime<Utc>], idle_threshold_minutes: u32) -> Option<u32> {
+    if timestamps.is_empty() { return None; }
+    let mut sorted = timestamps.to_vec();
+    sorted.sort();
+    let threshold = chrono::Duration::minutes(idle_threshold_minutes as i64);
+    let active_secs: i64 = sorted
+        .windows(2)
+        .map(|w| w[1] - w[0])
+        .filter(|gap| *gap <= threshold)
+        .map(|gap| gap.num_seconds())
+        .sum();
+    Some((active_secs / 60) as u32)
 }
 
 /// Check if user message content is a command (not a real user message)
@@ -329,32 +786,6 @@ fn extract_text_flat(entry: &serde_json::Value) -> Option<String> {
         })
 }
 
-// ---------------------------------------------------------------------------
-// Cost estimation
-// ---------------------------------------------------------------------------
-
-struct ModelPricing {
-    input_per_1m: f64,
-    output_per_1m: f64,
-}
-
-fn get_pricing(model: Option<&str>) -> ModelPricing {
-    let model = model.unwrap_or("");
-    if model.contains("opus") {
-        ModelPricing { input_per_1m: 15.0, output_per_1m: 75.0 }
-    } else if model.contains("haiku") {
-        ModelPricing { input_per_1m: 0.25, output_per_1m: 1.25 }
-    } else {
-        // Default: Sonnet pricing (most common)
-        ModelPricing { input_per_1m: 3.0, output_per_1m: 15.0 }
-    }
-}
-
-pub fn estimate_cost_usd(input_tokens: u64, output_tokens: u64, model: Option<&str>) -> f64 {
-    let p = get_pricing(model);
-    (input_tokens as f64 * p.input_per_1m + output_tokens as f64 * p.output_per_1m) / 1_000_000.0
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,7 +809,7 @@ mod tests {
     fn test_parse_jsonl_extracts_user_messages() {
         let tmp = TempDir::new().unwrap();
         let path = write_sample_transcript(tmp.path());
-        let highlights = extract_highlights(&path, tmp.path(), 100, 100_000);
+        let highlights = extract_highlights(&path, tmp.path());
         assert_eq!(highlights.user_messages.len(), 2);
         assert!(highlights.user_messages[0].contains("Fix the login bug"));
     }
@@ -387,7 +818,7 @@ mod tests {
     fn test_parse_jsonl_extracts_tools() {
         let tmp = TempDir::new().unwrap();
         let path = write_sample_transcript(tmp.path());
-        let highlights = extract_highlights(&path, tmp.path(), 100, 100_000);
+        let highlights = extract_highlights(&path, tmp.path());
         assert!(highlights.tools_used.contains(&"Read".to_string()));
         assert!(highlights.tools_used.contains(&"Write".to_string()));
     }
@@ -401,7 +832,7 @@ mod tests {
             r#"{"role":"assistant","type":"tool_use","name":"Read","input":{}}"#,
         ];
         std::fs::write(&path, lines.join("\n")).unwrap();
-        let highlights = extract_highlights(&path, tmp.path(), 100, 100_000);
+        let highlights = extract_highlights(&path, tmp.path());
         assert_eq!(highlights.tools_used.len(), 1);
     }
 
@@ -409,7 +840,7 @@ mod tests {
     fn test_missing_transcript_returns_fallback() {
         let tmp = TempDir::new().unwrap();
         let path = tmp.path().join("nonexistent.jsonl");
-        let highlights = extract_highlights(&path, tmp.path(), 100, 100_000);
+        let highlights = extract_highlights(&path, tmp.path());
         assert!(highlights.user_messages.is_empty() ||
             highlights.assistant_summaries.iter().any(|s| s.contains("unavailable")));
     }
@@ -423,8 +854,125 @@ mod tests {
             lines.push(format!(r#"{{"role":"user","type":"text","message":"msg {}"}}"#, i));
         }
         std::fs::write(&path, lines.join("\n")).unwrap();
-        let highlights = extract_highlights(&path, tmp.path(), 5, 100_000);
-        assert!(highlights.user_messages.len() <= 5);
+        let highlights = extract_highlights(&path, tmp.path());
+        // Everything is parsed; only what gets stored is capped.
+        assert_eq!(highlights.user_messages.len(), 50);
+        let stored = cap_for_storage(&highlights.user_messages, 5, 100_000);
+        assert_eq!(stored, vec!["msg 0", "msg 46", "msg 47", "msg 48", "msg 49"]);
+    }
+
+    #[test]
+    fn test_cap_usage_turns_folds_older_turns() {
+        let turn = |model: &str, input: u64, subagent: Option<&str>| TurnUsage {
+            model: model.into(),
+            usage: TokenUsage { input, ..Default::default() },
+            turns: 1,
+            cost_usd: Some(input as f64),
+            subagent: subagent.map(str::to_string),
+        };
+        let turns = vec![
+            turn("opus", 1, None),
+            turn("haiku", 2, Some("a1")),
+            turn("opus", 3, None),
+            turn("haiku", 4, Some("a1")),
+            turn("opus", 5, None),
+            turn("opus", 6, None),
+        ];
+        assert_eq!(cap_usage_turns(&turns, 10), turns);
+
+        let capped = cap_usage_turns(&turns, 2);
+        assert_eq!(capped.len(), 4);
+        assert_eq!((capped[0].model.as_str(), capped[0].turns, capped[0].usage.input), ("opus", 2, 4));
+        assert_eq!((capped[1].subagent.as_deref(), capped[1].turns, capped[1].usage.input), (Some("a1"), 2, 6));
+        assert_eq!(capped[1].cost_usd, Some(6.0));
+        assert_eq!(&capped[2..], &turns[4..]);
+        let sum = |turns: &[TurnUsage]| turns.iter().fold((0, 0), |(n, t), x| (n + x.turns, t + x.usage.input));
+        assert_eq!(sum(&capped), sum(&turns));
+    }
+
+    #[test]
+    fn test_cap_for_storage_respects_bytes() {
+        let messages: Vec<String> = vec!["first".into(), "a".repeat(10), "b".repeat(10), "c".repeat(10)];
+        assert_eq!(cap_for_storage(&messages, 100, 24), vec!["first".to_string(), "c".repeat(10)]);
+        assert_eq!(cap_for_storage(&messages, 1, 1_000), vec!["first".to_string()]);
+        assert!(cap_for_storage(&messages, 0, 1_000).is_empty());
+        assert!(cap_for_storage(&[], 5, 1_000).is_empty());
+    }
+
+    #[test]
+    fn test_parse_counts_usage_across_whole_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("transcript.jsonl");
+        let mut lines = vec![r#"{"type":"user","message":{"role":"user","content":"the original request"}}"#.to_string()];
+        for i in 0..2_000 {
+            lines.push(format!(
+                r#"{{"type":"assistant","message":{{"role":"assistant","content":[{{"type":"text","text":"step {}"}}],"usage":{{"input_tokens":10,"output_tokens":1}}}}}}"#,
+                i
+            ));
+        }
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
+        assert_eq!(highlights.user_messages, vec!["the original request"]);
+        assert_eq!(highlights.usage.input, 20_000);
+        assert_eq!(highlights.usage.output, 2_000);
+    }
+
+    #[test]
+    fn test_parse_transcript_resumes_from_offset() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("transcript.jsonl");
+        let first = r#"{"type":"user","message":{"role":"user","content":"first"},"timestamp":"2026-01-01T10:00:00Z"}"#;
+        let second = r#"{"type":"assistant","message":{"role":"assistant","content":[],"usage":{"input_tokens":5,"output_tokens":2}}}"#;
+        std::fs::write(&path, format!("{}\n", first)).unwrap();
+
+        let mut state = TranscriptState::default();
+        parse_transcript(&path, &mut state).unwrap();
+        assert_eq!(state.offset, first.len() as u64 + 1);
+        assert_eq!(state.highlights.user_messages, vec!["first"]);
+
+        // A half-written line is left for the next pass.
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        std::io::Write::write_all(&mut file, &second.as_bytes()[..20]).unwrap();
+        parse_transcript(&path, &mut state).unwrap();
+        assert_eq!(state.offset, first.len() as u64 + 1);
+
+        std::io::Write::write_all(&mut file, &second.as_bytes()[20..]).unwrap();
+        parse_transcript(&path, &mut state).unwrap();
+        assert_eq!(state.highlights.user_messages, vec!["first"]);
+        assert_eq!(state.highlights.usage.input, 5);
+        assert_eq!(state.timestamps.len(), 1);
+        let offset = state.offset;
+        parse_transcript(&path, &mut state).unwrap();
+        assert_eq!(state.offset, offset);
+        assert_eq!(state.highlights.usage.input, 5);
+
+        // A replaced (shorter) transcript is parsed from the start.
+        std::fs::write(&path, "{}\n").unwrap();
+        parse_transcript(&path, &mut state).unwrap();
+        assert_eq!(state.offset, 3);
+        assert!(state.highlights.user_messages.is_empty());
+    }
+
+    #[test]
+    fn test_update_state_persists_offset() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let path = tmp.path().join("transcript.jsonl");
+        std::fs::write(&path, "{\"type\":\"user\",\"message\":{\"role\":\"user\",\"content\":\"one\"}}\n").unwrap();
+        update_state(&data_dir, "s1", &path).unwrap();
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        std::io::Write::write_all(&mut file, b"{\"type\":\"user\",\"message\":{\"role\":\"user\",\"content\":\"two\"}}\n").unwrap();
+        let state = update_state(&data_dir, "s1", &path).unwrap();
+        assert_eq!(state.highlights.user_messages, vec!["one", "two"]);
+        assert!(state_path(&data_dir, "s1").exists());
+        let gitignore = std::fs::read_to_string(data_dir.join(".gitignore")).unwrap();
+        assert!(gitignore.contains(".transcripts/"));
+
+        remove_state(&data_dir, "s1").unwrap();
+        assert!(!state_path(&data_dir, "s1").exists());
+        remove_state(&data_dir, "s1").unwrap();
     }
 
     // --- Tests for real Claude Code JSONL format ---
@@ -442,7 +990,7 @@ mod tests {
     fn test_parse_real_claude_code_format() {
         let path = fixture_path("real_claude_code.jsonl");
         assert!(path.exists(), "fixture file missing: {:?}", path);
-        let highlights = parse_jsonl(&path, 500, 1_000_000).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
 
         // Should extract real user messages (not tool_result arrays, not isMeta)
         assert!(
@@ -496,7 +1044,7 @@ mod tests {
             r#"{"type":"user","message":{"role":"user","content":"Real user message"}}"#,
         ];
         std::fs::write(&path, lines.join("\n")).unwrap();
-        let highlights = parse_jsonl(&path, 500, 1_000_000).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
 
         // Only the real user message should be extracted
         assert_eq!(
@@ -521,7 +1069,7 @@ mod tests {
             r#"{"type":"user","message":{"role":"user","content":"Another real question"}}"#,
         ];
         std::fs::write(&path, lines.join("\n")).unwrap();
-        let highlights = parse_jsonl(&path, 500, 1_000_000).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
 
         // Only string content user messages, not tool_result arrays
         assert_eq!(
@@ -537,7 +1085,7 @@ mod tests {
         // The old flat format must still work
         let tmp = TempDir::new().unwrap();
         let path = write_sample_transcript(tmp.path());
-        let highlights = parse_jsonl(&path, 100, 100_000).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
 
         assert_eq!(highlights.user_messages.len(), 2, "expected 2 user messages from flat format");
         assert!(highlights.user_messages[0].contains("Fix the login bug"));
@@ -560,7 +1108,7 @@ mod tests {
             r#"{"type":"system","subtype":"command","content":"<command-name>commit</command-name>","isMeta":true}"#,
         ];
         std::fs::write(&path, lines.join("\n")).unwrap();
-        let highlights = parse_jsonl(&path, 500, 1_000_000).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
 
         assert_eq!(
             highlights.user_messages.len(), 1,
@@ -579,7 +1127,7 @@ mod tests {
             r#"{"type":"user","message":{"role":"user","content":"Please review the PR"}}"#,
         ];
         std::fs::write(&path, lines.join("\n")).unwrap();
-        let highlights = parse_jsonl(&path, 500, 1_000_000).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
 
         assert_eq!(
             highlights.user_messages.len(), 1,
@@ -596,9 +1144,12 @@ mod tests {
             user_messages: vec![],
             assistant_summaries: vec![],
             tools_used: vec![],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
+            tool_stats: vec![],
+            touched_files: vec![],
+            usage: TokenUsage::default(),
+            turns: vec![],
             model: None,
+            subagents: vec![],
         };
         let summary = build_summary(&highlights);
         assert!(summary.what_was_done.is_empty());
@@ -615,9 +1166,12 @@ mod tests {
             ],
             assistant_summaries: vec![],
             tools_used: vec![],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
+            tool_stats: vec![],
+            touched_files: vec![],
+            usage: TokenUsage::default(),
+            turns: vec![],
             model: None,
+            subagents: vec![],
         };
         let summary = build_summary(&highlights);
         assert_eq!(summary.first_request, "Fix the login bug");
@@ -634,9 +1188,12 @@ mod tests {
                 "All 12 tests pass".into(),
             ],
             tools_used: vec![],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
+            tool_stats: vec![],
+            touched_files: vec![],
+            usage: TokenUsage::default(),
+            turns: vec![],
             model: None,
+            subagents: vec![],
         };
         let summary = build_summary(&highlights);
         // Should use the last 3 summaries
@@ -653,9 +1210,12 @@ mod tests {
             user_messages: vec![],
             assistant_summaries: vec!["Fixed the bug".into()],
             tools_used: vec![],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
+            tool_stats: vec![],
+            touched_files: vec![],
+            usage: TokenUsage::default(),
+            turns: vec![],
             model: None,
+            subagents: vec![],
         };
         let summary = build_summary(&highlights);
         assert_eq!(summary.what_was_done, "Fixed the bug");
@@ -670,9 +1230,12 @@ mod tests {
             ],
             assistant_summaries: vec!["Bug fixed".into()],
             tools_used: vec![],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
+            tool_stats: vec![],
+            touched_files: vec![],
+            usage: TokenUsage::default(),
+            turns: vec![],
             model: None,
+            subagents: vec![],
         };
         let summary = build_summary(&highlights);
         assert!(
@@ -691,9 +1254,12 @@ mod tests {
                 "The remaining work is to add integration tests".into(),
             ],
             tools_used: vec![],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
+            tool_stats: vec![],
+            touched_files: vec![],
+            usage: TokenUsage::default(),
+            turns: vec![],
             model: None,
+            subagents: vec![],
         };
         let summary = build_summary(&highlights);
         assert!(
@@ -711,9 +1277,12 @@ mod tests {
             ],
             assistant_summaries: vec![],
             tools_used: vec![],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
+            tool_stats: vec![],
+            touched_files: vec![],
+            usage: TokenUsage::default(),
+            turns: vec![],
             model: None,
+            subagents: vec![],
         };
         let summary = build_summary(&highlights);
         assert!(
@@ -729,9 +1298,12 @@ mod tests {
             user_messages: vec!["NEXT step is deployment".into()],
             assistant_summaries: vec![],
             tools_used: vec![],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
+            tool_stats: vec![],
+            touched_files: vec![],
+            usage: TokenUsage::default(),
+            turns: vec![],
             model: None,
+            subagents: vec![],
         };
         let summary = build_summary(&highlights);
         assert!(
@@ -750,9 +1322,12 @@ mod tests {
                 "Next: add tests".into(),
             ],
             tools_used: vec![],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
+            tool_stats: vec![],
+            touched_files: vec![],
+            usage: TokenUsage::default(),
+            turns: vec![],
             model: None,
+            subagents: vec![],
         };
         let summary = build_summary(&highlights);
         // Should only appear once even though both user and assistant said it
@@ -768,9 +1343,12 @@ mod tests {
                 "Work completed.\nNext steps:\n- Add error handling\n- Write docs".into(),
             ],
             tools_used: vec![],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
+            tool_stats: vec![],
+            touched_files: vec![],
+            usage: TokenUsage::default(),
+            turns: vec![],
             model: None,
+            subagents: vec![],
         };
         let summary = build_summary(&highlights);
         assert!(
@@ -796,9 +1374,12 @@ mod tests {
                 "I'll add a rate limiter to protect against brute force attacks".into(),
             ],
             tools_used: vec!["Read".into(), "Edit".into(), "Bash".into(), "Write".into()],
-            total_input_tokens: 0,
-            total_output_tokens: 0,
+            tool_stats: vec![],
+            touched_files: vec![],
+            usage: TokenUsage::default(),
+            turns: vec![],
             model: None,
+            subagents: vec![],
         };
         let summary = build_summary(&highlights);
 
@@ -812,47 +1393,168 @@ mod tests {
     // --- Tests for token/cost tracking ---
 
     #[test]
-    fn test_estimate_cost_opus() {
-        let cost = estimate_cost_usd(1_000_000, 1_000_000, Some("claude-opus-4-6"));
-        assert!((cost - 90.0).abs() < 0.01);
+    fn test_graceful_fallback_no_usage() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("transcript.jsonl");
+        let line = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"hello"}]}}"#;
+        std::fs::write(&path, line).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
+        assert_eq!(highlights.usage.input, 0);
+        assert_eq!(highlights.usage.output, 0);
     }
 
     #[test]
-    fn test_estimate_cost_sonnet_default() {
-        let cost = estimate_cost_usd(1_000_000, 1_000_000, None);
-        assert!((cost - 18.0).abs() < 0.01);
+    fn test_parse_jsonl_extracts_usage() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("transcript.jsonl");
+        let lines = [
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"hello"}],"usage":{"input_tokens":100,"output_tokens":50},"model":"claude-sonnet-4-6-20250514"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"done"}],"usage":{"input_tokens":200,"output_tokens":100,"cache_creation_input_tokens":1000,"cache_read_input_tokens":5000},"model":"claude-sonnet-4-6-20250514"}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
+        assert_eq!(highlights.usage, TokenUsage { input: 300, output: 150, cache_creation: 1000, cache_read: 5000 });
+        assert_eq!(highlights.usage.total(), 6450);
+        assert_eq!(highlights.model.as_deref(), Some("claude-sonnet-4-6-20250514"));
     }
 
     #[test]
-    fn test_estimate_cost_haiku() {
-        let cost = estimate_cost_usd(1_000_000, 1_000_000, Some("claude-haiku-4-5"));
-        assert!((cost - 1.5).abs() < 0.01);
+    fn test_parse_jsonl_usage_per_turn() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("transcript.jsonl");
+        let lines = [
+            // One response split over two entries, repeating its usage
+            r#"{"type":"assistant","message":{"id":"msg_1","role":"assistant","model":"claude-opus-4-6","content":[{"type":"text","text":"a"}],"usage":{"input_tokens":10,"output_tokens":1,"cache_read_input_tokens":100}}}"#,
+            r#"{"type":"assistant","message":{"id":"msg_1","role":"assistant","model":"claude-opus-4-6","content":[{"type":"tool_use","name":"Read"}],"usage":{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":100}}}"#,
+            r#"{"type":"assistant","message":{"id":"msg_2","role":"assistant","model":"claude-haiku-4-5","content":[],"usage":{"input_tokens":3,"output_tokens":2}}}"#,
+            r#"{"type":"assistant","message":{"id":"msg_3","role":"assistant","model":"<synthetic>","content":[],"usage":{"input_tokens":0,"output_tokens":0}}}"#,
+            r#"{"type":"assistant","message":{"id":"msg_4","role":"assistant","model":"claude-opus-4-6","content":[],"usage":{"input_tokens":7,"output_tokens":4}}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
+        let models: Vec<&str> = highlights.turns.iter().map(|t| t.model.as_str()).collect();
+        assert_eq!(models, vec!["claude-opus-4-6", "claude-haiku-4-5", "claude-opus-4-6"]);
+        assert_eq!(highlights.turns[0].usage, TokenUsage { input: 10, output: 5, cache_creation: 0, cache_read: 100 });
+        assert_eq!(highlights.usage, TokenUsage { input: 20, output: 11, cache_creation: 0, cache_read: 100 });
     }
 
     #[test]
-    fn test_graceful_fallback_no_usage() {
+    fn test_sidechains_attributed_to_subagents() {
         let tmp = TempDir::new().unwrap();
         let path = tmp.path().join("transcript.jsonl");
-        let line = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"hello"}]}}"#;
-        std::fs::write(&path, line).unwrap();
-        let highlights = parse_jsonl(&path, 100, 100_000).unwrap();
-        assert_eq!(highlights.total_input_tokens, 0);
-        assert_eq!(highlights.total_output_tokens, 0);
+        let lines = [
+            r#"{"type":"user","uuid":"u1","message":{"role":"user","content":"Audit the auth module"}}"#,
+            r#"{"type":"assistant","uuid":"a1","message":{"id":"m1","role":"assistant","model":"claude-opus-4-6","content":[{"type":"tool_use","id":"t1","name":"Task","input":{"description":"Find auth call sites","prompt":"List every caller of login()","subagent_type":"Explore"}}],"usage":{"input_tokens":100,"output_tokens":10}}}"#,
+            r#"{"type":"user","uuid":"s1","parentUuid":null,"isSidechain":true,"timestamp":"2026-01-01T10:00:00Z","message":{"role":"user","content":"List every caller of login()"}}"#,
+            r#"{"type":"assistant","uuid":"s2","parentUuid":"s1","isSidechain":true,"timestamp":"2026-01-01T10:01:00Z","message":{"id":"m2","role":"assistant","model":"claude-haiku-4-5","content":[{"type":"tool_use","id":"t2","name":"Grep","input":{"pattern":"login("}}],"usage":{"input_tokens":50,"output_tokens":5}}}"#,
+            r#"{"type":"user","uuid":"s3","parentUuid":"s2","isSidechain":true,"message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t2","is_error":true}]}}"#,
+            r#"{"type":"assistant","uuid":"s4","parentUuid":"s3","isSidechain":true,"timestamp":"2026-01-01T10:03:00Z","message":{"id":"m3","role":"assistant","model":"claude-haiku-4-5","content":[{"type":"text","text":"Found three callers. Details follow"}],"usage":{"input_tokens":60,"output_tokens":20}}}"#,
+            r#"{"type":"user","uuid":"u2","parentUuid":"a1","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"Found three callers"}]}}"#,
+            r#"{"type":"assistant","uuid":"a2","message":{"id":"m4","role":"assistant","model":"claude-opus-4-6","content":[{"type":"text","text":"The audit is done."}],"usage":{"input_tokens":200,"output_tokens":30}}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
+
+        assert_eq!(highlights.user_messages, vec!["Audit the auth module"]);
+        assert_eq!(highlights.assistant_summaries, vec!["The audit is done"]);
+        assert_eq!(highlights.tools_used, vec!["Task"]);
+        assert_eq!(highlights.usage.total(), 475);
+
+        assert_eq!(highlights.subagents.len(), 1);
+        let sub = &highlights.subagents[0];
+        assert_eq!(sub.id, "s1");
+        assert_eq!(sub.description, "Find auth call sites");
+        assert_eq!(sub.agent_type.as_deref(), Some("Explore"));
+        assert_eq!(sub.model.as_deref(), Some("claude-haiku-4-5"));
+        assert_eq!((sub.turns, sub.usage.total()), (2, 135));
+        assert_eq!(sub.tool_stats, vec![ToolStats { name: "Grep".into(), calls: 1, errors: 1 }]);
+        assert_eq!(sub.summaries, vec!["Found three callers"]);
+        assert_eq!(sub.ended_at.unwrap() - sub.started_at.unwrap(), chrono::Duration::minutes(3));
+
+        let tags: Vec<Option<&str>> = highlights.turns.iter().map(|t| t.subagent.as_deref()).collect();
+        assert_eq!(tags, vec![None, Some("s1"), Some("s1"), None]);
     }
 
     #[test]
-    fn test_parse_jsonl_extracts_usage() {
+    fn test_parallel_sidechains_by_agent_id() {
         let tmp = TempDir::new().unwrap();
         let path = tmp.path().join("transcript.jsonl");
-        let lines = vec![
-            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"hello"}],"usage":{"input_tokens":100,"output_tokens":50},"model":"claude-sonnet-4-6-20250514"}}"#,
-            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"done"}],"usage":{"input_tokens":200,"output_tokens":100},"model":"claude-sonnet-4-6-20250514"}}"#,
+        let lines = [
+            r#"{"type":"user","isSidechain":true,"agentId":"a1","message":{"role":"user","content":"Check the docs"}}"#,
+            r#"{"type":"user","isSidechain":true,"agentId":"b2","message":{"role":"user","content":"Check the tests"}}"#,
+            r#"{"type":"assistant","isSidechain":true,"agentId":"a1","message":{"id":"m1","role":"assistant","model":"m","content":[{"type":"text","text":"a"}],"usage":{"input_tokens":5,"output_tokens":1}}}"#,
+            r#"{"type":"assistant","isSidechain":true,"agentId":"b2","message":{"id":"m2","role":"assistant","model":"m","content":[],"usage":{"input_tokens":7,"output_tokens":1}}}"#,
+            // Second block of a1's response repeats its usage
+            r#"{"type":"assistant","isSidechain":true,"agentId":"a1","message":{"id":"m1","role":"assistant","model":"m","content":[{"type":"tool_use","id":"t1","name":"Read","input":{"file_path":"/repo/README.md"}}],"usage":{"input_tokens":5,"output_tokens":4}}}"#,
         ];
         std::fs::write(&path, lines.join("\n")).unwrap();
-        let highlights = parse_jsonl(&path, 100, 100_000).unwrap();
-        assert_eq!(highlights.total_input_tokens, 300);
-        assert_eq!(highlights.total_output_tokens, 150);
-        assert_eq!(highlights.model.as_deref(), Some("claude-sonnet-4-6-20250514"));
+        let highlights = parse_jsonl(&path).unwrap();
+
+        let subs: Vec<(&str, &str, u32, u64)> = highlights.subagents.iter()
+            .map(|s| (s.id.as_str(), s.description.as_str(), s.turns, s.usage.total()))
+            .collect();
+        assert_eq!(subs, vec![("a1", "Check the docs", 1, 9), ("b2", "Check the tests", 1, 8)]);
+        assert_eq!(highlights.usage.total(), 17);
+        assert!(highlights.user_messages.is_empty() && highlights.tool_stats.is_empty());
+        assert_eq!(highlights.touched_files[0].path, "/repo/README.md");
+    }
+
+    #[test]
+    fn test_parse_jsonl_tool_stats_and_touched_files() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("transcript.jsonl");
+        let lines = [
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Read","input":{"file_path":"/repo/src/lib.rs"}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"..."}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t2","name":"Edit","input":{"file_path":"/repo/src/lib.rs","old_string":"a","new_string":"b"}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t2","is_error":true,"content":"old_string not found"}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t3","name":"Edit","input":{"file_path":"/repo/src/lib.rs","old_string":"x","new_string":"y"}},{"type":"tool_use","id":"t4","name":"Bash","input":{"command":"cargo test"}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t3","content":"ok"},{"type":"tool_result","tool_use_id":"t4","is_error":true,"content":"exit 101"}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t5","name":"Write","input":{"file_path":"/tmp/notes.md","content":""}}]}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
+        assert_eq!(highlights.tool_stats, vec![
+            ToolStats { name: "Read".into(), calls: 1, errors: 0 },
+            ToolStats { name: "Edit".into(), calls: 2, errors: 1 },
+            ToolStats { name: "Bash".into(), calls: 1, errors: 1 },
+            ToolStats { name: "Write".into(), calls: 1, errors: 0 },
+        ]);
+        assert_eq!(highlights.touched_files, vec![
+            TouchedFile { path: "/repo/src/lib.rs".into(), reads: 1, edits: 2, writes: 0 },
+            TouchedFile { path: "/tmp/notes.md".into(), reads: 0, edits: 0, writes: 1 },
+        ]);
+    }
+
+    #[test]
+    fn test_tool_result_matched_across_passes() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("transcript.jsonl");
+        let call = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{}}]}}"#;
+        std::fs::write(&path, format!("{}\n", call)).unwrap();
+        let mut state = TranscriptState::default();
+        parse_transcript(&path, &mut state).unwrap();
+        assert_eq!(state.pending_tools.get("t1").map(String::as_str), Some("Bash"));
+
+        let result = r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","is_error":true}]}}"#;
+        std::fs::write(&path, format!("{}\n{}\n", call, result)).unwrap();
+        parse_transcript(&path, &mut state).unwrap();
+        assert!(state.pending_tools.is_empty());
+        assert_eq!(state.highlights.tool_stats[0].errors, 1);
+    }
+
+    #[test]
+    fn test_relativize_touched_files_merges() {
+        let files = vec![
+            TouchedFile { path: "/repo/src/lib.rs".into(), reads: 1, edits: 0, writes: 0 },
+            TouchedFile { path: "src/lib.rs".into(), reads: 0, edits: 2, writes: 0 },
+            TouchedFile { path: "/elsewhere/a.txt".into(), reads: 1, edits: 0, writes: 0 },
+        ];
+        let files = relativize_touched_files(files, Path::new("/repo"));
+        assert_eq!(files, vec![
+            TouchedFile { path: "src/lib.rs".into(), reads: 1, edits: 2, writes: 0 },
+            TouchedFile { path: "/elsewhere/a.txt".into(), reads: 1, edits: 0, writes: 0 },
+        ]);
     }
 
     #[test]
@@ -861,7 +1563,54 @@ mod tests {
         let path = tmp.path().join("transcript.jsonl");
         let line = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"hi"}],"model":"claude-opus-4-6-20250514"}}"#;
         std::fs::write(&path, line).unwrap();
-        let highlights = parse_jsonl(&path, 100, 100_000).unwrap();
+        let highlights = parse_jsonl(&path).unwrap();
         assert_eq!(highlights.model.as_deref(), Some("claude-opus-4-6-20250514"));
     }
+
+    // --- Tests for entry timestamps ---
+
+    #[test]
+    fn test_time_bounds_reads_whole_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("transcript.jsonl");
+        let lines = [
+            r#"{"type":"system","subtype":"init","timestamp":"2026-01-01T09:00:00Z"}"#,
+            r#"{"type":"user","message":{"role":"user","content":"start"},"timestamp":"2026-01-01T10:00:00Z"}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[]},"timestamp":"2026-01-01T10:05:00.123Z"}"#,
+            r#"{"type":"user","message":{"role":"user","content":"no timestamp"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[]},"timestamp":"2026-01-01T10:45:00Z"}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let timestamps = entry_timestamps(&path).unwrap();
+        assert_eq!(timestamps.len(), 3);
+        let (first, last) = time_bounds(&path).unwrap();
+        assert_eq!(first, "2026-01-01T10:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert_eq!(last, "2026-01-01T10:45:00Z".parse::<DateTime<Utc>>().unwrap());
+    }
+
+    #[test]
+    fn test_time_bounds_without_timestamps() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_sample_transcript(tmp.path());
+        assert!(time_bounds(&path).is_none());
+        assert!(time_bounds(&tmp.path().join("missing.jsonl")).is_none());
+    }
+
+    #[test]
+    fn test_active_minutes_skips_idle_gaps() {
+        let ts = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        let timestamps = vec![
+            ts("2026-01-01T10:00:00Z"),
+            ts("2026-01-01T10:10:00Z"),
+            ts("2026-01-01T10:20:00Z"),
+            // Two-hour lunch break
+            ts("2026-01-01T12:20:00Z"),
+            ts("2026-01-01T12:25:30Z"),
+        ];
+        assert_eq!(active_minutes(&timestamps, 15), Some(25));
+        // A threshold longer than the break counts everything
+        assert_eq!(active_minutes(&timestamps, 180), Some(145));
+        assert_eq!(active_minutes(&timestamps[..1], 15), Some(0));
+        assert_eq!(active_minutes(&[], 15), None);
+    }
 }
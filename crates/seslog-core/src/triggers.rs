@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use crate::config::{Language, TriggerConfig, TriggerOverride};
+
+/// Marks command files written by Seslog so uninstall only removes its own.
+const MANAGED_MARKER: &str = "<!-- seslog-managed -->";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerAction {
+    CreateRoadmap,
+    SessionSummary,
+}
+
+impl TriggerAction {
+    pub const ALL: [TriggerAction; 2] = [TriggerAction::CreateRoadmap, TriggerAction::SessionSummary];
+
+    /// Slash command name, installed as `~/.claude/commands/<name>.md`.
+    pub fn command(self) -> &'static str {
+        match self {
+            TriggerAction::CreateRoadmap => "seslog-roadmap",
+            TriggerAction::SessionSummary => "seslog-summary",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            TriggerAction::CreateRoadmap => "Create a Seslog roadmap for this project",
+            TriggerAction::SessionSummary => "Summarize this session for Seslog and update the roadmap",
+        }
+    }
+
+    fn default_phrases(self, language: Language) -> Vec<String> {
+        let phrases: &[&str] = match (self, language) {
+            (TriggerAction::CreateRoadmap, Language::En) => &["create roadmap"],
+            (TriggerAction::CreateRoadmap, Language::Tr) => &["yol haritasi olustur"],
+            (TriggerAction::SessionSummary, Language::En) => &["session summary"],
+            (TriggerAction::SessionSummary, Language::Tr) => &["oturum ozet"],
+        };
+        phrases.iter().map(|p| p.to_string()).collect()
+    }
+
+    fn default_instructions(self) -> &'static str {
+        match self {
+            TriggerAction::CreateRoadmap => "Analyze the project state and write a roadmap to ~/.seslog/projects/{slug}/roadmap.md. Use this exact format:\n## Phase Name\n- [x] Completed item\n- [>] Active item (currently working on)\n- [ ] Pending item\n- [~] Suspended item\n- [!] Blocked item\n\nOptional attributes for dependency tracking:\n- [ ] Train model {id: train}\n- [ ] Evaluate {id: eval, depends: train}\nIMPORTANT: Never remove or modify existing {id:...} or {depends:...} attributes.",
+            TriggerAction::SessionSummary => "1. Summarize what was done and remaining work in 3-4 sentences in {language}, then run: seslog summary \"<your summary>\"\n2. If ~/.seslog/projects/{slug}/roadmap.md exists, update it: mark completed items as [x], the currently active item as [>], and pending items as [ ]. Write the updated file directly.\nIf the seslog MCP tools are available, use record_summary and update_roadmap_item instead of the command and the file edit.",
+        }
+    }
+
+    fn overrides(self, config: &TriggerConfig) -> &TriggerOverride {
+        match self {
+            TriggerAction::CreateRoadmap => &config.roadmap,
+            TriggerAction::SessionSummary => &config.summary,
+        }
+    }
+}
+
+/// One trigger with its configured phrases and instructions, ready to render.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trigger {
+    pub action: TriggerAction,
+    pub phrases: Vec<String>,
+    pub instructions: String,
+}
+
+impl Trigger {
+    /// `When the user says 'a' or 'b' (or runs /cmd):` followed by the instructions.
+    pub fn render(&self) -> String {
+        let said: Vec<String> = self.phrases.iter().map(|p| format!("'{}'", p)).collect();
+        let lead = if said.is_empty() {
+            format!("When the user runs /{}:", self.action.command())
+        } else {
+            format!("When the user says {} (or runs /{}):", said.join(" or "), self.action.command())
+        };
+        format!("{}\n{}", lead, self.instructions)
+    }
+}
+
+/// Resolve `action` for `slug`, applying the overrides in `config` over the language defaults.
+pub fn resolve(config: &TriggerConfig, action: TriggerAction, slug: &str) -> Trigger {
+    let overrides = action.overrides(config);
+    let phrases = if overrides.phrases.is_empty() {
+        action.default_phrases(config.language)
+    } else {
+        overrides.phrases.clone()
+    };
+    let template = if overrides.instructions.trim().is_empty() {
+        action.default_instructions()
+    } else {
+        overrides.instructions.as_str()
+    };
+    let instructions = template
+        .replace("{slug}", slug)
+        .replace("{language}", config.language.name());
+    Trigger { action, phrases, instructions }
+}
+
+/// Body of the slash command file for `action`. The slug is not known at install time,
+/// so Claude is pointed at the session-start context for it.
+pub fn slash_command(config: &TriggerConfig, action: TriggerAction) -> String {
+    let trigger = resolve(config, action, "<slug>");
+    format!(
+        "---\ndescription: {}\n---\n{}\nThe project's <slug> is shown in the [Seslog] project context from session start.\n\n{}\n",
+        action.description(), MANAGED_MARKER, trigger.instructions,
+    )
+}
+
+/// Write the Seslog slash commands into `commands_dir`. User-written files with the
+/// same name are left alone. Returns the paths written.
+pub fn install_slash_commands(commands_dir: &Path, config: &TriggerConfig) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(commands_dir)?;
+    let mut written = Vec::new();
+    for action in TriggerAction::ALL {
+        let path = commands_dir.join(format!("{}.md", action.command()));
+        if path.exists() && !is_managed(&path) {
+            continue;
+        }
+        crate::storage::atomic_write(&path, slash_command(config, action).as_bytes())?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Remove the slash commands Seslog wrote. Returns how many were removed.
+pub fn remove_slash_commands(commands_dir: &Path) -> Result<usize> {
+    let mut removed = 0;
+    for action in TriggerAction::ALL {
+        let path = commands_dir.join(format!("{}.md", action.command()));
+        if path.exists() && is_managed(&path) {
+            std::fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+fn is_managed(path: &Path) -> bool {
+    std::fs::read_to_string(path).map(|c| c.contains(MANAGED_MARKER)).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_language_defaults() {
+        let en = resolve(&TriggerConfig::default(), TriggerAction::SessionSummary, "api");
+        assert_eq!(en.phrases, vec!["session summary"]);
+        assert!(en.instructions.contains("in English"));
+        assert!(en.instructions.contains("~/.seslog/projects/api/roadmap.md"));
+        assert!(en.render().starts_with("When the user says 'session summary' (or runs /seslog-summary):\n1."));
+
+        let tr = TriggerConfig { language: Language::Tr, ..TriggerConfig::default() };
+        assert_eq!(resolve(&tr, TriggerAction::CreateRoadmap, "api").phrases, vec!["yol haritasi olustur"]);
+        assert!(resolve(&tr, TriggerAction::SessionSummary, "api").instructions.contains("in Turkish"));
+        assert!(resolve(&tr, TriggerAction::CreateRoadmap, "api").instructions.contains("{id: train}"));
+    }
+
+    #[test]
+    fn test_overrides_replace_defaults() {
+        let config = TriggerConfig {
+            summary: TriggerOverride {
+                phrases: vec!["wrap up".into(), "fin".into()],
+                instructions: "Run seslog summary for {slug}.".into(),
+            },
+            ..TriggerConfig::default()
+        };
+        let trigger = resolve(&config, TriggerAction::SessionSummary, "api");
+        assert_eq!(trigger.render(), "When the user says 'wrap up' or 'fin' (or runs /seslog-summary):\nRun seslog summary for api.");
+        assert_eq!(resolve(&config, TriggerAction::CreateRoadmap, "api").phrases, vec!["create roadmap"]);
+    }
+
+    #[test]
+    fn test_install_and_remove_slash_commands() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("seslog-roadmap.md"), "my own command").unwrap();
+        let written = install_slash_commands(tmp.path(), &TriggerConfig::default()).unwrap();
+        assert_eq!(written, vec![tmp.path().join("seslog-summary.md")]);
+        let body = std::fs::read_to_string(&written[0]).unwrap();
+        assert!(body.starts_with("---\ndescription: "));
+        assert!(body.contains("seslog summary"));
+
+        // Reinstalling rewrites managed files; removal keeps the user's own.
+        assert_eq!(install_slash_commands(tmp.path(), &TriggerConfig::default()).unwrap().len(), 1);
+        assert_eq!(remove_slash_commands(tmp.path()).unwrap(), 1);
+        assert!(!tmp.path().join("seslog-summary.md").exists());
+        assert_eq!(std::fs::read_to_string(tmp.path().join("seslog-roadmap.md")).unwrap(), "my own command");
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use seslog_core::decisions::{self, Decision, DecisionSource};
+use std::path::Path;
+
+/// Build a manual decision, tied to the in-flight session of the project when there is one.
+pub fn build_decision(
+    active_dir: &Path,
+    slug: &str,
+    title: &str,
+    why: &str,
+    sanitizer: Option<&seslog_core::sanitize::Sanitizer>,
+) -> Decision {
+    let clean = |s: &str| match sanitizer {
+        Some(san) => san.sanitize(s).text,
+        None => s.to_string(),
+    };
+    Decision {
+        date: chrono::Utc::now(),
+        title: clean(title.trim()),
+        why: clean(why.trim()),
+        session_id: seslog_core::active_session::find_marker_for_project(active_dir, slug)
+            .map(|m| format!("ses_{}", m.session_id)),
+        source: DecisionSource::Manual,
+    }
+}
+
+/// Decision command: seslog decision "title" --why "..."
+/// Appends to the current project's decisions.jsonl.
+pub fn run(title: &str, why: &str) -> Result<()> {
+    if title.trim().is_empty() {
+        anyhow::bail!("decision title must not be empty");
+    }
+    let cwd = std::env::current_dir()?;
+    let slug = crate::utils::resolve_project_slug(&cwd.to_string_lossy());
+
+    let base = seslog_core::storage::seslog_dir()?;
+    let config = seslog_core::config::load_config(&base.join("config.toml")).unwrap_or_default();
+    let sanitizer = config.sanitize_secrets.then(|| crate::utils::load_sanitizer(&config));
+    let decision = build_decision(&base.join(".active"), &slug, title, why, sanitizer.as_ref());
+    decisions::append_decision(&base.join("projects").join(&slug), &decision)?;
+    eprintln!("[Seslog] Decision recorded for {}", slug);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use seslog_core::models::ActiveSession;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_build_decision_links_active_session() {
+        let tmp = TempDir::new().unwrap();
+        let active = tmp.path().join(".active");
+        seslog_core::active_session::write_marker_to(&active, &ActiveSession {
+            schema_version: seslog_core::models::SCHEMA_VERSION,
+            session_id: "abc-123".into(),
+            project_slug: "api".into(),
+            cwd: "/work/api".into(),
+            machine: "mac".into(),
+            started_at: chrono::Utc::now(),
+            transcript_path: String::new(),
+            git_start: None,
+            pending_summary: None,
+            pending_next_steps: None,
+        }).unwrap();
+
+        let d = build_decision(&active, "api", " Use JSONL ", "appends merge cleanly", None);
+        assert_eq!(d.title, "Use JSONL");
+        assert_eq!(d.session_id.as_deref(), Some("ses_abc-123"));
+        assert_eq!(d.source, DecisionSource::Manual);
+        assert!(build_decision(&active, "web", "x", "", None).session_id.is_none());
+    }
+}
@@ -19,7 +19,7 @@ impl fmt::Display for CheckResult {
 
 pub fn run() -> Result<()> {
     eprintln!("seslog doctor report:");
-    let checks = vec![check_data_dir(), check_config(), check_hooks_registered(), check_quarantine()];
+    let checks = vec![check_data_dir(), check_config(), check_hooks_registered(), check_quarantine(), check_encryption(), check_pending_push()];
     let mut has_fail = false;
     for check in &checks {
         eprintln!("{}", check);
@@ -43,7 +43,10 @@ fn check_config() -> CheckResult {
         Err(_) => return CheckResult::Fail("Cannot find config".into()),
     };
     match seslog_core::config::load_config(&config_path) {
-        Ok(_) => CheckResult::Ok("Config: valid".into()),
+        Ok(cfg) => match seslog_core::sanitize::Sanitizer::from_config(&cfg.redaction) {
+            Ok(_) => CheckResult::Ok("Config: valid".into()),
+            Err(e) => CheckResult::Warn(format!("Config: {} (built-in rules only)", e)),
+        },
         Err(e) => CheckResult::Fail(format!("Config: {}", e)),
     }
 }
@@ -77,6 +80,32 @@ fn check_quarantine() -> CheckResult {
     }
 }
 
+fn check_encryption() -> CheckResult {
+    let dir = match seslog_core::storage::seslog_dir() {
+        Ok(d) => d,
+        Err(_) => return CheckResult::Warn("Cannot check encryption".into()),
+    };
+    match seslog_core::encryption::Keyring::load(&dir) {
+        Ok(Some(keyring)) => CheckResult::Ok(format!("Encryption: on ({} recipient(s))", keyring.recipients().len())),
+        Ok(None) => CheckResult::Ok("Encryption: off".into()),
+        Err(e) => CheckResult::Fail(format!("Encryption: {} (sync will not push)", e)),
+    }
+}
+
+fn check_pending_push() -> CheckResult {
+    let dir = match seslog_core::storage::seslog_dir() {
+        Ok(d) => d,
+        Err(_) => return CheckResult::Warn("Cannot check sync state".into()),
+    };
+    match seslog_core::sync_queue::load_pending(&dir) {
+        Some(p) => CheckResult::Warn(format!(
+            "Sync: push pending since {} ({} attempt(s)): {}",
+            p.first_failed_at.format("%Y-%m-%d %H:%M"), p.attempts, p.last_error
+        )),
+        None => CheckResult::Ok("Sync: nothing waiting to be pushed".into()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,132 @@
+use anyhow::Result;
+use clap::Subcommand;
+use seslog_core::config::{self, LocalConfig, LOCAL_CONFIG_FILE};
+use seslog_core::encryption::{self, Keyring};
+use std::path::Path;
+
+#[derive(Subcommand)]
+pub enum EncryptionCommand {
+    /// Create this machine's age key and turn encryption on
+    Init {
+        /// Public key (age1...) of another machine that should read synced records
+        #[arg(long = "recipient")]
+        recipients: Vec<String>,
+    },
+    /// Let another machine read records written from now on (run `rekey` for older ones)
+    AddRecipient { recipient: String },
+    /// Show whether encryption is on, the key file and recipients
+    Status,
+    /// Re-encrypt all session and checkpoint records to the current recipients
+    Rekey,
+    /// Decrypt all records back to plaintext and turn encryption off
+    Disable,
+}
+
+pub fn run(command: EncryptionCommand) -> Result<()> {
+    let base = seslog_core::storage::seslog_dir()?;
+    match command {
+        EncryptionCommand::Init { recipients } => init(&base, recipients),
+        EncryptionCommand::AddRecipient { recipient } => add_recipient(&base, &recipient),
+        EncryptionCommand::Status => status(&base),
+        EncryptionCommand::Rekey => {
+            let keyring = require_keyring(&base)?;
+            let count = encryption::rekey_records(&base, &keyring)?;
+            eprintln!("[seslog] Re-encrypted {} record(s) to {} recipient(s)", count, keyring.recipients().len());
+            Ok(())
+        }
+        EncryptionCommand::Disable => {
+            let keyring = require_keyring(&base)?;
+            let count = encryption::decrypt_records(&base, &keyring)?;
+            let path = base.join(LOCAL_CONFIG_FILE);
+            let mut local = config::load_local_config(&path)?;
+            local.encryption.enabled = false;
+            config::write_local_config(&path, &local)?;
+            eprintln!("[seslog] Decrypted {} record(s); encryption is off (key kept)", count);
+            Ok(())
+        }
+    }
+}
+
+/// Enable encryption in `local.toml`, generating the identity if needed, and print its public key.
+fn init(base: &Path, recipients: Vec<String>) -> Result<()> {
+    let path = base.join(LOCAL_CONFIG_FILE);
+    let mut local = config::load_local_config(&path)?;
+    local.encryption.enabled = true;
+    for r in recipients {
+        if !local.encryption.recipients.contains(&r) {
+            local.encryption.recipients.push(r);
+        }
+    }
+    let public = encryption::init_identity(base, &local.encryption)?;
+    // Validate before anything is written, so a bad recipient leaves the old config intact.
+    Keyring::from_config(base, &local.encryption)?;
+    encryption::ensure_ignored(base)?;
+    config::write_local_config(&path, &local)?;
+    eprintln!("[seslog] Encryption enabled. Session and checkpoint records are encrypted on the next sync.");
+    eprintln!("[seslog] These stay plaintext: {}", encryption::PLAINTEXT_FILES.join(", "));
+    eprintln!("[seslog] Run on your other machines:  seslog encryption add-recipient {}", public);
+    println!("{}", public);
+    Ok(())
+}
+
+fn add_recipient(base: &Path, recipient: &str) -> Result<()> {
+    let path = base.join(LOCAL_CONFIG_FILE);
+    let mut local: LocalConfig = config::load_local_config(&path)?;
+    if local.encryption.recipients.iter().any(|r| r == recipient) {
+        eprintln!("[seslog] {} is already a recipient", recipient);
+        return Ok(());
+    }
+    local.encryption.recipients.push(recipient.to_string());
+    if local.encryption.enabled {
+        Keyring::from_config(base, &local.encryption)?;
+    }
+    config::write_local_config(&path, &local)?;
+    eprintln!("[seslog] Added recipient. Run `seslog encryption rekey` to re-encrypt existing records.");
+    Ok(())
+}
+
+fn status(base: &Path) -> Result<()> {
+    let local = config::load_local_config(&base.join(LOCAL_CONFIG_FILE))?;
+    let files = encryption::record_files(base);
+    let encrypted = files.iter()
+        .filter(|p| std::fs::read(p).is_ok_and(|b| encryption::is_encrypted(&b)))
+        .count();
+    println!("Encryption: {}", if local.encryption.enabled { "on" } else { "off" });
+    println!("Identity:   {}", local.encryption.identity_file);
+    match Keyring::load(base) {
+        Ok(Some(keyring)) => {
+            for r in keyring.recipients() {
+                println!("Recipient:  {}", r);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => println!("Error:      {}", e),
+    }
+    println!("Records:    {} of {} encrypted (projects/*/sessions/, projects/*/checkpoints/)", encrypted, files.len());
+    println!("Plaintext:  {}", encryption::PLAINTEXT_FILES.join(", "));
+    Ok(())
+}
+
+fn require_keyring(base: &Path) -> Result<Keyring> {
+    Keyring::load(base)?.ok_or_else(|| anyhow::anyhow!("encryption is not enabled (run `seslog encryption init`)"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_init_is_idempotent_and_rejects_bad_recipient() {
+        let tmp = TempDir::new().unwrap();
+        init(tmp.path(), vec![]).unwrap();
+        let first = std::fs::read_to_string(tmp.path().join(".keys/identity.txt")).unwrap();
+        init(tmp.path(), vec![]).unwrap();
+        assert_eq!(std::fs::read_to_string(tmp.path().join(".keys/identity.txt")).unwrap(), first);
+
+        assert!(init(tmp.path(), vec!["age1notakey".into()]).is_err());
+        let local = config::load_local_config(&tmp.path().join(LOCAL_CONFIG_FILE)).unwrap();
+        assert!(local.encryption.enabled);
+        assert!(local.encryption.recipients.is_empty());
+    }
+}
@@ -33,11 +33,25 @@ pub fn run() -> Result<()> {
     if !config_path.exists() {
         seslog_core::config::write_config(&config_path, &seslog_core::config::AppConfig::default())?;
     }
+    seslog_core::pricing::ensure_pricing_file(&base)?;
+
+    // Slash commands for the configured triggers
+    let config = seslog_core::config::load_config(&config_path).unwrap_or_default();
+    let commands_dir = claude_settings_path()?.with_file_name("commands");
+    for path in seslog_core::triggers::install_slash_commands(&commands_dir, &config.triggers)? {
+        eprintln!("[seslog] Slash command installed: {}", path.display());
+    }
+
+    // MCP server (user scope, in ~/.claude.json)
+    let claude_json = claude_json_path()?;
+    let patched = patch_mcp_server(&read_settings(&claude_json)?, &binary_path)?;
+    seslog_core::storage::atomic_write(&claude_json, serde_json::to_string_pretty(&patched)?.as_bytes())?;
+    eprintln!("[seslog] MCP server registered: {} mcp", binary_path);
 
     // .gitignore
     let gitignore = base.join(".gitignore");
     if !gitignore.exists() {
-        seslog_core::storage::atomic_write(&gitignore, b"cache.db\n*.db-*\nqueue/\n.events/\n")?;
+        seslog_core::storage::atomic_write(&gitignore, b"cache.db\n*.db-*\nqueue/\n.events/\n.active/\n.sync/\nlocal.toml\n.keys/\n")?;
     }
 
     // Register machine
@@ -55,6 +69,12 @@ fn claude_settings_path() -> Result<PathBuf> {
     Ok(home.join(".claude").join("settings.json"))
 }
 
+/// User-scoped MCP servers live in `~/.claude.json`, not `settings.json`.
+pub fn claude_json_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("HOME not found"))?;
+    Ok(home.join(".claude.json"))
+}
+
 fn read_settings(path: &Path) -> Result<serde_json::Value> {
     match std::fs::read_to_string(path) {
         Ok(content) => Ok(serde_json::from_str(&content)?),
@@ -98,6 +118,30 @@ pub fn patch_hooks_into_settings(settings: &serde_json::Value, binary_path: &str
     patched
 }
 
+pub const MCP_SERVER_NAME: &str = "seslog";
+
+/// Add (or refresh) the `seslog` stdio server under `mcpServers`, keeping everything else.
+///
+/// A file whose root or `mcpServers` is not an object is left for the user to fix rather
+/// than overwritten.
+pub fn patch_mcp_server(claude_json: &serde_json::Value, binary_path: &str) -> Result<serde_json::Value> {
+    let mut patched = claude_json.clone();
+    let root = patched.as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("~/.claude.json is not a JSON object; MCP server not registered"))?;
+    let servers = root.entry("mcpServers").or_insert_with(|| serde_json::json!({}));
+    if servers.is_null() {
+        *servers = serde_json::json!({});
+    }
+    let servers = servers.as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("\"mcpServers\" in ~/.claude.json is not an object; MCP server not registered"))?;
+    servers.insert(MCP_SERVER_NAME.into(), serde_json::json!({
+        "type": "stdio",
+        "command": binary_path,
+        "args": ["mcp"],
+    }));
+    Ok(patched)
+}
+
 /// Check if an event entry is seslog-managed.
 /// Handles both old "ctx-lab-managed" key and new "seslog-managed" key,
 /// plus both flat and nested hook formats for backward compatibility.
@@ -272,4 +316,26 @@ mod tests {
         });
         assert!(!is_seslog_managed(&unmanaged));
     }
+
+    #[test]
+    fn test_patch_mcp_server_keeps_other_servers() {
+        let existing = serde_json::json!({
+            "numStartups": 3,
+            "mcpServers": {"github": {"type": "stdio", "command": "gh-mcp"}},
+        });
+        let patched = patch_mcp_server(&existing, "/bin/seslog").unwrap();
+        assert_eq!(patched["numStartups"], 3);
+        assert_eq!(patched["mcpServers"]["github"]["command"], "gh-mcp");
+        assert_eq!(patched["mcpServers"]["seslog"]["command"], "/bin/seslog");
+        assert_eq!(patched["mcpServers"]["seslog"]["args"], serde_json::json!(["mcp"]));
+        assert_eq!(patch_mcp_server(&patched, "/bin/seslog").unwrap(), patched);
+        assert!(patch_mcp_server(&serde_json::json!({}), "/bin/seslog").unwrap()["mcpServers"]["seslog"].is_object());
+        assert!(patch_mcp_server(&serde_json::json!({"mcpServers": null}), "/bin/seslog").unwrap()["mcpServers"]["seslog"].is_object());
+    }
+
+    #[test]
+    fn test_patch_mcp_server_rejects_non_objects() {
+        assert!(patch_mcp_server(&serde_json::json!([1, 2]), "/bin/seslog").is_err());
+        assert!(patch_mcp_server(&serde_json::json!({"mcpServers": []}), "/bin/seslog").is_err());
+    }
 }
@@ -1,14 +1,23 @@
 use clap::{Parser, Subcommand};
 
 mod checkpoint;
+mod decision;
 mod doctor;
+mod encryption;
 mod event_bridge;
 mod install;
+mod mcp;
+mod migrate_projects;
 mod process_queue;
+mod project;
+mod query;
+mod reprice;
+mod roadmap;
 mod session_end;
 mod session_start;
 mod stop;
 mod summary;
+mod template;
 mod uninstall;
 mod utils;
 
@@ -29,10 +38,88 @@ enum Commands {
     Uninstall,
     Doctor,
     ProcessQueue,
+    /// Serve Seslog tools to Claude Code over MCP (stdio)
+    Mcp,
     Summary {
         #[arg()]
         text: String,
     },
+    /// Record a decision in the current project's decision log
+    Decision {
+        title: String,
+        /// Rationale behind the decision
+        #[arg(long, default_value = "")]
+        why: String,
+    },
+    /// Project configuration
+    Project {
+        #[command(subcommand)]
+        command: project::ProjectCommand,
+    },
+    /// List sessions, newest first
+    Log {
+        /// Project slug, id or name (default: all projects)
+        project: Option<String>,
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+        #[arg(long, value_enum, default_value_t)]
+        format: query::OutputFormat,
+    },
+    /// Show one session in full
+    Show {
+        /// Session id or unique prefix
+        session: String,
+        #[arg(long, value_enum, default_value_t)]
+        format: query::OutputFormat,
+    },
+    /// List projects
+    Projects {
+        /// Include archived projects
+        #[arg(long)]
+        all: bool,
+        #[arg(long, value_enum, default_value_t)]
+        format: query::OutputFormat,
+    },
+    /// Show project roadmaps, or edit one with a subcommand
+    #[command(args_conflicts_with_subcommands = true)]
+    Roadmap {
+        #[command(subcommand)]
+        edit: Option<roadmap::RoadmapCommand>,
+        /// Project slug, id or name (default: all projects)
+        project: Option<String>,
+        #[arg(long, value_enum, default_value_t)]
+        format: query::OutputFormat,
+    },
+    /// Session, time and cost totals
+    Stats {
+        /// Time window: 7d, 12h, 2w or a YYYY-MM-DD date (default: all time)
+        #[arg(long)]
+        since: Option<String>,
+        #[arg(long, value_enum, default_value_t)]
+        format: query::OutputFormat,
+    },
+    /// Encrypt synced session and checkpoint records with age
+    Encryption {
+        #[command(subcommand)]
+        command: encryption::EncryptionCommand,
+    },
+    /// Edit and preview the CLAUDE.md block and session-start context templates
+    Template {
+        #[command(subcommand)]
+        command: template::TemplateCommand,
+    },
+    /// Recompute session costs after editing ~/.seslog/pricing.toml
+    Reprice {
+        /// Report what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Re-key project directories by git remote / root commit instead of folder name
+    MigrateProjects {
+        /// Report what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 fn main() {
@@ -46,7 +133,20 @@ fn main() {
         Commands::Uninstall => uninstall::run(),
         Commands::Doctor => doctor::run(),
         Commands::ProcessQueue => process_queue::run(),
+        Commands::Mcp => mcp::run(),
         Commands::Summary { text } => summary::run(&text),
+        Commands::Decision { title, why } => decision::run(&title, &why),
+        Commands::Project { command } => project::run(command),
+        Commands::Log { project, limit, format } => query::run_log(project.as_deref(), limit, format),
+        Commands::Show { session, format } => query::run_show(&session, format),
+        Commands::Projects { all, format } => query::run_projects(all, format),
+        Commands::Roadmap { edit: Some(command), .. } => roadmap::run(command),
+        Commands::Roadmap { edit: None, project, format } => query::run_roadmap(project.as_deref(), format),
+        Commands::Stats { since, format } => query::run_stats(since.as_deref(), format),
+        Commands::Encryption { command } => encryption::run(command),
+        Commands::Template { command } => template::run(command),
+        Commands::Reprice { dry_run } => reprice::run(dry_run),
+        Commands::MigrateProjects { dry_run } => migrate_projects::run(dry_run),
     };
     if let Err(e) = result {
         eprintln!("[seslog] ERROR: {}", e);
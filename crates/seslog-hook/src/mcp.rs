@@ -0,0 +1,315 @@
+//! `seslog mcp`: a Model Context Protocol server over stdio (newline-delimited JSON-RPC 2.0),
+//! so Claude makes structured, validated edits instead of rewriting files or shelling out.
+
+use anyhow::{Context, Result};
+use seslog_core::roadmap::{self, ItemStatus};
+use seslog_core::roadmap_edit::{self, RoadmapEdit};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+const DEFAULT_PROTOCOL_VERSION: &str = "2024-11-05";
+
+pub fn run() -> Result<()> {
+    let server = Server {
+        data_dir: seslog_core::storage::seslog_dir()?,
+        cwd: std::env::current_dir()?,
+    };
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => server.handle(&message),
+            Err(e) => Some(error_response(Value::Null, -32700, &format!("parse error: {}", e))),
+        };
+        if let Some(response) = response {
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Tool handlers resolve "the current project" from `cwd`, the directory Claude Code
+/// started the server in.
+pub struct Server {
+    pub data_dir: PathBuf,
+    pub cwd: PathBuf,
+}
+
+impl Server {
+    /// Handle one JSON-RPC message. Notifications get no response.
+    pub fn handle(&self, message: &Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": params["protocolVersion"].as_str().unwrap_or(DEFAULT_PROTOCOL_VERSION),
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "seslog", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => Ok(self.call_tool(params["name"].as_str().unwrap_or_default(), &params["arguments"])),
+            _ if method.starts_with("notifications/") => return None,
+            _ => Err((-32601, format!("method not found: {}", method))),
+        };
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, msg)) => error_response(id, code, &msg),
+        })
+    }
+
+    /// Tool failures are reported in the result with `isError`, so Claude sees the message.
+    pub fn call_tool(&self, name: &str, args: &Value) -> Value {
+        let outcome = match name {
+            "get_project_context" => self.get_project_context(args),
+            "record_summary" => self.record_summary(args),
+            "update_roadmap_item" => self.update_roadmap_item(args),
+            "add_decision" => self.add_decision(args),
+            "search_sessions" => self.search_sessions(args),
+            _ => Err(anyhow::anyhow!("unknown tool '{}'", name)),
+        };
+        match outcome {
+            Ok(text) => json!({ "content": [{ "type": "text", "text": text }] }),
+            Err(e) => json!({ "content": [{ "type": "text", "text": format!("{:#}", e) }], "isError": true }),
+        }
+    }
+
+    fn config(&self) -> seslog_core::config::AppConfig {
+        seslog_core::config::load_config(&self.data_dir.join("config.toml")).unwrap_or_default()
+    }
+
+    /// The project named by the optional `project` argument, or the one for `cwd`.
+    fn project_slug(&self, args: &Value) -> Result<String> {
+        match optional_str(args, "project") {
+            Some(q) => seslog_core::catalog::find_project(&self.data_dir, q)
+                .map(|p| p.slug)
+                .ok_or_else(|| anyhow::anyhow!("no project matching '{}'", q)),
+            None => Ok(crate::utils::resolve_project_slug(&self.cwd.to_string_lossy())),
+        }
+    }
+
+    fn get_project_context(&self, args: &Value) -> Result<String> {
+        let slug = self.project_slug(args)?;
+        let config = self.config();
+        let project_dir = self.data_dir.join("projects").join(&slug);
+        let mut input = seslog_core::context::ContextInput::load(&slug, &project_dir, &self.cwd, config.context_recent_sessions as usize);
+        if optional_str(args, "project").is_some() {
+            // The working directory's git state may belong to another project.
+            input.git = None;
+            input.uncommitted_stat = None;
+            input.uncommitted_files.clear();
+        }
+        let composer = seslog_core::context::ContextComposer::from_config(&config);
+        seslog_core::templates::render_additional_context(&self.data_dir, &composer, &input)
+    }
+
+    fn record_summary(&self, args: &Value) -> Result<String> {
+        let summary = required_str(args, "summary")?;
+        let next_steps = optional_str(args, "next_steps");
+        let slug = self.project_slug(&json!({}))?;
+
+        let config = self.config();
+        let sanitizer = config.sanitize_secrets.then(|| crate::utils::load_sanitizer(&config));
+        let clean = |s: &str| match &sanitizer {
+            Some(san) => san.sanitize(s).text,
+            None => s.to_string(),
+        };
+        let next_steps = next_steps.map(clean);
+        crate::summary::record_for_active_session(&self.data_dir, &slug, &clean(summary), next_steps.as_deref())?;
+        Ok(format!("Summary saved for {}", slug))
+    }
+
+    fn update_roadmap_item(&self, args: &Value) -> Result<String> {
+        let item = required_str(args, "item")?;
+        let status: ItemStatus = serde_json::from_value(args["status"].clone())
+            .context("status must be one of: done, active, pending, suspended, blocked")?;
+        let slug = self.project_slug(args)?;
+        let path = self.data_dir.join("projects").join(&slug).join("roadmap.md");
+        if !path.exists() {
+            anyhow::bail!("project '{}' has no roadmap yet", slug);
+        }
+        let edit = RoadmapEdit::SetStatus { item: item.to_string(), status: status.clone() };
+        let updated = roadmap_edit::edit_file(&path, &edit)?;
+        Ok(format!("Marked '{}' as {} ({}% done)", item, status.as_str(), roadmap::progress_percent(&updated)))
+    }
+
+    fn add_decision(&self, args: &Value) -> Result<String> {
+        let title = required_str(args, "title")?;
+        let why = optional_str(args, "why").unwrap_or_default();
+        let slug = self.project_slug(&json!({}))?;
+        let config = self.config();
+        let sanitizer = config.sanitize_secrets.then(|| crate::utils::load_sanitizer(&config));
+        let decision = crate::decision::build_decision(&self.data_dir.join(".active"), &slug, title, why, sanitizer.as_ref());
+        seslog_core::decisions::append_decision(&self.data_dir.join("projects").join(&slug), &decision)?;
+        Ok(format!("Decision recorded for {}", slug))
+    }
+
+    fn search_sessions(&self, args: &Value) -> Result<String> {
+        let query = required_str(args, "query")?;
+        let slug = optional_str(args, "project").map(|_| self.project_slug(args)).transpose()?;
+        let limit = args["limit"].as_u64().unwrap_or(10).clamp(1, 50) as usize;
+        let hits = seslog_core::catalog::search_sessions(&self.data_dir, query, slug.as_deref(), limit);
+        if hits.is_empty() {
+            return Ok(format!("No sessions match '{}'", query));
+        }
+        Ok(hits.iter().map(|(project, s)| {
+            let mut line = format!("{} {} {}: {}", s.started_at.format("%Y-%m-%d"), project.slug, s.id,
+                s.summary.lines().next().unwrap_or_default());
+            if !s.next_steps.is_empty() {
+                line.push_str(&format!(" (next: {})", s.next_steps.lines().next().unwrap_or_default()));
+            }
+            line
+        }).collect::<Vec<_>>().join("\n"))
+    }
+}
+
+fn required_str<'a>(args: &'a Value, key: &str) -> Result<&'a str> {
+    optional_str(args, key).ok_or_else(|| anyhow::anyhow!("'{}' is required and must be a non-empty string", key))
+}
+
+fn optional_str<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key).and_then(|v| v.as_str()).map(str::trim).filter(|s| !s.is_empty())
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn tool_definitions() -> Value {
+    let project = json!({ "type": "string", "description": "Project slug, id or name (default: the current project)" });
+    json!([
+        {
+            "name": "get_project_context",
+            "description": "Last session, active roadmap step, pending next steps, blocked items, recent decisions and git state of a project.",
+            "inputSchema": { "type": "object", "properties": { "project": project } },
+        },
+        {
+            "name": "record_summary",
+            "description": "Store a summary of this session (3-4 sentences) and optionally the next steps.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "summary": { "type": "string" },
+                    "next_steps": { "type": "string" },
+                },
+                "required": ["summary"],
+            },
+        },
+        {
+            "name": "update_roadmap_item",
+            "description": "Set the status of one roadmap item, found by its {id: ...} or exact text.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "item": { "type": "string", "description": "Item id or exact item text" },
+                    "status": { "type": "string", "enum": ["done", "active", "pending", "suspended", "blocked"] },
+                    "project": project,
+                },
+                "required": ["item", "status"],
+            },
+        },
+        {
+            "name": "add_decision",
+            "description": "Append a decision and its rationale to the project's decision log.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "why": { "type": "string" },
+                },
+                "required": ["title"],
+            },
+        },
+        {
+            "name": "search_sessions",
+            "description": "Find past sessions whose summary, next steps, highlights, decisions or commits mention a phrase.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "project": project,
+                    "limit": { "type": "integer", "minimum": 1, "maximum": 50 },
+                },
+                "required": ["query"],
+            },
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn server() -> (TempDir, Server) {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("projects").join("api");
+        std::fs::create_dir_all(dir.join("sessions")).unwrap();
+        std::fs::write(dir.join("meta.toml"), "schema_version = 1\n\n[project]\nid = \"proj_api\"\nname = \"API\"\nstatus = \"active\"\ncreated_at = \"2026-01-01T00:00:00Z\"\n\n[paths]\n").unwrap();
+        std::fs::write(dir.join("roadmap.md"), "## Phase 1\n- [x] Auth\n- [>] Billing {id: billing}\n- [ ] Invoices\n").unwrap();
+        let session = json!({
+            "schema_version": 1, "id": "ses_1", "project_id": "proj_api", "machine": "mac",
+            "started_at": "2026-01-10T10:00:00Z", "summary": "Added invoice export",
+        });
+        std::fs::write(dir.join("sessions").join("ses_1.json"), session.to_string()).unwrap();
+        let server = Server { data_dir: tmp.path().to_path_buf(), cwd: tmp.path().to_path_buf() };
+        (tmp, server)
+    }
+
+    fn text(result: &Value) -> &str {
+        result["content"][0]["text"].as_str().unwrap()
+    }
+
+    #[test]
+    fn test_initialize_and_list_tools() {
+        let (_tmp, server) = server();
+        let init = server.handle(&json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"protocolVersion": "2025-06-18"}})).unwrap();
+        assert_eq!(init["result"]["protocolVersion"], "2025-06-18");
+        assert_eq!(init["result"]["serverInfo"]["name"], "seslog");
+        assert!(server.handle(&json!({"jsonrpc": "2.0", "method": "notifications/initialized"})).is_none());
+
+        let list = server.handle(&json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"})).unwrap();
+        let names: Vec<&str> = list["result"]["tools"].as_array().unwrap().iter().map(|t| t["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["get_project_context", "record_summary", "update_roadmap_item", "add_decision", "search_sessions"]);
+
+        let unknown = server.handle(&json!({"jsonrpc": "2.0", "id": 3, "method": "resources/list"})).unwrap();
+        assert_eq!(unknown["error"]["code"], -32601);
+    }
+
+    #[test]
+    fn test_update_roadmap_item_validates() {
+        let (tmp, server) = server();
+        let ok = server.call_tool("update_roadmap_item", &json!({"item": "billing", "status": "done", "project": "api"}));
+        assert_eq!(text(&ok), "Marked 'billing' as done (67% done)");
+        let roadmap = std::fs::read_to_string(tmp.path().join("projects/api/roadmap.md")).unwrap();
+        assert!(roadmap.contains("- [x] Billing {id: billing}"));
+
+        let bad_status = server.call_tool("update_roadmap_item", &json!({"item": "Invoices", "status": "finished", "project": "api"}));
+        assert_eq!(bad_status["isError"], true);
+        assert!(text(&bad_status).contains("status must be one of"));
+        let missing = server.call_tool("update_roadmap_item", &json!({"item": "Deploy", "status": "done", "project": "api"}));
+        assert!(text(&missing).contains("no roadmap item"));
+        assert_eq!(server.call_tool("update_roadmap_item", &json!({"status": "done"}))["isError"], true);
+    }
+
+    #[test]
+    fn test_search_and_context_tools() {
+        let (_tmp, server) = server();
+        let hits = server.call_tool("search_sessions", &json!({"query": "invoice", "project": "api"}));
+        assert_eq!(text(&hits), "2026-01-10 api ses_1: Added invoice export");
+        assert!(text(&server.call_tool("search_sessions", &json!({"query": "nothing"}))).starts_with("No sessions"));
+
+        let context = server.call_tool("get_project_context", &json!({"project": "API"}));
+        assert!(text(&context).contains("Last session: Added invoice export"));
+        assert!(text(&context).contains("Active roadmap step: Billing"));
+        assert_eq!(server.call_tool("nope", &json!({}))["isError"], true);
+    }
+}
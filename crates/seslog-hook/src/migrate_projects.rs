@@ -0,0 +1,38 @@
+use anyhow::Result;
+
+/// Migrate basename-slug project directories to identity-keyed ones.
+/// `seslog migrate-projects [--dry-run]`
+pub fn run(dry_run: bool) -> Result<()> {
+    let base = seslog_core::storage::seslog_dir()?;
+    let hostname = hostname::get()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "unknown".into());
+    let transcripts_root = dirs::home_dir()
+        .ok_or_else(|| anyhow::anyhow!("HOME directory not found"))?
+        .join(".claude")
+        .join("projects");
+
+    let report = seslog_core::project_identity::migrate_projects(&base, &hostname, &transcripts_root, dry_run)?;
+
+    if dry_run {
+        eprintln!("[seslog] Dry run, nothing written.");
+    }
+    for (slug, key) in &report.identified {
+        eprintln!("  identify  {} -> {}", slug, key);
+    }
+    for (file, from, to) in &report.split {
+        eprintln!("  split     {} : {} -> {}", file, from, to);
+    }
+    for (from, into) in &report.merged {
+        eprintln!("  merge     {} -> {}", from, into);
+    }
+    for warning in &report.warnings {
+        eprintln!("  [WARN]    {}", warning);
+    }
+    if report.identified.is_empty() && report.split.is_empty() && report.merged.is_empty() {
+        eprintln!("[seslog] Projects already up to date.");
+    } else if !dry_run {
+        eprintln!("[seslog] Migration complete. Merged directories are backed up in {}", base.join(".migrated").display());
+    }
+    Ok(())
+}
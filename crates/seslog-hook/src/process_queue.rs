@@ -39,14 +39,15 @@ fn process_checkpoint(payload: serde_json::Value) -> Result<()> {
     let cwd_path = std::path::Path::new(cwd);
 
     let base = seslog_core::storage::seslog_dir()?;
-    let slug = crate::utils::project_slug_from_cwd(cwd);
+    let config = seslog_core::config::load_config(&base.join("config.toml")).unwrap_or_default();
+    let slug = crate::utils::resolve_project_slug(cwd);
     let checkpoints_dir = base.join("projects").join(&slug).join("checkpoints");
     std::fs::create_dir_all(&checkpoints_dir)?;
 
     let now = chrono::Utc::now();
     let chk_id = format!("chk_{}", &uuid::Uuid::new_v4().to_string()[..8]);
 
-    let checkpoint = seslog_core::models::Checkpoint {
+    let mut checkpoint = seslog_core::models::Checkpoint {
         schema_version: seslog_core::models::SCHEMA_VERSION,
         id: chk_id.clone(),
         session_id: format!("ses_{}", session_id),
@@ -58,8 +59,11 @@ fn process_checkpoint(payload: serde_json::Value) -> Result<()> {
         git_diff_stat: seslog_core::git_ops::diff_stat(cwd_path).unwrap_or(None),
         files_changed: seslog_core::git_ops::changed_files(cwd_path).unwrap_or_default(),
         recent_commits: seslog_core::git_ops::recent_commits(cwd_path, 3).unwrap_or_default(),
+        git_context: seslog_core::git_ops::git_context(cwd_path),
         source: "postToolUse_debounced".into(),
     };
+    let hash_key = seslog_core::privacy::HashKey::for_mode(&base, config.privacy_mode)?;
+    seslog_core::privacy::scrub_checkpoint(&mut checkpoint, config.privacy_mode, &hash_key);
 
     let path = checkpoints_dir.join(format!(
         "{}_{}.json",
@@ -68,6 +72,13 @@ fn process_checkpoint(payload: serde_json::Value) -> Result<()> {
     ));
     seslog_core::storage::write_json(&path, &checkpoint)?;
     eprintln!("[seslog] Checkpoint created: {}", chk_id);
+
+    // Parse what the transcript gained since the last pass so enrichment only reads the tail.
+    if let Some(transcript_path) = payload["transcript_path"].as_str() {
+        if let Err(e) = seslog_core::transcript::update_state(&base, session_id, std::path::Path::new(transcript_path)) {
+            eprintln!("[seslog] WARN: transcript parse failed: {}", e);
+        }
+    }
     Ok(())
 }
 
@@ -94,36 +105,83 @@ fn process_session_enrichment(payload: serde_json::Value) -> Result<()> {
     let base = seslog_core::storage::seslog_dir()?;
     let config = seslog_core::config::load_config(&base.join("config.toml"))?;
 
-    // Parse transcript
-    let highlights = seslog_core::transcript::extract_highlights(
-        std::path::Path::new(transcript_path),
+    // Reconcile start/end with the in-flight marker and the full transcript.
+    // Without a marker, a non-zero duration means session_end already had a real start.
+    let session_id = payload["session_id"].as_str().unwrap_or("");
+    let marker = seslog_core::active_session::read_marker(session_id).unwrap_or(None);
+    let known_start = marker.map(|m| m.started_at).or_else(|| {
+        (session.duration_minutes.unwrap_or(0) > 0).then_some(session.started_at)
+    });
+    // Picks up where checkpoints and session_end left off.
+    let transcript = seslog_core::transcript::update_state(&base, session_id, std::path::Path::new(transcript_path))
+        .unwrap_or_default();
+    let timestamps = transcript.timestamps;
+    let timing = seslog_core::active_session::resolve_timing(
+        known_start,
+        session.ended_at.unwrap_or_else(chrono::Utc::now),
+        seslog_core::transcript::timestamp_bounds(&timestamps),
+    );
+    session.started_at = timing.started_at;
+    session.ended_at = Some(timing.ended_at);
+    session.duration_minutes = Some(timing.duration_minutes);
+    session.active_minutes = seslog_core::active_session::active_minutes_in(
+        &timestamps,
+        &timing,
+        config.idle_threshold_minutes,
+    )
+    .or(session.active_minutes);
+
+    // Highlights cover the whole transcript; the caps only apply to what is stored.
+    let highlights = seslog_core::transcript::highlights_or_fallback(
+        transcript.highlights,
         std::path::Path::new(cwd),
-        config.transcript_max_messages as usize,
-        (config.transcript_max_tokens * 4) as usize,
     );
 
     // Build structured summary from transcript highlights (must borrow before partial moves)
     let transcript_summary = seslog_core::transcript::build_summary(&highlights);
 
-    // Token count and cost estimation
-    let total_tokens = highlights.total_input_tokens + highlights.total_output_tokens;
-    if total_tokens > 0 {
-        session.token_count = Some(total_tokens);
-        session.estimated_cost_usd = Some(
-            seslog_core::transcript::estimate_cost_usd(
-                highlights.total_input_tokens,
-                highlights.total_output_tokens,
-                highlights.model.as_deref(),
-            )
+    // Delegated work, priced below along with the rest of the session
+    session.subagents = highlights.subagents.clone();
+
+    // Token count and cost estimation, priced turn by turn
+    let usage = highlights.usage;
+    if usage.total() > 0 {
+        let pricing = seslog_core::pricing::load_pricing(&base).unwrap_or_else(|e| {
+            eprintln!("[seslog] WARN: {}, using built-in prices", e);
+            seslog_core::pricing::PricingTable::default()
+        });
+        session.token_count = Some(usage.total());
+        session.token_usage = Some(usage);
+        // The record is synced: long sessions keep their latest turns, older ones as totals
+        session.usage_turns = seslog_core::transcript::cap_usage_turns(
+            &highlights.turns,
+            seslog_core::transcript::STORED_USAGE_TURNS,
         );
+        session.model = highlights.model.clone();
+        seslog_core::pricing::reprice_session(&mut session, &pricing);
     }
-    session.model = highlights.model.clone();
+    // The model that did most of the work, not just the first one seen.
+    session.model = session.model_breakdown().first().map(|m| m.model.clone()).or(highlights.model.clone());
 
     session.tools_used = highlights.tools_used;
-    session.transcript_highlights = highlights.user_messages;
+    session.tool_stats = highlights.tool_stats;
+    // Relative to the repository root, like the paths in `git_range`.
+    let root = seslog_core::git_ops::git_context(std::path::Path::new(cwd))
+        .and_then(|c| c.worktree)
+        .unwrap_or_else(|| cwd.to_string());
+    session.touched_files = seslog_core::transcript::relativize_touched_files(
+        highlights.touched_files,
+        std::path::Path::new(&root),
+    );
+    session.transcript_highlights = seslog_core::transcript::cap_for_storage(
+        &highlights.user_messages,
+        config.transcript_max_messages as usize,
+        (config.transcript_max_tokens * 4) as usize,
+    );
 
     // Manual summary (from `seslog summary`) takes priority
-    if session.summary_source != Some(seslog_core::models::SummarySource::Manual)
+    if config.privacy_mode.keeps_transcript_text()
+        && session.summary_source != Some(seslog_core::models::SummarySource::Manual)
         && !transcript_summary.what_was_done.is_empty()
     {
         session.summary = transcript_summary.what_was_done;
@@ -133,40 +191,56 @@ fn process_session_enrichment(payload: serde_json::Value) -> Result<()> {
     if session.next_steps.is_empty() {
         session.next_steps = transcript_summary.next_steps;
     }
+    session.decisions = transcript_summary.decisions;
+
+    // Drop whatever the privacy mode does not allow before anything is sanitized or written.
+    let hash_key = seslog_core::privacy::HashKey::for_mode(&base, config.privacy_mode)?;
+    seslog_core::privacy::scrub_session(&mut session, config.privacy_mode, &hash_key);
 
     // Sanitize
     if config.sanitize_secrets {
-        let sanitized = seslog_core::sanitize::sanitize(&session.summary);
-        session.summary = sanitized.text;
-        session.redaction_count = sanitized.redaction_count;
-        session.transcript_highlights = session
-            .transcript_highlights
-            .into_iter()
-            .map(|h| seslog_core::sanitize::sanitize(&h).text)
-            .collect();
-        let sanitized_next = seslog_core::sanitize::sanitize(&session.next_steps);
-        session.next_steps = sanitized_next.text;
-        session.redaction_count += sanitized_next.redaction_count;
+        let sanitizer = crate::utils::load_sanitizer(&config);
+        let mut count = 0u32;
+        let mut hits = Vec::new();
+        let mut clean = |text: &str| {
+            let result = sanitizer.sanitize(text);
+            count += result.redaction_count;
+            seslog_core::sanitize::merge_hits(&mut hits, result.patterns_found);
+            result.text
+        };
+        session.summary = clean(&session.summary);
+        session.next_steps = clean(&session.next_steps);
+        session.transcript_highlights = session.transcript_highlights.iter().map(|h| clean(h)).collect();
+        session.decisions = session.decisions.iter().map(|d| clean(d)).collect();
+        for subagent in &mut session.subagents {
+            subagent.description = clean(&subagent.description);
+            subagent.summaries = subagent.summaries.iter().map(|s| clean(s)).collect();
+        }
+        session.redaction_count = count;
+        session.redactions = hits;
     }
 
     // Update CLAUDE.md
-    let slug = crate::utils::project_slug_from_cwd(cwd);
+    let slug = crate::utils::resolve_project_slug(cwd);
     let roadmap_path = base.join("projects").join(&slug).join("roadmap.md");
     let roadmap_content = std::fs::read_to_string(&roadmap_path).unwrap_or_default();
-    let active_step = seslog_core::roadmap::active_item(&roadmap_content).map(|i| i.text);
-    let block = format!(
-        "## Project Status (auto-updated by Seslog)\n\n**Last Session:** {}\n**Summary:** {}\n{}",
-        session
-            .ended_at
-            .map_or("unknown".into(), |t| t.format("%Y-%m-%d %H:%M").to_string()),
-        session.summary,
-        active_step.map_or(String::new(), |s| format!("**Active Step:** {}", s)),
-    );
-    if let Err(e) = seslog_core::claude_md::update_claude_md(std::path::Path::new(cwd), &block) {
-        eprintln!("[seslog] WARN: update_claude_md failed: {}", e);
+    let status = seslog_core::templates::StatusData::new(&slug, Some(&session), &roadmap_content);
+    match seslog_core::templates::render_named(&base, Some(&slug), seslog_core::templates::CLAUDE_MD_BLOCK, &status) {
+        Ok(block) => {
+            if let Err(e) = seslog_core::claude_md::update_claude_md(std::path::Path::new(cwd), &block) {
+                eprintln!("[seslog] WARN: update_claude_md failed: {}", e);
+            }
+        }
+        Err(e) => eprintln!("[seslog] WARN: rendering CLAUDE.md block failed: {}", e),
     }
 
     seslog_core::storage::write_json(session_path, &session)?;
+    if let Err(e) = seslog_core::active_session::remove_marker(session_id) {
+        eprintln!("[seslog] WARN: remove_marker failed: {}", e);
+    }
+    if let Err(e) = seslog_core::transcript::remove_state(&base, session_id) {
+        eprintln!("[seslog] WARN: remove transcript state failed: {}", e);
+    }
     eprintln!("[seslog] Session enriched: {}", session.id);
     Ok(())
 }
@@ -0,0 +1,80 @@
+use anyhow::Result;
+use clap::Subcommand;
+use seslog_core::project_map::{self, MappingRule};
+
+#[derive(Subcommand)]
+pub enum ProjectCommand {
+    /// Manage directory -> project mapping rules (projects.toml)
+    Map {
+        #[command(subcommand)]
+        action: MapAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MapAction {
+    /// Add a rule, or replace the rule with the same pattern
+    Add {
+        /// Glob (e.g. "~/mono/services/*") or path prefix
+        pattern: String,
+        /// Project slug; "{name}" expands to the matched folder name
+        project: String,
+    },
+    /// Remove the rule with this pattern
+    Remove { pattern: String },
+    /// List rules in match order
+    List,
+    /// Show which project a directory maps to (defaults to the current directory)
+    Test { path: Option<String> },
+}
+
+pub fn run(command: ProjectCommand) -> Result<()> {
+    match command {
+        ProjectCommand::Map { action } => run_map(action),
+    }
+}
+
+fn run_map(action: MapAction) -> Result<()> {
+    let map_path = seslog_core::storage::seslog_dir()?.join("projects.toml");
+    let mut map = project_map::load_map(&map_path)?;
+
+    match action {
+        MapAction::Add { pattern, project } => {
+            let rule = MappingRule { pattern: pattern.clone(), project: project.clone() };
+            rule.validate()?;
+            map.add(rule);
+            project_map::write_map(&map_path, &map)?;
+            eprintln!("[seslog] Mapped {} -> {}", pattern, project);
+        }
+        MapAction::Remove { pattern } => {
+            if map.remove(&pattern) {
+                project_map::write_map(&map_path, &map)?;
+                eprintln!("[seslog] Removed rule {}", pattern);
+            } else {
+                anyhow::bail!("no rule with pattern '{}'", pattern);
+            }
+        }
+        MapAction::List => {
+            if map.rules.is_empty() {
+                eprintln!("[seslog] No mapping rules ({})", map_path.display());
+            }
+            for (i, rule) in map.rules.iter().enumerate() {
+                println!("{:>3}  {}  ->  {}", i + 1, rule.pattern, rule.project);
+            }
+        }
+        MapAction::Test { path } => {
+            let path = match path {
+                Some(p) => std::path::PathBuf::from(p),
+                None => std::env::current_dir()?,
+            };
+            match map.lookup(&path) {
+                Some(hit) => println!("{} (rule matched {})", hit.slug, hit.dir.display()),
+                None => {
+                    let identity = seslog_core::project_identity::identify(&path);
+                    println!("no rule matched; identity {} ({})", identity.key, identity.name);
+                }
+            }
+        }
+    }
+    Ok(())
+}
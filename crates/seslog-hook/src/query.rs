@@ -0,0 +1,484 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use seslog_core::catalog::{self, ProjectEntry};
+use seslog_core::models::Session;
+use std::path::Path;
+
+/// Output format for the read-only query commands (`log`, `show`, `projects`,
+/// `roadmap`, `stats`). These read `~/.seslog/projects/**` directly, so they work
+/// without the desktop app and always reflect what is on disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Markdown,
+}
+
+/// Column-aligned rows rendered as plain text or a markdown table.
+struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Markdown => {
+                let escape = |s: &str| s.replace('|', "\\|").replace('\n', " ");
+                let mut out = format!("| {} |\n", self.headers.join(" | "));
+                out.push_str(&format!("|{}\n", " --- |".repeat(self.headers.len())));
+                for row in &self.rows {
+                    let cells: Vec<String> = row.iter().map(|c| escape(c)).collect();
+                    out.push_str(&format!("| {} |\n", cells.join(" | ")));
+                }
+                out
+            }
+            _ => {
+                let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+                for row in &self.rows {
+                    for (w, cell) in widths.iter_mut().zip(row) {
+                        *w = (*w).max(cell.chars().count());
+                    }
+                }
+                let line = |cells: Vec<&str>| -> String {
+                    let padded: Vec<String> = cells.iter().zip(&widths)
+                        .map(|(c, w)| format!("{:<width$}", c, width = *w))
+                        .collect();
+                    padded.join("  ").trim_end().to_string() + "\n"
+                };
+                let mut out = line(self.headers.clone());
+                for row in &self.rows {
+                    out.push_str(&line(row.iter().map(String::as_str).collect()));
+                }
+                out
+            }
+        }
+    }
+}
+
+fn first_line(text: &str, max: usize) -> String {
+    let line = text.lines().next().unwrap_or("");
+    if line.chars().count() > max {
+        line.chars().take(max - 1).collect::<String>() + "\u{2026}"
+    } else {
+        line.to_string()
+    }
+}
+
+fn minutes(m: Option<u32>) -> String {
+    match m {
+        Some(m) if m >= 60 => format!("{}h {}m", m / 60, m % 60),
+        Some(m) => format!("{}m", m),
+        None => "-".into(),
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(value)? + "\n")
+}
+
+fn resolve_projects(data_dir: &Path, project: Option<&str>) -> Result<Vec<ProjectEntry>> {
+    match project {
+        Some(q) => catalog::find_project(data_dir, q)
+            .map(|p| vec![p])
+            .ok_or_else(|| anyhow::anyhow!("no project matching '{}'", q)),
+        None => Ok(catalog::list_projects(data_dir)),
+    }
+}
+
+/// Parse `7d`, `12h`, `2w`, `30m` or a `YYYY-MM-DD` date into a cutoff time.
+pub fn parse_since(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    let invalid = || anyhow::anyhow!("invalid --since '{}' (try 7d, 12h, 2w or 2026-01-31)", input);
+    let count = |num: &str| num.parse::<i64>().map_err(|_| invalid());
+    // Checked constructors: an absurd count is a bad argument, not a panic
+    let span = if let Some(num) = input.strip_suffix('m') {
+        Duration::try_minutes(count(num)?)
+    } else if let Some(num) = input.strip_suffix('h') {
+        Duration::try_hours(count(num)?)
+    } else if let Some(num) = input.strip_suffix('d') {
+        Duration::try_days(count(num)?)
+    } else if let Some(num) = input.strip_suffix('w') {
+        Duration::try_weeks(count(num)?)
+    } else {
+        None
+    };
+    let span = span.ok_or_else(invalid)?;
+    now.checked_sub_signed(span).ok_or_else(invalid)
+}
+
+pub fn log_output(data_dir: &Path, project: Option<&str>, limit: usize, format: OutputFormat) -> Result<String> {
+    let mut sessions: Vec<(String, Session)> = resolve_projects(data_dir, project)?
+        .into_iter()
+        .flat_map(|p| {
+            let slug = p.slug.clone();
+            catalog::load_sessions(&p.dir).into_iter().map(move |s| (slug.clone(), s))
+        })
+        .collect();
+    sessions.sort_by_key(|(_, s)| std::cmp::Reverse(s.started_at));
+    sessions.truncate(limit);
+
+    if format == OutputFormat::Json {
+        let values: Vec<serde_json::Value> = sessions.iter().map(|(slug, s)| {
+            let mut v = serde_json::to_value(s).unwrap_or_default();
+            v["project"] = serde_json::Value::String(slug.clone());
+            v
+        }).collect();
+        return to_json(&values);
+    }
+    let table = Table {
+        headers: vec!["DATE", "PROJECT", "SESSION", "DURATION", "SUMMARY"],
+        rows: sessions.iter().map(|(slug, s)| vec![
+            s.started_at.format("%Y-%m-%d %H:%M").to_string(),
+            slug.clone(),
+            s.id.trim_start_matches("ses_").chars().take(8).collect(),
+            minutes(s.duration_minutes),
+            first_line(&s.summary, 60),
+        ]).collect(),
+    };
+    Ok(table.render(format))
+}
+
+pub fn show_output(data_dir: &Path, session: &str, format: OutputFormat) -> Result<String> {
+    let (project, s) = catalog::find_session(data_dir, session)
+        .ok_or_else(|| anyhow::anyhow!("no unique session matching '{}'", session))?;
+    if format == OutputFormat::Json {
+        let mut v = serde_json::to_value(&s)?;
+        v["project"] = serde_json::Value::String(project.slug);
+        return to_json(&v);
+    }
+
+    let md = format == OutputFormat::Markdown;
+    let mut out = String::new();
+    out.push_str(&if md { format!("# {}\n\n", s.id) } else { format!("{}\n\n", s.id) });
+    let fields = [
+        ("Project", project.slug.clone()),
+        ("Machine", s.machine.clone()),
+        ("Started", s.started_at.format("%Y-%m-%d %H:%M UTC").to_string()),
+        ("Ended", s.ended_at.map(|e| e.format("%Y-%m-%d %H:%M UTC").to_string()).unwrap_or_else(|| "-".into())),
+        ("Duration", minutes(s.duration_minutes)),
+        ("Active", minutes(s.active_minutes)),
+        ("Model", s.model.clone().unwrap_or_else(|| "-".into())),
+        ("Tokens", match (s.token_count, s.token_usage) {
+            (Some(t), Some(u)) => format!(
+                "{} (input {}, output {}, cache write {}, cache read {})",
+                t, u.input, u.output, u.cache_creation, u.cache_read
+            ),
+            (Some(t), None) => t.to_string(),
+            (None, _) => "-".into(),
+        }),
+        ("Cost", s.estimated_cost_usd.map(|c| format!("${:.2}", c)).unwrap_or_else(|| "-".into())),
+    ];
+    for (label, value) in fields {
+        out.push_str(&if md { format!("- **{}:** {}\n", label, value) } else { format!("{:<10}{}\n", format!("{}:", label), value) });
+    }
+    let mut section = |title: &str, lines: Vec<String>| {
+        if lines.is_empty() { return; }
+        out.push_str(&if md { format!("\n## {}\n\n", title) } else { format!("\n{}:\n", title) });
+        for line in lines {
+            out.push_str(&if md { format!("{}\n", line) } else { format!("  {}\n", line) });
+        }
+    };
+    section("Summary", s.summary.lines().map(String::from).collect());
+    section("Next steps", s.next_steps.lines().map(String::from).collect());
+    section("Decisions", s.decisions.iter().map(|d| format!("- {}", d)).collect());
+    section("Models", s.model_breakdown().iter().map(|m| format!(
+        "- {}: {} turn(s), {} tokens, {}",
+        m.model, m.turns, m.usage.total(),
+        m.cost_usd.map(|c| format!("${:.2}", c)).unwrap_or_else(|| "-".into())
+    )).collect());
+    section("Tools", s.tool_stats.iter().map(|t| match t.errors {
+        0 => format!("- {}: {} call(s)", t.name, t.calls),
+        errors => format!("- {}: {} call(s), {} error(s)", t.name, t.calls, errors),
+    }).collect());
+    section("Files touched", s.touched_files.iter().map(|f| {
+        let counts: Vec<String> = [("read", f.reads), ("edited", f.edits), ("written", f.writes)]
+            .iter()
+            .filter(|(_, n)| *n > 0)
+            .map(|(label, n)| format!("{} {}", label, n))
+            .collect();
+        format!("- {} ({})", f.path, counts.join(", "))
+    }).collect());
+    section("Subagents", s.subagents.iter().map(|a| format!(
+        "- {}{}: {} turn(s), {} tokens, {}",
+        a.agent_type.as_deref().map(|t| format!("[{}] ", t)).unwrap_or_default(),
+        if a.description.is_empty() { a.id.as_str() } else { a.description.as_str() },
+        a.turns, a.usage.total(),
+        a.cost_usd.map(|c| format!("${:.2}", c)).unwrap_or_else(|| "-".into())
+    )).collect());
+    section("Highlights", s.transcript_highlights.iter().map(|h| format!("- {}", h)).collect());
+    section("Redactions", s.redactions.iter().map(|h| format!("- {}", h)).collect());
+    section("Commits", s.git_commits.iter().map(|c| format!("- {}", c)).collect());
+    Ok(out)
+}
+
+pub fn projects_output(data_dir: &Path, include_archived: bool, format: OutputFormat) -> Result<String> {
+    let rows: Vec<(ProjectEntry, Vec<Session>)> = catalog::list_projects(data_dir)
+        .into_iter()
+        .filter(|p| include_archived || p.meta.project.status != "archived")
+        .map(|p| {
+            let sessions = catalog::load_sessions(&p.dir);
+            (p, sessions)
+        })
+        .collect();
+
+    if format == OutputFormat::Json {
+        let values: Vec<serde_json::Value> = rows.iter().map(|(p, sessions)| serde_json::json!({
+            "slug": p.slug,
+            "id": p.meta.project.id,
+            "name": p.meta.project.name,
+            "status": p.meta.project.status,
+            "sessions": sessions.len(),
+            "total_minutes": sessions.iter().filter_map(|s| s.duration_minutes).sum::<u32>(),
+            "last_session_at": sessions.first().map(|s| s.started_at),
+        })).collect();
+        return to_json(&values);
+    }
+    let table = Table {
+        headers: vec!["PROJECT", "NAME", "STATUS", "SESSIONS", "TIME", "LAST SESSION"],
+        rows: rows.iter().map(|(p, sessions)| vec![
+            p.slug.clone(),
+            p.meta.project.name.clone(),
+            p.meta.project.status.clone(),
+            sessions.len().to_string(),
+            minutes(Some(sessions.iter().filter_map(|s| s.duration_minutes).sum())),
+            sessions.first().map(|s| s.started_at.format("%Y-%m-%d").to_string()).unwrap_or_else(|| "-".into()),
+        ]).collect(),
+    };
+    Ok(table.render(format))
+}
+
+pub fn roadmap_output(data_dir: &Path, project: Option<&str>, format: OutputFormat) -> Result<String> {
+    let roadmaps: Vec<(String, String)> = resolve_projects(data_dir, project)?
+        .into_iter()
+        .filter_map(|p| {
+            let content = std::fs::read_to_string(p.dir.join("roadmap.md")).ok()?;
+            (!content.trim().is_empty()).then_some((p.slug, content))
+        })
+        .collect();
+
+    if format == OutputFormat::Json {
+        let values: Vec<serde_json::Value> = roadmaps.iter().map(|(slug, content)| {
+            let data = seslog_core::roadmap::parse_roadmap_data(content);
+            serde_json::json!({
+                "project": slug,
+                "progress_percent": data.progress_percent,
+                "items": data.items,
+                "warnings": data.warnings,
+                "dependencies": data.dependencies,
+            })
+        }).collect();
+        return to_json(&values);
+    }
+
+    let mut out = String::new();
+    for (slug, content) in &roadmaps {
+        let progress = seslog_core::roadmap::progress_percent(content);
+        if format == OutputFormat::Markdown {
+            out.push_str(&format!("# {} ({:.0}%)\n\n{}\n", slug, progress, content.trim_end()));
+        } else {
+            out.push_str(&format!("{} ({:.0}%)\n", slug, progress));
+            for line in content.lines().filter(|l| !l.trim().is_empty()) {
+                out.push_str(&format!("  {}\n", line));
+            }
+        }
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+pub fn stats_output(data_dir: &Path, since: Option<&str>, now: DateTime<Utc>, format: OutputFormat) -> Result<String> {
+    let cutoff = since.map(|s| parse_since(s, now)).transpose()?;
+    let mut per_project = Vec::new();
+    for p in catalog::list_projects(data_dir) {
+        let sessions: Vec<Session> = catalog::load_sessions(&p.dir)
+            .into_iter()
+            .filter(|s| cutoff.is_none_or(|c| s.started_at >= c))
+            .collect();
+        if sessions.is_empty() { continue; }
+        per_project.push((p.slug, sessions));
+    }
+
+    struct Totals { sessions: usize, minutes: u32, active: u32, tokens: u64, cost: f64 }
+    let totals = |sessions: &[Session]| Totals {
+        sessions: sessions.len(),
+        minutes: sessions.iter().filter_map(|s| s.duration_minutes).sum(),
+        active: sessions.iter().filter_map(|s| s.active_minutes.or(s.duration_minutes)).sum(),
+        tokens: sessions.iter().filter_map(|s| s.token_count).sum(),
+        cost: sessions.iter().filter_map(|s| s.estimated_cost_usd).sum(),
+    };
+    let all: Vec<Session> = per_project.iter().flat_map(|(_, s)| s.clone()).collect();
+    let grand = totals(&all);
+
+    if format == OutputFormat::Json {
+        let project_values: Vec<serde_json::Value> = per_project.iter().map(|(slug, sessions)| {
+            let t = totals(sessions);
+            serde_json::json!({
+                "project": slug, "sessions": t.sessions, "total_minutes": t.minutes,
+                "active_minutes": t.active, "tokens": t.tokens, "cost_usd": t.cost,
+            })
+        }).collect();
+        return to_json(&serde_json::json!({
+            "since": cutoff,
+            "sessions": grand.sessions,
+            "total_minutes": grand.minutes,
+            "active_minutes": grand.active,
+            "tokens": grand.tokens,
+            "cost_usd": grand.cost,
+            "projects": project_values,
+        }));
+    }
+
+    let row = |name: String, t: Totals| vec![
+        name,
+        t.sessions.to_string(),
+        minutes(Some(t.minutes)),
+        minutes(Some(t.active)),
+        t.tokens.to_string(),
+        format!("${:.2}", t.cost),
+    ];
+    let mut rows: Vec<Vec<String>> = per_project.iter()
+        .map(|(slug, sessions)| row(slug.clone(), totals(sessions)))
+        .collect();
+    rows.push(row("TOTAL".into(), grand));
+    let table = Table { headers: vec!["PROJECT", "SESSIONS", "TIME", "ACTIVE", "TOKENS", "COST"], rows };
+
+    let heading = match cutoff {
+        Some(c) => format!("Since {}", c.format("%Y-%m-%d %H:%M UTC")),
+        None => "All time".to_string(),
+    };
+    Ok(match format {
+        OutputFormat::Markdown => format!("## {}\n\n{}", heading, table.render(format)),
+        _ => format!("{}\n\n{}", heading, table.render(format)),
+    })
+}
+
+fn print(output: Result<String>) -> Result<()> {
+    print!("{}", output?);
+    Ok(())
+}
+
+pub fn run_log(project: Option<&str>, limit: usize, format: OutputFormat) -> Result<()> {
+    print(log_output(&seslog_core::storage::seslog_dir()?, project, limit, format))
+}
+
+pub fn run_show(session: &str, format: OutputFormat) -> Result<()> {
+    print(show_output(&seslog_core::storage::seslog_dir()?, session, format))
+}
+
+pub fn run_projects(include_archived: bool, format: OutputFormat) -> Result<()> {
+    print(projects_output(&seslog_core::storage::seslog_dir()?, include_archived, format))
+}
+
+pub fn run_roadmap(project: Option<&str>, format: OutputFormat) -> Result<()> {
+    print(roadmap_output(&seslog_core::storage::seslog_dir()?, project, format))
+}
+
+pub fn run_stats(since: Option<&str>, format: OutputFormat) -> Result<()> {
+    print(stats_output(&seslog_core::storage::seslog_dir()?, since, Utc::now(), format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("projects").join("api");
+        std::fs::create_dir_all(dir.join("sessions")).unwrap();
+        std::fs::write(dir.join("meta.toml"), "schema_version = 1\n\n[project]\nid = \"proj_api\"\nname = \"API\"\nstatus = \"active\"\ncreated_at = \"2026-01-01T00:00:00Z\"\n\n[paths]\n").unwrap();
+        std::fs::write(dir.join("roadmap.md"), "## Phase 1\n- [x] Auth\n- [>] Billing\n").unwrap();
+        for (id, day, minutes, cost) in [("ses_old11111", "01", 30, 0.5), ("ses_new22222", "10", 90, 1.25)] {
+            let json = serde_json::json!({
+                "schema_version": 1, "id": id, "project_id": "proj_api", "machine": "mac",
+                "started_at": format!("2026-01-{}T10:00:00Z", day), "duration_minutes": minutes,
+                "summary": format!("Work on {}\nsecond line", id), "estimated_cost_usd": cost,
+                "next_steps": "Ship it",
+                "tool_stats": [{"name": "Edit", "calls": 3, "errors": 1}],
+                "touched_files": [{"path": "src/lib.rs", "reads": 1, "edits": 3}],
+                "subagents": [{"id": "agent_1", "agent_type": "Explore", "description": "Find callers", "turns": 2,
+                               "usage": {"input": 100, "output": 20}, "cost_usd": 0.01}],
+            });
+            std::fs::write(dir.join("sessions").join(format!("{}.json", id)), json.to_string()).unwrap();
+        }
+        tmp
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now: DateTime<Utc> = "2026-01-10T12:00:00Z".parse().unwrap();
+        assert_eq!(parse_since("7d", now).unwrap(), "2026-01-03T12:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert_eq!(parse_since("12h", now).unwrap(), "2026-01-10T00:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert_eq!(parse_since("2026-01-05", now).unwrap(), "2026-01-05T00:00:00Z".parse::<DateTime<Utc>>().unwrap());
+        assert!(parse_since("soon", now).is_err());
+        assert!(parse_since("", now).is_err());
+        assert!(parse_since("7é", now).is_err());
+        assert!(parse_since("é", now).is_err());
+        assert!(parse_since("99999999999999w", now).is_err());
+    }
+
+    #[test]
+    fn test_log_table_newest_first() {
+        let tmp = setup();
+        let out = log_output(tmp.path(), None, 10, OutputFormat::Table).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].starts_with("DATE"));
+        assert!(lines[1].contains("2026-01-10") && lines[1].contains("new22222"));
+        assert!(!out.contains("second line"));
+        assert_eq!(log_output(tmp.path(), Some("api"), 1, OutputFormat::Table).unwrap().lines().count(), 2);
+        assert!(log_output(tmp.path(), Some("missing"), 1, OutputFormat::Table).is_err());
+    }
+
+    #[test]
+    fn test_log_json_and_markdown() {
+        let tmp = setup();
+        let json: serde_json::Value = serde_json::from_str(&log_output(tmp.path(), None, 10, OutputFormat::Json).unwrap()).unwrap();
+        assert_eq!(json[0]["id"], "ses_new22222");
+        assert_eq!(json[0]["project"], "api");
+        let md = log_output(tmp.path(), None, 10, OutputFormat::Markdown).unwrap();
+        assert!(md.starts_with("| DATE | PROJECT |"));
+        assert!(md.lines().nth(1).unwrap().starts_with("| --- |"));
+    }
+
+    #[test]
+    fn test_show_by_prefix() {
+        let tmp = setup();
+        let out = show_output(tmp.path(), "old1", OutputFormat::Table).unwrap();
+        assert!(out.starts_with("ses_old11111"));
+        assert!(out.contains("Ship it"));
+        assert!(out.contains("second line"));
+        let md = show_output(tmp.path(), "old1", OutputFormat::Markdown).unwrap();
+        assert!(md.contains("## Next steps"));
+        assert!(out.contains("- Edit: 3 call(s), 1 error(s)"));
+        assert!(out.contains("- src/lib.rs (read 1, edited 3)"));
+        assert!(out.contains("- [Explore] Find callers: 2 turn(s), 120 tokens, $0.01"));
+        assert!(show_output(tmp.path(), "zzz", OutputFormat::Table).is_err());
+    }
+
+    #[test]
+    fn test_projects_and_roadmap() {
+        let tmp = setup();
+        let out = projects_output(tmp.path(), false, OutputFormat::Table).unwrap();
+        assert!(out.lines().nth(1).unwrap().contains("2h 0m"));
+        let json: serde_json::Value = serde_json::from_str(&roadmap_output(tmp.path(), None, OutputFormat::Json).unwrap()).unwrap();
+        assert_eq!(json[0]["progress_percent"], 50.0);
+        assert_eq!(json[0]["items"][1]["status"], "active");
+        assert_eq!(json[0]["dependencies"]["critical_path"], serde_json::json!([1]));
+    }
+
+    #[test]
+    fn test_stats_since_window() {
+        let tmp = setup();
+        let now: DateTime<Utc> = "2026-01-11T00:00:00Z".parse().unwrap();
+        let json: serde_json::Value = serde_json::from_str(&stats_output(tmp.path(), Some("7d"), now, OutputFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["sessions"], 1);
+        assert_eq!(json["total_minutes"], 90);
+        let all = stats_output(tmp.path(), None, now, OutputFormat::Table).unwrap();
+        assert!(all.starts_with("All time"));
+        assert!(all.contains("TOTAL") && all.contains("$1.75"));
+    }
+}
@@ -0,0 +1,23 @@
+use anyhow::Result;
+
+/// Recompute session costs from `pricing.toml`.
+/// `seslog reprice [--dry-run]`
+pub fn run(dry_run: bool) -> Result<()> {
+    let base = seslog_core::storage::seslog_dir()?;
+    let table = seslog_core::pricing::load_pricing(&base)?;
+    let report = seslog_core::pricing::reprice_all(&base, &table, dry_run)?;
+
+    if dry_run {
+        eprintln!("[seslog] Dry run, nothing written.");
+    }
+    let cost = |c: Option<f64>| c.map(|c| format!("${:.2}", c)).unwrap_or_else(|| "-".into());
+    for (id, old, new) in &report.changed {
+        eprintln!("  {}  {} -> {}", id, cost(*old), cost(*new));
+    }
+    eprintln!(
+        "[seslog] {} of {} sessions with token usage repriced",
+        report.changed.len(),
+        report.checked
+    );
+    Ok(())
+}
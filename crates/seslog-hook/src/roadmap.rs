@@ -0,0 +1,164 @@
+use anyhow::Result;
+use clap::Subcommand;
+use seslog_core::roadmap::ItemStatus;
+use seslog_core::roadmap_edit::{self, RoadmapEdit};
+use std::path::Path;
+
+/// Items are addressed by their `{id: ...}` or, failing that, their exact text.
+#[derive(Subcommand)]
+pub enum RoadmapCommand {
+    /// Add an item (at the end of the roadmap unless --phase or --after is given)
+    Add {
+        text: String,
+        /// Phase heading to append the item to
+        #[arg(long)]
+        phase: Option<String>,
+        /// Item (id or text) to insert after
+        #[arg(long)]
+        after: Option<String>,
+        #[arg(long)]
+        id: Option<String>,
+        /// Ids this item depends on, comma-separated
+        #[arg(long, value_delimiter = ',')]
+        depends: Vec<String>,
+        #[arg(long, default_value = "pending")]
+        status: ItemStatus,
+        /// Project slug, id or name (default: the current directory's project)
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Set an item's status: done, active, pending, suspended or blocked
+    Status {
+        item: String,
+        status: ItemStatus,
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Change an item's text, keeping its status, id and dependencies
+    Rename {
+        item: String,
+        text: String,
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Move an item to the end of a phase or after another item
+    Move {
+        item: String,
+        #[arg(long, required_unless_present = "after")]
+        phase: Option<String>,
+        #[arg(long)]
+        after: Option<String>,
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Remove an item
+    Remove {
+        item: String,
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Append a phase heading
+    Phase {
+        name: String,
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Replace an item's dependencies (none clears them)
+    Depends {
+        item: String,
+        #[arg(value_delimiter = ',')]
+        ids: Vec<String>,
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Set an item's id (none clears it)
+    Id {
+        item: String,
+        id: Option<String>,
+        #[arg(long)]
+        project: Option<String>,
+    },
+}
+
+impl RoadmapCommand {
+    fn into_edit(self) -> (Option<String>, RoadmapEdit) {
+        match self {
+            RoadmapCommand::Add { text, phase, after, id, depends, status, project } => {
+                (project, RoadmapEdit::AddItem { text, status, phase, id, depends_on: depends, after })
+            }
+            RoadmapCommand::Status { item, status, project } => (project, RoadmapEdit::SetStatus { item, status }),
+            RoadmapCommand::Rename { item, text, project } => (project, RoadmapEdit::Rename { item, text }),
+            RoadmapCommand::Move { item, phase, after, project } => (project, RoadmapEdit::Move { item, phase, after }),
+            RoadmapCommand::Remove { item, project } => (project, RoadmapEdit::Remove { item }),
+            RoadmapCommand::Phase { name, project } => (project, RoadmapEdit::AddPhase { name }),
+            RoadmapCommand::Depends { item, ids, project } => (project, RoadmapEdit::SetDependencies { item, depends_on: ids }),
+            RoadmapCommand::Id { item, id, project } => (project, RoadmapEdit::SetId { item, id }),
+        }
+    }
+}
+
+pub fn run(command: RoadmapCommand) -> Result<()> {
+    let base = seslog_core::storage::seslog_dir()?;
+    let (project, edit) = command.into_edit();
+    let slug = crate::template::resolve_slug(&base, project.as_deref())?;
+    let progress = apply(&base, &slug, &edit)?;
+    eprintln!("[seslog] Roadmap updated for {} ({}% done)", slug, progress);
+    Ok(())
+}
+
+/// Apply `edit` to `slug`'s roadmap, returning the new progress percentage.
+fn apply(base: &Path, slug: &str, edit: &RoadmapEdit) -> Result<f32> {
+    let path = base.join("projects").join(slug).join("roadmap.md");
+    let updated = roadmap_edit::edit_file(&path, edit)?;
+    Ok(seslog_core::roadmap::progress_percent(&updated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(subcommand)]
+        command: RoadmapCommand,
+    }
+
+    fn edit(args: &[&str]) -> RoadmapEdit {
+        let cli = Cli::try_parse_from(std::iter::once("roadmap").chain(args.iter().copied())).unwrap();
+        cli.command.into_edit().1
+    }
+
+    #[test]
+    fn test_commands_map_to_edits() {
+        assert_eq!(
+            edit(&["add", "Evaluate", "--id", "eval", "--depends", "train,data", "--phase", "Phase 1"]),
+            RoadmapEdit::AddItem {
+                text: "Evaluate".into(),
+                status: ItemStatus::Pending,
+                phase: Some("Phase 1".into()),
+                id: Some("eval".into()),
+                depends_on: vec!["train".into(), "data".into()],
+                after: None,
+            }
+        );
+        assert_eq!(edit(&["status", "eval", "done"]), RoadmapEdit::SetStatus { item: "eval".into(), status: ItemStatus::Done });
+        assert_eq!(edit(&["depends", "eval"]), RoadmapEdit::SetDependencies { item: "eval".into(), depends_on: vec![] });
+        assert!(Cli::try_parse_from(["roadmap", "status", "eval", "finished"]).is_err());
+        assert!(Cli::try_parse_from(["roadmap", "move", "eval"]).is_err());
+    }
+
+    #[test]
+    fn test_apply_writes_roadmap() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("projects/api")).unwrap();
+        apply(tmp.path(), "api", &edit(&["phase", "Phase 1"])).unwrap();
+        apply(tmp.path(), "api", &edit(&["add", "Auth", "--id", "auth"])).unwrap();
+        let progress = apply(tmp.path(), "api", &edit(&["status", "auth", "done"])).unwrap();
+        assert_eq!(progress, 100.0);
+        assert!(apply(tmp.path(), "api", &edit(&["add", "Billing", "--depends", "payments"])).is_err());
+        let content = std::fs::read_to_string(tmp.path().join("projects/api/roadmap.md")).unwrap();
+        assert_eq!(content, "## Phase 1\n- [x] Auth {id: auth}\n");
+    }
+}
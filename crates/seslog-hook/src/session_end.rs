@@ -14,7 +14,7 @@ pub fn run() -> Result<()> {
     let payload = parse_payload(&input)?;
 
     let base = seslog_core::storage::seslog_dir()?;
-    let slug = crate::utils::project_slug_from_cwd(&payload.cwd);
+    let slug = crate::utils::resolve_project_slug(&payload.cwd);
     let project_dir = base.join("projects").join(&slug);
     let sessions_dir = project_dir.join("sessions");
     std::fs::create_dir_all(&sessions_dir)?;
@@ -24,20 +24,58 @@ pub fn run() -> Result<()> {
         .unwrap_or_else(|_| "unknown".into());
     let now = chrono::Utc::now();
 
-    // Quick git stats
+    // Real start from the SessionStart marker, reconciled with transcript timestamps.
+    // The marker itself is removed by the enrichment pass.
+    let marker = seslog_core::active_session::read_marker(&payload.session_id).unwrap_or(None);
+    let timestamps = seslog_core::transcript::update_state(&base, &payload.session_id, std::path::Path::new(&payload.transcript_path))
+        .map(|state| state.timestamps)
+        .unwrap_or_default();
+    let timing = seslog_core::active_session::resolve_timing(
+        marker.as_ref().map(|m| m.started_at),
+        now,
+        seslog_core::transcript::timestamp_bounds(&timestamps),
+    );
+
+    // Active time ignores idle gaps; older history from a resumed transcript is excluded.
+    let config = seslog_core::config::load_config(&base.join("config.toml")).unwrap_or_default();
+    let active_minutes = seslog_core::active_session::active_minutes_in(
+        &timestamps,
+        &timing,
+        config.idle_threshold_minutes,
+    );
+
+    // Git changes since the HEAD recorded at session start. Without a start state, fall
+    // back to the uncommitted diff and the latest commits.
     let cwd_path = std::path::Path::new(&payload.cwd);
-    let diff_stat = seslog_core::git_ops::diff_stat(cwd_path).unwrap_or(None);
-    let commits = seslog_core::git_ops::recent_commits(cwd_path, 3).unwrap_or_default();
+    let git_start = marker.as_ref().and_then(|m| m.git_start.clone());
+    let git_range = git_start.and_then(|start| {
+        seslog_core::git_ops::session_range(cwd_path, start.sha.as_deref())
+            .map_err(|e| eprintln!("[seslog] WARN: session_range failed: {}", e))
+            .ok()
+    });
+    let (diff_stat, commits, files_changed) = match &git_range {
+        Some(range) => (
+            range.diff_stat(),
+            seslog_core::git_ops::commits_since(cwd_path, range.base.as_deref()).unwrap_or_default(),
+            range.files.len() as u32,
+        ),
+        None => (
+            seslog_core::git_ops::diff_stat(cwd_path).unwrap_or(None),
+            seslog_core::git_ops::recent_commits(cwd_path, 3).unwrap_or_default(),
+            seslog_core::git_ops::changed_files(cwd_path).map(|f| f.len() as u32).unwrap_or(0),
+        ),
+    };
 
     // Minimal session JSON
-    let session = seslog_core::models::Session {
+    let mut session = seslog_core::models::Session {
         schema_version: seslog_core::models::SCHEMA_VERSION,
         id: format!("ses_{}", &payload.session_id),
         project_id: crate::utils::read_project_id(&slug),
         machine: hostname,
-        started_at: now,
-        ended_at: Some(now),
-        duration_minutes: None,
+        started_at: timing.started_at,
+        ended_at: Some(timing.ended_at),
+        duration_minutes: Some(timing.duration_minutes),
+        active_minutes,
         end_reason: payload.reason.clone(),
         summary: diff_stat.unwrap_or_else(|| "Session ended".into()),
         summary_source: Some(seslog_core::models::SummarySource::Minimal),
@@ -47,20 +85,43 @@ pub fn run() -> Result<()> {
         next_steps: String::new(),
         tags: vec![],
         tools_used: vec![],
-        files_changed: 0,
+        tool_stats: vec![],
+        touched_files: vec![],
+        files_changed,
         git_commits: commits,
+        git_range,
+        git_context: seslog_core::git_ops::git_context(cwd_path),
         checkpoints_merged: vec![],
         recovered: false,
         redaction_count: 0,
+        redactions: vec![],
         token_count: None,
+        token_usage: None,
+        usage_turns: vec![],
+        subagents: vec![],
         estimated_cost_usd: None,
         model: None,
     };
 
+    // A summary recorded through the MCP server while the session ran
+    if let Some(m) = &marker {
+        if let Some(summary) = &m.pending_summary {
+            session.summary = summary.clone();
+            session.summary_source = Some(seslog_core::models::SummarySource::Manual);
+        }
+        if let Some(next) = &m.pending_next_steps {
+            session.next_steps = next.clone();
+        }
+    }
+
+    let hash_key = seslog_core::privacy::HashKey::for_mode(&base, config.privacy_mode)?;
+    seslog_core::privacy::scrub_session(&mut session, config.privacy_mode, &hash_key);
+
+    // A hashed machine name carries the `h:` marker; keep the file name portable
     let session_file = sessions_dir.join(format!(
         "{}_{}_{}.json",
         now.format("%Y%m%d"),
-        session.machine,
+        session.machine.replace(':', "-"),
         &payload.session_id
     ));
     seslog_core::storage::write_json(&session_file, &session)?;
@@ -27,12 +27,55 @@ pub fn write_manual_summary(session_path: &Path, text: &str) -> Result<()> {
     Ok(())
 }
 
+/// The record SessionEnd wrote for `session_id`, if it exists yet.
+pub fn find_session_record(sessions_dir: &Path, session_id: &str) -> Option<PathBuf> {
+    let suffix = format!("_{}.json", session_id);
+    std::fs::read_dir(sessions_dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| p.file_name().is_some_and(|n| n.to_string_lossy().ends_with(&suffix)))
+}
+
+/// Record a manual summary for the session currently running in `slug`.
+///
+/// The session is found through its in-flight marker, never by recency, so a running
+/// session cannot overwrite the previous one. Once SessionEnd has written the record the
+/// summary goes straight into it; before that it is queued on the marker and SessionEnd
+/// applies it. Returns the session id.
+pub fn record_for_active_session(data_dir: &Path, slug: &str, summary: &str, next_steps: Option<&str>) -> Result<String> {
+    let active_dir = data_dir.join(".active");
+    let mut marker = seslog_core::active_session::find_marker_for_project(&active_dir, slug)
+        .ok_or_else(|| anyhow::anyhow!("no session in progress for project '{}'", slug))?;
+
+    let sessions_dir = data_dir.join("projects").join(slug).join("sessions");
+    match find_session_record(&sessions_dir, &marker.session_id) {
+        Some(path) => {
+            write_manual_summary(&path, summary)?;
+            if let Some(next) = next_steps {
+                let mut session: seslog_core::models::Session = seslog_core::storage::safe_read_json(&path)?
+                    .ok_or_else(|| anyhow::anyhow!("failed to read session file"))?;
+                session.next_steps = next.to_string();
+                seslog_core::storage::write_json(&path, &session)?;
+            }
+        }
+        None => {
+            marker.pending_summary = Some(summary.to_string());
+            if let Some(next) = next_steps {
+                marker.pending_next_steps = Some(next.to_string());
+            }
+            seslog_core::active_session::write_marker_to(&active_dir, &marker)?;
+        }
+    }
+    Ok(marker.session_id)
+}
+
 /// Manual summary command: seslog summary "text"
 /// Finds the current project from cwd, writes summary to latest session JSON.
 pub fn run(text: &str) -> Result<()> {
     let cwd = std::env::current_dir()?;
     let cwd_str = cwd.to_string_lossy().to_string();
-    let slug = crate::utils::project_slug_from_cwd(&cwd_str);
+    let slug = crate::utils::resolve_project_slug(&cwd_str);
 
     let base = seslog_core::storage::seslog_dir()?;
     let sessions_dir = base.join("projects").join(&slug).join("sessions");
@@ -110,6 +153,68 @@ mod tests {
         assert_eq!(session["summary_source"], "manual");
     }
 
+    fn write_marker(data_dir: &Path, session_id: &str) {
+        let marker = seslog_core::models::ActiveSession {
+            schema_version: 1,
+            session_id: session_id.into(),
+            project_slug: "api".into(),
+            cwd: "/work/api".into(),
+            machine: "mac".into(),
+            started_at: chrono::Utc::now(),
+            transcript_path: String::new(),
+            git_start: None,
+            pending_summary: None,
+            pending_next_steps: None,
+        };
+        seslog_core::active_session::write_marker_to(&data_dir.join(".active"), &marker).unwrap();
+    }
+
+    #[test]
+    fn test_record_for_active_session_requires_marker() {
+        let tmp = TempDir::new().unwrap();
+        let sessions = tmp.path().join("projects/api/sessions");
+        std::fs::create_dir_all(&sessions).unwrap();
+        let previous = sessions.join("20260101_mac_old.json");
+        std::fs::write(&previous, make_session_json("previous")).unwrap();
+
+        assert!(record_for_active_session(tmp.path(), "api", "new", None).is_err());
+        let content: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&previous).unwrap()).unwrap();
+        assert_eq!(content["summary"], "previous");
+    }
+
+    #[test]
+    fn test_record_for_active_session_queues_on_marker() {
+        let tmp = TempDir::new().unwrap();
+        let sessions = tmp.path().join("projects/api/sessions");
+        std::fs::create_dir_all(&sessions).unwrap();
+        let previous = sessions.join("20260101_mac_old.json");
+        std::fs::write(&previous, make_session_json("previous")).unwrap();
+        write_marker(tmp.path(), "cur");
+
+        let id = record_for_active_session(tmp.path(), "api", "work so far", Some("ship it")).unwrap();
+        assert_eq!(id, "cur");
+        let marker = seslog_core::active_session::find_marker_for_project(&tmp.path().join(".active"), "api").unwrap();
+        assert_eq!(marker.pending_summary.as_deref(), Some("work so far"));
+        assert_eq!(marker.pending_next_steps.as_deref(), Some("ship it"));
+        let content: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&previous).unwrap()).unwrap();
+        assert_eq!(content["summary"], "previous");
+    }
+
+    #[test]
+    fn test_record_for_active_session_writes_existing_record() {
+        let tmp = TempDir::new().unwrap();
+        let sessions = tmp.path().join("projects/api/sessions");
+        std::fs::create_dir_all(&sessions).unwrap();
+        let record = sessions.join("20260101_mac_cur.json");
+        std::fs::write(&record, make_session_json("Session ended")).unwrap();
+        write_marker(tmp.path(), "cur");
+
+        record_for_active_session(tmp.path(), "api", "done", None).unwrap();
+        let content: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&record).unwrap()).unwrap();
+        assert_eq!(content["summary"], "done");
+        assert_eq!(content["summary_source"], "manual");
+    }
+
     #[test]
     fn test_write_manual_summary_missing_file() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use clap::Subcommand;
+use seslog_core::context::{ContextComposer, ContextInput};
+use seslog_core::templates::{self, StatusData, TemplateOrigin, ADDITIONAL_CONTEXT, CLAUDE_MD_BLOCK, TEMPLATE_NAMES};
+use std::path::Path;
+
+#[derive(Subcommand)]
+pub enum TemplateCommand {
+    /// Copy the built-in templates into ~/.seslog/templates/ (or a project's templates/) for editing
+    Init {
+        /// Write per-project overrides for this project (slug, id or name)
+        #[arg(long)]
+        project: Option<String>,
+        /// Overwrite templates that already exist
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show which file each template resolves to
+    List {
+        /// Project slug, id or name (default: the current directory's project)
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Render a template with a project's current data
+    Preview {
+        /// claude_md.md or additional_context.md
+        #[arg(default_value = CLAUDE_MD_BLOCK)]
+        name: String,
+        /// Project slug, id or name (default: the current directory's project)
+        #[arg(long)]
+        project: Option<String>,
+    },
+}
+
+pub fn run(command: TemplateCommand) -> Result<()> {
+    let base = seslog_core::storage::seslog_dir()?;
+    match command {
+        TemplateCommand::Init { project, force } => {
+            let dir = match project {
+                Some(q) => base.join("projects").join(resolve_slug(&base, Some(&q))?).join("templates"),
+                None => base.join("templates"),
+            };
+            let written = templates::write_defaults(&dir, force)?;
+            for path in &written {
+                eprintln!("[seslog] Wrote {}", path.display());
+            }
+            if written.is_empty() {
+                eprintln!("[seslog] Templates already exist in {} (use --force to overwrite)", dir.display());
+            }
+            Ok(())
+        }
+        TemplateCommand::List { project } => {
+            let slug = resolve_slug(&base, project.as_deref())?;
+            for name in TEMPLATE_NAMES {
+                let (_, origin) = templates::load_template(&base, Some(&slug), name)?;
+                let from = match origin {
+                    TemplateOrigin::Project(p) | TemplateOrigin::Global(p) => p.display().to_string(),
+                    TemplateOrigin::Builtin => "built-in".into(),
+                };
+                println!("{:<22} {}", name, from);
+            }
+            Ok(())
+        }
+        TemplateCommand::Preview { name, project } => {
+            let slug = resolve_slug(&base, project.as_deref())?;
+            print!("{}", preview_output(&base, &slug, &std::env::current_dir()?, &name)?);
+            Ok(())
+        }
+    }
+}
+
+/// Project slug from a slug/id/name query, or from the current directory.
+pub fn resolve_slug(base: &Path, project: Option<&str>) -> Result<String> {
+    match project {
+        Some(q) => seslog_core::catalog::find_project(base, q)
+            .map(|p| p.slug)
+            .ok_or_else(|| anyhow::anyhow!("no project matching '{}'", q)),
+        None => Ok(crate::utils::resolve_project_slug(&std::env::current_dir()?.to_string_lossy())),
+    }
+}
+
+/// Render `name` exactly as the hooks would for `slug`, with git state read from `cwd`.
+pub fn preview_output(base: &Path, slug: &str, cwd: &Path, name: &str) -> Result<String> {
+    let config = seslog_core::config::load_config(&base.join("config.toml")).unwrap_or_default();
+    let project_dir = base.join("projects").join(slug);
+    let input = ContextInput::load(slug, &project_dir, cwd, config.context_recent_sessions as usize);
+    let out = match name {
+        CLAUDE_MD_BLOCK => {
+            let status = StatusData::new(slug, input.sessions.first(), &input.roadmap);
+            templates::render_named(base, Some(slug), CLAUDE_MD_BLOCK, &status)?
+        }
+        ADDITIONAL_CONTEXT => {
+            let composer = ContextComposer::from_config(&config);
+            templates::render_additional_context(base, &composer, &input)?
+        }
+        _ => anyhow::bail!("unknown template '{}' (expected one of: {})", name, TEMPLATE_NAMES.join(", ")),
+    };
+    Ok(out + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_preview_uses_project_override() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("projects").join("api");
+        std::fs::create_dir_all(dir.join("sessions")).unwrap();
+        std::fs::write(dir.join("roadmap.md"), "## Phase 1\n- [x] Auth\n- [>] Billing\n").unwrap();
+        let session = serde_json::json!({
+            "schema_version": 1, "id": "ses_1", "project_id": "proj_api", "machine": "mac",
+            "started_at": "2026-01-10T10:00:00Z", "summary": "Added invoices",
+        });
+        std::fs::write(dir.join("sessions").join("ses_1.json"), session.to_string()).unwrap();
+
+        let out = preview_output(tmp.path(), "api", tmp.path(), CLAUDE_MD_BLOCK).unwrap();
+        assert!(out.contains("**Summary:** Added invoices"));
+        assert!(out.contains("- [>] Billing"));
+
+        templates::write_defaults(&dir.join("templates"), false).unwrap();
+        std::fs::write(dir.join("templates").join(CLAUDE_MD_BLOCK), "{{ slug }} at {{ progress }}%").unwrap();
+        assert_eq!(preview_output(tmp.path(), "api", tmp.path(), CLAUDE_MD_BLOCK).unwrap(), "api at 50%\n");
+
+        let ctx = preview_output(tmp.path(), "api", tmp.path(), ADDITIONAL_CONTEXT).unwrap();
+        assert!(ctx.contains("Last session: Added invoices"));
+        assert!(preview_output(tmp.path(), "api", tmp.path(), "other.md").is_err());
+    }
+}
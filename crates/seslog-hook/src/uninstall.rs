@@ -2,10 +2,26 @@ use anyhow::Result;
 
 pub fn run() -> Result<()> {
     eprintln!("[seslog] Uninstalling hooks...");
-    let settings_path = dirs::home_dir()
+    let claude_dir = dirs::home_dir()
         .ok_or_else(|| anyhow::anyhow!("HOME not found"))?
-        .join(".claude")
-        .join("settings.json");
+        .join(".claude");
+    let settings_path = claude_dir.join("settings.json");
+
+    let removed = seslog_core::triggers::remove_slash_commands(&claude_dir.join("commands"))?;
+    if removed > 0 {
+        eprintln!("[seslog] Removed {} slash command(s)", removed);
+    }
+
+    let claude_json = crate::install::claude_json_path()?;
+    if let Ok(content) = std::fs::read_to_string(&claude_json) {
+        let mut value: serde_json::Value = serde_json::from_str(&content)?;
+        let removed = value.get_mut("mcpServers").and_then(|s| s.as_object_mut())
+            .and_then(|s| s.remove(crate::install::MCP_SERVER_NAME));
+        if removed.is_some() {
+            seslog_core::storage::atomic_write(&claude_json, serde_json::to_string_pretty(&value)?.as_bytes())?;
+            eprintln!("[seslog] MCP server removed from {}", claude_json.display());
+        }
+    }
 
     if !settings_path.exists() {
         eprintln!("[seslog] No settings.json found, nothing to uninstall");
@@ -1,3 +1,86 @@
+/// Resolve the project directory slug for `cwd`, registering the project if new.
+///
+/// Rules in `projects.toml` are consulted first; otherwise the project is found by
+/// its stable identity (git remote, root commit, or canonical path). Falls back to
+/// the basename slug if the data directory is unavailable. With `privacy_mode = "off"`
+/// the project is registered under a hashed identity, name and path.
+///
+/// Results are cached per directory until the privacy mode or `projects.toml` changes.
+pub fn resolve_project_slug(cwd: &str) -> String {
+    let base = match seslog_core::storage::seslog_dir() {
+        Ok(b) => b,
+        Err(_) => return project_slug_from_cwd(cwd),
+    };
+    let privacy_mode = seslog_core::config::load_config(&base.join("config.toml"))
+        .map(|c| c.privacy_mode)
+        .unwrap_or_default();
+    let hash_key = if privacy_mode.hashes_identifiers() {
+        match seslog_core::privacy::HashKey::load_or_create(&base) {
+            Ok(key) => Some(key),
+            Err(e) => {
+                eprintln!("[seslog] WARN: loading the privacy hash key failed: {}", e);
+                return "project-unknown".into();
+            }
+        }
+    } else {
+        None
+    };
+
+    let rules_modified = std::fs::metadata(base.join("projects.toml"))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos());
+    let fingerprint = format!("{:?}:{}", privacy_mode, rules_modified);
+    let cache_key = match &hash_key {
+        Some(key) => key.hash(cwd),
+        None => cwd.to_string(),
+    };
+    if let Some(slug) = seslog_core::project_identity::cached_slug(&base, &cache_key, &fingerprint) {
+        return slug;
+    }
+    let slug = resolve_uncached(&base, cwd, hash_key.as_ref());
+    if let Err(e) = seslog_core::project_identity::cache_slug(&base, &cache_key, &fingerprint, &slug) {
+        eprintln!("[seslog] WARN: caching the project slug failed: {}", e);
+    }
+    slug
+}
+
+fn resolve_uncached(base: &std::path::Path, cwd: &str, hash_key: Option<&seslog_core::privacy::HashKey>) -> String {
+    let hostname = hostname::get()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "unknown".into());
+    // meta.toml is synced: in off mode it records this machine under its hash too
+    let hostname = match hash_key {
+        Some(key) => key.hash(&hostname),
+        None => hostname,
+    };
+
+    match seslog_core::project_map::load_map(&base.join("projects.toml")).and_then(|map| {
+        seslog_core::project_map::resolve_mapped(&base.join("projects"), &map, std::path::Path::new(cwd), &hostname, hash_key)
+    }) {
+        Ok(Some(slug)) => return slug,
+        Ok(None) => {}
+        Err(e) => eprintln!("[seslog] WARN: projects.toml rules failed: {}", e),
+    }
+
+    let projects_dir = base.join("projects");
+    let resolved = match hash_key {
+        Some(key) => seslog_core::project_identity::resolve_anonymous_project_dir(&projects_dir, std::path::Path::new(cwd), &hostname, key),
+        None => seslog_core::project_identity::resolve_project_dir(&projects_dir, std::path::Path::new(cwd), &hostname),
+    };
+    match resolved {
+        Ok(slug) => slug,
+        Err(e) => {
+            eprintln!("[seslog] WARN: resolve_project_dir failed: {}", e);
+            match hash_key {
+                Some(key) => format!("project-{}", &key.digest(cwd)[..8]),
+                None => project_slug_from_cwd(cwd),
+            }
+        }
+    }
+}
+
 /// Derive a project slug from the current working directory.
 /// Uses the last path component (directory name) as the slug.
 pub fn project_slug_from_cwd(cwd: &str) -> String {
@@ -25,6 +108,15 @@ pub fn read_project_id(slug: &str) -> String {
     meta.project.id
 }
 
+/// Sanitizer for the configured `[redaction]` rules. An invalid custom pattern is
+/// reported and the built-in rules are used, so a typo never blocks a hook.
+pub fn load_sanitizer(config: &seslog_core::config::AppConfig) -> seslog_core::sanitize::Sanitizer {
+    seslog_core::sanitize::Sanitizer::from_config(&config.redaction).unwrap_or_else(|e| {
+        eprintln!("[seslog] WARN: {}; using built-in redaction rules", e);
+        seslog_core::sanitize::Sanitizer::default()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;